@@ -2,28 +2,29 @@ extern crate tex_rs;
 use tex_rs::core::*;
 use tex_rs::latex_file::*;
 use tex_rs::writable::Writable;
+use tex_rs::Result;
 
-fn main() {
-    let mut f = new_latex_file("out.tex");
+fn main() -> Result<()> {
+    let mut f = new_latex_file("out.tex")?;
     f.title("Example of use of tex-rs");
     f.author("GuilloteauQ");
-    f.begin_document();
+    f.begin_document()?;
 
     let mut abstract_bloc = Core::bloc("abstract");
-    abstract_bloc.add(Core::text("This document is an example of use of Tex-rs"));
-    abstract_bloc.write_latex(&mut f);
+    abstract_bloc.add(Core::text("This document is an example of use of Tex-rs"))?;
+    abstract_bloc.write_latex(&mut f)?;
 
     let mut sec = Core::section("Examples");
     let mut enume = Core::bloc("itemize");
 
-    let countries = vec!["France", "UK", "Germany", "Italy"];
-    sec.add(Core::text("Here are some countries in Europe"));
+    let countries = ["France", "UK", "Germany", "Italy"];
+    sec.add(Core::text("Here are some countries in Europe"))?;
     for country in countries.iter() {
-        enume.add(Core::item(Core::text(*country)));
+        enume.add(Core::item(Core::text(*country)))?;
     }
 
-    sec.add(enume);
-    sec.write_latex(&mut f);
+    sec.add(enume)?;
+    sec.write_latex(&mut f)?;
 
-    f.write_footer();
+    f.write_footer()
 }
@@ -0,0 +1,277 @@
+/// File defining the validation of the images of a document: the files are resolved
+/// like graphicx does (directory of the document, then \graphicspath), and can be
+/// copied or linked into a bundle directory, so the generated project is self-contained
+///
+use core::*;
+use error::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The extensions of the images supported by graphicx, tried in this order
+/// when the name of the image has no extension
+pub const GRAPHICS_EXTENSIONS: [&str; 5] = ["pdf", "png", "jpg", "jpeg", "eps"];
+
+/// How the images are put in a bundle
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BundleMode {
+    /// The files are copied
+    Copy,
+    /// The files are symbolic links to the original ones (copied if not supported)
+    Symlink,
+}
+
+/// An image found on the disk
+#[derive(Clone, Debug, PartialEq)]
+pub struct Asset {
+    /// The name of the image, as written in the document
+    pub filename: String,
+    /// The file found
+    pub path: PathBuf,
+    /// The file, relative to the directory of the document
+    /// (absolute if the name of the image is)
+    pub relative: PathBuf,
+}
+
+/// The resolution of the images: the directory of the document and the \graphicspath
+#[derive(Clone, Debug)]
+pub struct Assets {
+    root: PathBuf,
+    graphics_path: Vec<String>,
+}
+
+impl Assets {
+    /// Returns a new resolver, for a document written in the directory
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Assets {
+            root: root.as_ref().to_path_buf(),
+            graphics_path: Vec::new(),
+        }
+    }
+
+    /// Changes the directories searched after the one of the document (\graphicspath)
+    pub fn set_graphics_path(&mut self, graphics_path: Vec<String>) {
+        self.graphics_path = graphics_path;
+    }
+
+    /// Returns the file of an image
+    pub fn resolve<T: AsRef<str>>(&self, filename: T) -> Result<Asset> {
+        let filename = filename.as_ref();
+        let candidates = match Path::new(filename).extension() {
+            Some(extension) => {
+                let extension = extension.to_string_lossy().to_lowercase();
+                if !GRAPHICS_EXTENSIONS.contains(&extension.as_str()) {
+                    return Err(Error::UnsupportedAsset(filename.to_string()));
+                }
+                vec![filename.to_string()]
+            }
+            None => GRAPHICS_EXTENSIONS
+                .iter()
+                .map(|e| format!("{}.{}", filename, e))
+                .collect(),
+        };
+        let dirs = Some("")
+            .into_iter()
+            .chain(self.graphics_path.iter().map(|d| d.as_str()));
+        for dir in dirs {
+            for candidate in candidates.iter() {
+                let relative = Path::new(dir).join(candidate);
+                let path = self.root.join(&relative);
+                if path.is_file() {
+                    return Ok(Asset {
+                        filename: filename.to_string(),
+                        path,
+                        relative,
+                    });
+                }
+            }
+        }
+        Err(Error::MissingAsset(filename.to_string()))
+    }
+
+    /// Returns the files of the images of the elements
+    /// The first missing file or unsupported extension is reported
+    pub fn check(&self, elements: &[Core]) -> Result<Vec<Asset>> {
+        graphic_files(elements)
+            .iter()
+            .map(|f| self.resolve(f))
+            .collect()
+    }
+
+    /// Copies or links the images of the elements into the bundle directory,
+    /// at the same place relative to the document (the absolute names are left out)
+    pub fn bundle<P: AsRef<Path>>(
+        &self,
+        elements: &[Core],
+        bundle: P,
+        mode: BundleMode,
+    ) -> Result<Vec<Asset>> {
+        let assets = self.check(elements)?;
+        // The images would be replaced by themselves: the originals would be removed
+        let bundle = bundle.as_ref();
+        if let (Ok(dir), Ok(root)) = (fs::canonicalize(bundle), fs::canonicalize(&self.root)) {
+            if dir == root {
+                return Err(Error::InvalidBundle(bundle.display().to_string()));
+            }
+        }
+        for asset in assets.iter().filter(|a| a.relative.is_relative()) {
+            let target = bundle.join(&asset.relative);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            if same_file(&asset.path, &target)? {
+                continue;
+            }
+            if fs::symlink_metadata(&target).is_ok() {
+                fs::remove_file(&target)?;
+            }
+            match mode {
+                BundleMode::Copy => {
+                    fs::copy(&asset.path, &target)?;
+                }
+                BundleMode::Symlink => link(&asset.path, &target)?,
+            }
+        }
+        Ok(assets)
+    }
+}
+
+/// Returns true if the target is the source file itself (written with another path),
+/// a link to the source is not the source
+fn same_file(source: &Path, target: &Path) -> Result<bool> {
+    let name = match target.file_name() {
+        Some(name) => name,
+        None => return Ok(false),
+    };
+    let parent = match target.parent() {
+        Some(parent) if parent != Path::new("") => fs::canonicalize(parent)?,
+        _ => fs::canonicalize(".")?,
+    };
+    Ok(parent.join(name) == fs::canonicalize(source)?)
+}
+
+#[cfg(unix)]
+fn link(source: &Path, target: &Path) -> Result<()> {
+    std::os::unix::fs::symlink(fs::canonicalize(source)?, target)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn link(source: &Path, target: &Path) -> Result<()> {
+    fs::copy(source, target)?;
+    Ok(())
+}
+
+/// Returns the names of the images of the elements, without duplicates
+pub fn graphic_files(elements: &[Core]) -> Vec<String> {
+    let mut files: Vec<String> = Vec::new();
+    for element in elements.iter() {
+        element.walk(&mut |e: &Core| {
+            let names: Vec<&str> = match *e {
+                Core::Graph(ref g) => g.get_filename().into_iter().collect(),
+                Core::Figure(ref f) => f
+                    .get_subfigures()
+                    .iter()
+                    .filter_map(|g| g.get_filename())
+                    .collect(),
+                _ => Vec::new(),
+            };
+            for name in names {
+                if !files.iter().any(|f| f == name) {
+                    files.push(name.to_string());
+                }
+            }
+        });
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests_assets {
+    use super::*;
+    use graphics::Graphic;
+
+    /// Returns a new empty directory, with the images
+    fn project(name: &str, images: &[&str]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tex_rs_assets_{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        for image in images.iter() {
+            let path = dir.join(image);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "image").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn resolve() {
+        let dir = project("resolve", &["logo.png", "figures/plot.pdf"]);
+        let mut assets = Assets::new(&dir);
+        assert_eq!(
+            assets.resolve("logo.png").unwrap().relative,
+            Path::new("logo.png")
+        );
+        match assets.resolve("plot") {
+            Err(Error::MissingAsset(name)) => assert_eq!(name, "plot"),
+            _ => panic!("The image is not in the directory of the document"),
+        }
+        assets.set_graphics_path(vec!["figures/".to_string()]);
+        let plot = assets.resolve("plot").unwrap();
+        assert_eq!(plot.relative, Path::new("figures/plot.pdf"));
+        assert_eq!(plot.path, dir.join("figures/plot.pdf"));
+        match assets.resolve("data.svg") {
+            Err(Error::UnsupportedAsset(name)) => assert_eq!(name, "data.svg"),
+            _ => panic!("SVG images are not supported by graphicx"),
+        }
+    }
+
+    #[test]
+    fn bundle() {
+        let dir = project("source", &["logo.jpg", "figures/a.png"]);
+        let bundle = project("bundle", &[]);
+        let mut sec = Core::section("Images");
+        sec.add(Core::graphic("logo.jpg", "Logo")).unwrap();
+        sec.add(Core::figure(vec![Graphic::inline("figures/a")], "A"))
+            .unwrap();
+        sec.add(Core::graphic("logo.jpg", "Again")).unwrap();
+        assert_eq!(graphic_files(&[sec.clone()]), vec!["logo.jpg", "figures/a"]);
+        let assets = Assets::new(&dir);
+        let found = assets
+            .bundle(&[sec.clone()], &bundle, BundleMode::Copy)
+            .unwrap();
+        assert_eq!(found.len(), 2);
+        assert!(bundle.join("figures/a.png").is_file());
+        assets.bundle(&[sec], &bundle, BundleMode::Symlink).unwrap();
+        assert_eq!(
+            fs::read_to_string(bundle.join("logo.jpg")).unwrap(),
+            "image"
+        );
+    }
+
+    #[test]
+    fn bundle_into_the_sources() {
+        let dir = project("same", &["img.png"]);
+        let elements = [Core::graphic("img.png", "x")];
+        let assets = Assets::new(&dir);
+        // The directory of the document, spelled ./same
+        let spelled = dir.parent().unwrap().join("./tex_rs_assets_same");
+        for mode in [BundleMode::Copy, BundleMode::Symlink].iter() {
+            match assets.bundle(&elements, &spelled, *mode) {
+                Err(Error::InvalidBundle(_)) => (),
+                _ => panic!("The images can not be bundled into their own directory"),
+            }
+            assert_eq!(fs::read_to_string(dir.join("img.png")).unwrap(), "image");
+        }
+        // The bundle holds the image at the same place: it is left as it is
+        let dir = project("shared", &["doc/main.tex", "shared/b.png"]);
+        let mut assets = Assets::new(dir.join("doc"));
+        assets.set_graphics_path(vec!["../shared/".to_string()]);
+        let elements = [Core::graphic("b", "y")];
+        assets
+            .bundle(&elements, dir.join("bundle"), BundleMode::Copy)
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.join("shared/b.png")).unwrap(),
+            "image"
+        );
+    }
+}
@@ -0,0 +1,798 @@
+/// File defining the bibliography of a document:
+/// the entries (written in a .bib file), the citations (\cite, \parencite, ...)
+/// and the commands printing the references (natbib, biblatex or plain BibTeX)
+///
+use bibtex::parse_bibtex;
+use core::*;
+use error::*;
+use packages::Packages;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::slice;
+use writable::*;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EntryType {
+    Article,
+    Book,
+    InProceedings,
+    InCollection,
+    PhdThesis,
+    MastersThesis,
+    TechReport,
+    Misc,
+    Online,
+    /// Any other type of entry (@manual, @unpublished, ...)
+    Other(String),
+}
+
+impl EntryType {
+    /// Returns the type of entry corresponding to the name (case insensitive)
+    pub fn from_name<T: AsRef<str>>(name: T) -> Self {
+        match name.as_ref().to_lowercase().as_str() {
+            "article" => EntryType::Article,
+            "book" => EntryType::Book,
+            "inproceedings" => EntryType::InProceedings,
+            "incollection" => EntryType::InCollection,
+            "phdthesis" => EntryType::PhdThesis,
+            "mastersthesis" => EntryType::MastersThesis,
+            "techreport" => EntryType::TechReport,
+            "misc" => EntryType::Misc,
+            "online" => EntryType::Online,
+            other => EntryType::Other(other.to_string()),
+        }
+    }
+
+    /// Returns the name of the type, as written after the @
+    pub fn name(&self) -> &str {
+        match *self {
+            EntryType::Article => "article",
+            EntryType::Book => "book",
+            EntryType::InProceedings => "inproceedings",
+            EntryType::InCollection => "incollection",
+            EntryType::PhdThesis => "phdthesis",
+            EntryType::MastersThesis => "mastersthesis",
+            EntryType::TechReport => "techreport",
+            EntryType::Misc => "misc",
+            EntryType::Online => "online",
+            EntryType::Other(ref name) => name,
+        }
+    }
+
+    /// Returns the fields BibTeX expects for this type of entry
+    pub fn required_fields(&self) -> &[&str] {
+        match *self {
+            EntryType::Article => &["author", "title", "journal", "year"],
+            EntryType::Book => &["author", "title", "publisher", "year"],
+            EntryType::InProceedings | EntryType::InCollection => {
+                &["author", "title", "booktitle", "year"]
+            }
+            EntryType::PhdThesis | EntryType::MastersThesis => {
+                &["author", "title", "school", "year"]
+            }
+            EntryType::TechReport => &["author", "title", "institution", "year"],
+            EntryType::Online => &["title", "url"],
+            EntryType::Misc | EntryType::Other(_) => &[],
+        }
+    }
+}
+
+/// An entry of the bibliography (@article{key, ...})
+#[derive(Clone, Debug, PartialEq)]
+pub struct BibEntry {
+    /// The type of the entry
+    entry_type: EntryType,
+    /// The key used to cite the entry
+    key: String,
+    /// The fields of the entry (name in lower case, value), in order
+    fields: Vec<(String, String)>,
+    /// The original text of a parsed entry, written as is while the entry is not modified
+    raw: Option<String>,
+}
+
+impl BibEntry {
+    /// Returns a new entry without fields
+    pub fn new<T: AsRef<str>>(entry_type: EntryType, key: T) -> Self {
+        BibEntry {
+            entry_type,
+            key: key.as_ref().to_string(),
+            fields: Vec::new(),
+            raw: None,
+        }
+    }
+
+    /// Returns a new @article
+    pub fn article<T: AsRef<str>>(key: T) -> Self {
+        BibEntry::new(EntryType::Article, key)
+    }
+
+    /// Returns a new @book
+    pub fn book<T: AsRef<str>>(key: T) -> Self {
+        BibEntry::new(EntryType::Book, key)
+    }
+
+    /// Returns a new @inproceedings
+    pub fn inproceedings<T: AsRef<str>>(key: T) -> Self {
+        BibEntry::new(EntryType::InProceedings, key)
+    }
+
+    /// Returns a new @misc
+    pub fn misc<T: AsRef<str>>(key: T) -> Self {
+        BibEntry::new(EntryType::Misc, key)
+    }
+
+    /// Returns the type of the entry
+    pub fn get_type(&self) -> &EntryType {
+        &self.entry_type
+    }
+
+    /// Returns the key of the entry
+    pub fn get_key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns the fields of the entry
+    pub fn get_fields(&self) -> &[(String, String)] {
+        &self.fields
+    }
+
+    /// Returns the value of a field (the name is case insensitive)
+    pub fn get_field<T: AsRef<str>>(&self, name: T) -> Option<&str> {
+        let name = name.as_ref().to_lowercase();
+        self.fields
+            .iter()
+            .find(|f| f.0 == name)
+            .map(|f| f.1.as_str())
+    }
+
+    /// Sets the value of a field, replacing the previous one
+    /// The value is written verbatim between braces (it is LaTeX code)
+    pub fn set_field<T1: AsRef<str>, T2: AsRef<str>>(&mut self, name: T1, value: T2) {
+        let name = name.as_ref().to_lowercase();
+        let value = value.as_ref().to_string();
+        self.raw = None;
+        match self.fields.iter_mut().find(|f| f.0 == name) {
+            Some(field) => field.1 = value,
+            None => self.fields.push((name, value)),
+        }
+    }
+
+    /// Removes a field
+    pub fn remove_field<T: AsRef<str>>(&mut self, name: T) {
+        let name = name.as_ref().to_lowercase();
+        self.raw = None;
+        self.fields.retain(|f| f.0 != name);
+    }
+
+    /// Sets the original text of the entry, written instead of the fields
+    /// until the entry is modified
+    pub fn set_raw<T: AsRef<str>>(&mut self, raw: T) {
+        self.raw = Some(raw.as_ref().to_string());
+    }
+
+    /// Returns the original text of the entry, if it was parsed and not modified
+    pub fn get_raw(&self) -> Option<&str> {
+        self.raw.as_deref()
+    }
+
+    /// Sets the authors (separated by "and")
+    pub fn set_author<T: AsRef<str>>(&mut self, author: T) {
+        self.set_field("author", author);
+    }
+
+    /// Sets the title
+    pub fn set_title<T: AsRef<str>>(&mut self, title: T) {
+        self.set_field("title", title);
+    }
+
+    /// Sets the year
+    pub fn set_year(&mut self, year: u32) {
+        self.set_field("year", year.to_string());
+    }
+
+    /// Sets the journal of an article
+    pub fn set_journal<T: AsRef<str>>(&mut self, journal: T) {
+        self.set_field("journal", journal);
+    }
+
+    /// Sets the title of the proceedings or of the collection
+    pub fn set_booktitle<T: AsRef<str>>(&mut self, booktitle: T) {
+        self.set_field("booktitle", booktitle);
+    }
+
+    /// Sets the publisher
+    pub fn set_publisher<T: AsRef<str>>(&mut self, publisher: T) {
+        self.set_field("publisher", publisher);
+    }
+
+    /// Returns the required fields of the type which are not set
+    pub fn missing_fields(&self) -> Vec<&str> {
+        self.entry_type
+            .required_fields()
+            .iter()
+            .filter(|name| self.get_field(name).is_none())
+            .cloned()
+            .collect()
+    }
+
+    /// Returns true if the key can be used in a .bib file
+    pub fn has_valid_key(&self) -> bool {
+        !self.key.is_empty()
+            && !self
+                .key
+                .chars()
+                .any(|c| "\\{}#%~,\"()=".contains(c) || c.is_whitespace())
+    }
+
+    /// Returns the handle used to cite the entry
+    pub fn handle(&self) -> CiteKey {
+        CiteKey::new(&self.key)
+    }
+}
+
+impl Writable for BibEntry {
+    /// Writes the entry in the BibTeX format
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        if let Some(ref raw) = self.raw {
+            writeln!(buf, "{}", raw)?;
+            return Ok(());
+        }
+        writeln!(buf, "@{}{{{},", self.entry_type.name(), self.key)?;
+        for (name, value) in self.fields.iter() {
+            writeln!(buf, "  {} = {{{}}},", name, value)?;
+        }
+        writeln!(buf, "}}")?;
+        Ok(())
+    }
+}
+
+/// The handle of an entry of the bibliography, used to cite it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CiteKey {
+    key: String,
+}
+
+impl CiteKey {
+    /// Returns a new handle to the entry with this key
+    pub fn new<T: AsRef<str>>(key: T) -> Self {
+        CiteKey {
+            key: key.as_ref().to_string(),
+        }
+    }
+
+    /// Returns the key of the cited entry
+    pub fn get_key(&self) -> &str {
+        &self.key
+    }
+}
+
+/// The package used to print the bibliography
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BibBackend {
+    /// Plain BibTeX: \cite and \bibliography
+    BibTeX,
+    /// natbib: \citet, \citep and \bibliography
+    Natbib,
+    /// biblatex (with biber): \textcite, \parencite and \printbibliography
+    Biblatex,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CiteStyle {
+    /// [1]
+    Cite,
+    /// (Knuth, 1984)
+    ParenCite,
+    /// Knuth (1984)
+    TextCite,
+}
+
+impl CiteStyle {
+    /// Returns the LaTeX command of the style with the backend
+    /// (plain BibTeX only has \cite)
+    pub fn command(&self, backend: BibBackend) -> &str {
+        match (backend, *self) {
+            (_, CiteStyle::Cite) | (BibBackend::BibTeX, _) => "\\cite",
+            (BibBackend::Natbib, CiteStyle::ParenCite) => "\\citep",
+            (BibBackend::Natbib, CiteStyle::TextCite) => "\\citet",
+            (BibBackend::Biblatex, CiteStyle::ParenCite) => "\\parencite",
+            (BibBackend::Biblatex, CiteStyle::TextCite) => "\\textcite",
+        }
+    }
+}
+
+/// A citation of one or several entries (\cite{a,b})
+#[derive(Clone, Debug, PartialEq)]
+pub struct Citation {
+    /// The cited entries
+    keys: Vec<CiteKey>,
+    /// The command used to cite them
+    style: CiteStyle,
+    /// The backend the command is written for
+    backend: BibBackend,
+}
+
+impl Citation {
+    /// Returns a new Citation
+    pub fn new(keys: Vec<CiteKey>, style: CiteStyle, backend: BibBackend) -> Self {
+        Citation {
+            keys,
+            style,
+            backend,
+        }
+    }
+
+    /// Returns the cited entries
+    pub fn get_keys(&self) -> &[CiteKey] {
+        &self.keys
+    }
+
+    /// Returns the style of the citation
+    pub fn get_style(&self) -> CiteStyle {
+        self.style
+    }
+
+    /// Returns the backend of the citation
+    pub fn get_backend(&self) -> BibBackend {
+        self.backend
+    }
+}
+
+impl Writable for Citation {
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        if self.keys.is_empty() {
+            return Err(Error::InvalidStructure(
+                "A citation needs at least one key".to_string(),
+            ));
+        }
+        let keys: Vec<&str> = self.keys.iter().map(|k| k.get_key()).collect();
+        write!(
+            buf,
+            "{}{{{}}}",
+            self.style.command(self.backend),
+            keys.join(",")
+        )?;
+        Ok(())
+    }
+}
+
+/// The entries of a document and the way they are printed
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bibliography {
+    /// The package used to print the bibliography
+    backend: BibBackend,
+    /// The style of the bibliography (plain, plainnat, authoryear, ...)
+    style: Option<String>,
+    /// The name of the .bib file, without the extension
+    filename: String,
+    /// If false, an entry which is never cited is an error (true by default, for the shared .bib files)
+    allow_unused: bool,
+    /// The @string and @preamble blocks of the parsed files, written before the entries
+    blocks: Vec<String>,
+    /// The entries
+    entries: Vec<BibEntry>,
+}
+
+impl Bibliography {
+    /// Returns a new empty Bibliography, written in references.bib
+    pub fn new(backend: BibBackend) -> Self {
+        Bibliography {
+            backend,
+            style: None,
+            filename: "references".to_string(),
+            allow_unused: true,
+            blocks: Vec::new(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Returns the backend of the bibliography
+    pub fn get_backend(&self) -> BibBackend {
+        self.backend
+    }
+
+    /// Changes the style of the bibliography (\bibliographystyle, or the style option of biblatex)
+    pub fn set_style<T: AsRef<str>>(&mut self, style: T) {
+        self.style = Some(style.as_ref().to_string());
+    }
+
+    /// Returns the style of the bibliography (plain, plainnat or numeric if not set)
+    pub fn get_style(&self) -> &str {
+        match self.style {
+            Some(ref style) => style,
+            None => match self.backend {
+                BibBackend::BibTeX => "plain",
+                BibBackend::Natbib => "plainnat",
+                BibBackend::Biblatex => "numeric",
+            },
+        }
+    }
+
+    /// Changes the name of the .bib file (without the extension)
+    pub fn set_filename<T: AsRef<str>>(&mut self, filename: T) {
+        self.filename = filename.as_ref().trim_end_matches(".bib").to_string();
+    }
+
+    /// Returns the name of the .bib file (without the extension)
+    pub fn get_filename(&self) -> &str {
+        &self.filename
+    }
+
+    /// Allows the entries which are never cited (e.g. a shared .bib file), the default
+    /// If false, an entry which is never cited is reported by check_citations
+    pub fn set_allow_unused(&mut self, allow: bool) {
+        self.allow_unused = allow;
+    }
+
+    /// Checks that the entry can be added: a valid key, which is not in the bibliography
+    fn check_entry(&self, entry: &BibEntry) -> Result<()> {
+        if !entry.has_valid_key() {
+            return Err(Error::InvalidStructure(format!(
+                "The key '{}' is not a valid BibTeX key",
+                entry.get_key()
+            )));
+        }
+        if self.get(entry.get_key()).is_some() {
+            return Err(Error::DuplicateEntry(entry.get_key().to_string()));
+        }
+        Ok(())
+    }
+
+    /// Adds an entry, and returns the handle to cite it
+    pub fn add(&mut self, entry: BibEntry) -> Result<CiteKey> {
+        self.check_entry(&entry)?;
+        let handle = entry.handle();
+        self.entries.push(entry);
+        Ok(handle)
+    }
+
+    /// Adds the entries (and the @string definitions) of the content of a .bib file
+    /// Nothing is added if one of the entries can not be
+    pub fn parse_bib<T: AsRef<str>>(&mut self, input: T) -> Result<()> {
+        let parsed = parse_bibtex(input)?;
+        for (i, entry) in parsed.entries.iter().enumerate() {
+            self.check_entry(entry)?;
+            if parsed.entries[..i]
+                .iter()
+                .any(|e| e.get_key() == entry.get_key())
+            {
+                return Err(Error::DuplicateEntry(entry.get_key().to_string()));
+            }
+        }
+        self.blocks.extend(parsed.blocks);
+        self.entries.extend(parsed.entries);
+        Ok(())
+    }
+
+    /// Adds the entries (and the @string definitions) of a .bib file
+    pub fn load_bib_file<T: AsRef<str>>(&mut self, filename: T) -> Result<()> {
+        let mut content = String::new();
+        File::open(filename.as_ref())
+            .and_then(|mut f| f.read_to_string(&mut content))
+            .map_err(|e| Error::MissingFile(filename.as_ref().to_string(), e))?;
+        self.parse_bib(content)
+    }
+
+    /// Returns the entry with this key
+    pub fn get<T: AsRef<str>>(&self, key: T) -> Option<&BibEntry> {
+        self.entries.iter().find(|e| e.get_key() == key.as_ref())
+    }
+
+    /// Returns the entries
+    pub fn entries(&self) -> &[BibEntry] {
+        &self.entries
+    }
+
+    /// Returns the number of entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if there is no entry
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns a citation of the entry (\cite)
+    pub fn cite(&self, key: &CiteKey) -> Core {
+        self.cite_all(slice::from_ref(key), CiteStyle::Cite)
+    }
+
+    /// Returns a parenthetical citation of the entry (\citep or \parencite)
+    pub fn parencite(&self, key: &CiteKey) -> Core {
+        self.cite_all(slice::from_ref(key), CiteStyle::ParenCite)
+    }
+
+    /// Returns a textual citation of the entry (\citet or \textcite)
+    pub fn textcite(&self, key: &CiteKey) -> Core {
+        self.cite_all(slice::from_ref(key), CiteStyle::TextCite)
+    }
+
+    /// Returns a citation of several entries, with the command of the backend
+    pub fn cite_all(&self, keys: &[CiteKey], style: CiteStyle) -> Core {
+        Core::Cite(Citation::new(keys.to_vec(), style, self.backend))
+    }
+
+    /// Returns the line to put in the preamble (\addbibresource for biblatex)
+    pub fn preamble_command(&self) -> Option<String> {
+        match self.backend {
+            BibBackend::Biblatex => Some(format!("\\addbibresource{{{}.bib}}", self.filename)),
+            _ => None,
+        }
+    }
+
+    /// Checks that every citation points to an entry,
+    /// and that every entry is cited (unless the unused entries are allowed)
+    pub fn check_citations(&self, elements: &[Core]) -> Result<()> {
+        let citations = collect_citations(elements);
+        for citation in citations.iter() {
+            if citation.get_backend() != self.backend {
+                return Err(Error::InvalidStructure(
+                    "A citation was created for another backend".to_string(),
+                ));
+            }
+            for key in citation.get_keys().iter() {
+                if self.get(key.get_key()).is_none() {
+                    return Err(Error::MissingCitation(key.get_key().to_string()));
+                }
+            }
+        }
+        if !self.allow_unused {
+            for entry in self.entries.iter() {
+                let cited = citations
+                    .iter()
+                    .any(|c| c.get_keys().iter().any(|k| k.get_key() == entry.get_key()));
+                if !cited {
+                    return Err(Error::UnusedEntry(entry.get_key().to_string()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the entries in the BibTeX format
+    pub fn write_bib<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        for block in self.blocks.iter() {
+            writeln!(buf, "{}", block)?;
+        }
+        for (i, entry) in self.entries.iter().enumerate() {
+            if i > 0 || !self.blocks.is_empty() {
+                writeln!(buf)?;
+            }
+            entry.write_to_buffer(buf)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the entries in a new .bib file
+    pub fn write_bib_file<T: AsRef<str>>(&self, filename: T) -> Result<()> {
+        let mut f = File::create(filename.as_ref())?;
+        self.write_bib(&mut f)
+    }
+}
+
+impl Writable for Bibliography {
+    /// Writes the commands printing the bibliography (at the end of the document)
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        match self.backend {
+            BibBackend::BibTeX | BibBackend::Natbib => {
+                writeln!(buf, "\\bibliographystyle{{{}}}", self.get_style())?;
+                writeln!(buf, "\\bibliography{{{}}}", self.filename)?;
+            }
+            BibBackend::Biblatex => writeln!(buf, "\\printbibliography")?,
+        }
+        Ok(())
+    }
+
+    fn add_packages(&self, packages: &mut Packages) {
+        match self.backend {
+            BibBackend::BibTeX => (),
+            BibBackend::Natbib => packages.add_package("natbib"),
+            BibBackend::Biblatex => {
+                let style = format!("style={}", self.get_style());
+                packages.add_package_with_options("biblatex", vec!["backend=biber", &style]);
+            }
+        }
+    }
+}
+
+/// Returns the citations of the elements, in order
+fn collect_citations(elements: &[Core]) -> Vec<Citation> {
+    let mut citations = Vec::new();
+    for element in elements.iter() {
+        element.walk(&mut |e: &Core| {
+            if let Core::Cite(ref citation) = *e {
+                citations.push(citation.clone());
+            }
+        });
+    }
+    citations
+}
+
+/// Checks that the elements have no citation (for a document without bibliography)
+pub fn check_no_citation(elements: &[Core]) -> Result<()> {
+    match collect_citations(elements).first() {
+        Some(citation) => match citation.get_keys().first() {
+            Some(key) => Err(Error::MissingCitation(key.get_key().to_string())),
+            None => Err(Error::InvalidStructure(
+                "A citation needs at least one key".to_string(),
+            )),
+        },
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests_bibliography {
+    use super::*;
+
+    fn knuth() -> BibEntry {
+        let mut entry = BibEntry::book("knuth84");
+        entry.set_author("Donald E. Knuth");
+        entry.set_title("The \\TeX book");
+        entry.set_publisher("Addison-Wesley");
+        entry.set_year(1984);
+        entry
+    }
+
+    #[test]
+    fn write_entry() {
+        assert_eq!(
+            knuth().to_latex().unwrap(),
+            "@book{knuth84,\n  author = {Donald E. Knuth},\n  title = {The \\TeX book},\n  publisher = {Addison-Wesley},\n  year = {1984},\n}\n"
+        );
+    }
+
+    #[test]
+    fn fields() {
+        let mut entry = BibEntry::article("a");
+        entry.set_field("Title", "First");
+        entry.set_field("title", "Second");
+        assert_eq!(entry.get_field("TITLE"), Some("Second"));
+        assert_eq!(entry.get_fields().len(), 1);
+        assert_eq!(entry.missing_fields(), vec!["author", "journal", "year"]);
+        entry.remove_field("title");
+        assert!(entry.get_fields().is_empty());
+        assert!(knuth().missing_fields().is_empty());
+        assert_eq!(
+            EntryType::from_name("InProceedings"),
+            EntryType::InProceedings
+        );
+        assert_eq!(EntryType::from_name("manual").name(), "manual");
+    }
+
+    #[test]
+    fn add_entries() {
+        let mut bib = Bibliography::new(BibBackend::BibTeX);
+        let key = bib.add(knuth()).unwrap();
+        assert_eq!(key.get_key(), "knuth84");
+        match bib.add(knuth()) {
+            Err(Error::DuplicateEntry(key)) => assert_eq!(key, "knuth84"),
+            _ => panic!("An entry can not be added twice"),
+        }
+        match bib.add(BibEntry::misc("with space")) {
+            Err(Error::InvalidStructure(_)) => (),
+            _ => panic!("A key can not have spaces"),
+        }
+        assert_eq!(bib.len(), 1);
+    }
+
+    #[test]
+    fn citations() {
+        let mut natbib = Bibliography::new(BibBackend::Natbib);
+        let key = natbib.add(knuth()).unwrap();
+        assert_eq!(natbib.cite(&key).to_latex().unwrap(), "\\cite{knuth84}");
+        assert_eq!(
+            natbib.parencite(&key).to_latex().unwrap(),
+            "\\citep{knuth84}"
+        );
+        assert_eq!(
+            natbib.textcite(&key).to_latex().unwrap(),
+            "\\citet{knuth84}"
+        );
+        let mut biblatex = Bibliography::new(BibBackend::Biblatex);
+        let other = biblatex.add(BibEntry::misc("other")).unwrap();
+        assert_eq!(
+            biblatex
+                .cite_all(&[key.clone(), other], CiteStyle::ParenCite)
+                .to_latex()
+                .unwrap(),
+            "\\parencite{knuth84,other}"
+        );
+        let bibtex = Bibliography::new(BibBackend::BibTeX);
+        assert_eq!(bibtex.textcite(&key).to_latex().unwrap(), "\\cite{knuth84}");
+    }
+
+    #[test]
+    fn print_bibliography() {
+        let mut bib = Bibliography::new(BibBackend::Natbib);
+        bib.set_filename("refs.bib");
+        assert_eq!(
+            bib.to_latex().unwrap(),
+            "\\bibliographystyle{plainnat}\n\\bibliography{refs}\n"
+        );
+        assert_eq!(bib.preamble_command(), None);
+        let mut bib = Bibliography::new(BibBackend::Biblatex);
+        bib.set_style("authoryear");
+        assert_eq!(bib.to_latex().unwrap(), "\\printbibliography\n");
+        assert_eq!(
+            bib.preamble_command(),
+            Some("\\addbibresource{references.bib}".to_string())
+        );
+        let mut packages = Packages::new();
+        bib.add_packages(&mut packages);
+        assert_eq!(
+            packages.to_latex().unwrap(),
+            "\\usepackage[backend=biber,style=authoryear]{biblatex}\n"
+        );
+    }
+
+    #[test]
+    fn missing_and_unused() {
+        let mut bib = Bibliography::new(BibBackend::BibTeX);
+        let key = bib.add(knuth()).unwrap();
+        bib.add(BibEntry::misc("unused")).unwrap();
+        let body = vec![bib.cite(&key)];
+        assert!(bib.check_citations(&body).is_ok());
+        bib.set_allow_unused(false);
+        match bib.check_citations(&body) {
+            Err(Error::UnusedEntry(key)) => assert_eq!(key, "unused"),
+            _ => panic!("The unused entry should be reported"),
+        }
+        let body = vec![bib.cite(&CiteKey::new("lamport94"))];
+        match bib.check_citations(&body) {
+            Err(Error::MissingCitation(key)) => assert_eq!(key, "lamport94"),
+            _ => panic!("The missing entry should be reported"),
+        }
+        assert!(check_no_citation(&body).is_err());
+    }
+
+    #[test]
+    fn parse_and_cite() {
+        let mut bib = Bibliography::new(BibBackend::Natbib);
+        bib.parse_bib(
+            "@string{tug = {TUGboat}}\n\n@article{a, journal = tug}\n@misc{b,\n  note = {B}\n}",
+        )
+        .unwrap();
+        assert_eq!(bib.get("a").unwrap().get_field("journal"), Some("TUGboat"));
+        let body = vec![bib.cite(&CiteKey::new("a")), bib.cite(&CiteKey::new("c"))];
+        match bib.check_citations(&body) {
+            Err(Error::MissingCitation(key)) => assert_eq!(key, "c"),
+            _ => panic!("The key c is not in the file"),
+        }
+        let mut out = Vec::new();
+        bib.write_bib(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out.clone()).unwrap(),
+            "@string{tug = {TUGboat}}\n\n@article{a, journal = tug}\n\n@misc{b,\n  note = {B}\n}\n"
+        );
+        match bib.parse_bib("@string{x = {X}}\n@misc{O'Neil2000}\n@misc{b}") {
+            Err(Error::DuplicateEntry(key)) => assert_eq!(key, "b"),
+            _ => panic!("The entry b is already in the bibliography"),
+        }
+        assert_eq!(bib.len(), 2);
+        assert!(bib.get("O'Neil2000").is_none());
+        let mut unchanged = Vec::new();
+        bib.write_bib(&mut unchanged).unwrap();
+        assert_eq!(unchanged, out);
+        bib.parse_bib("@misc{O'Neil2000}").unwrap();
+        assert!(bib.get("O'Neil2000").is_some());
+        match bib.load_bib_file("this_file_does_not_exist.bib") {
+            Err(Error::MissingFile(..)) => (),
+            _ => panic!("A missing file should be reported"),
+        }
+    }
+
+    #[test]
+    fn write_bib() {
+        let mut bib = Bibliography::new(BibBackend::BibTeX);
+        bib.add(BibEntry::misc("a")).unwrap();
+        bib.add(BibEntry::misc("b")).unwrap();
+        let mut out = Vec::new();
+        bib.write_bib(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "@misc{a,\n}\n\n@misc{b,\n}\n"
+        );
+    }
+}
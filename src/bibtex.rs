@@ -0,0 +1,405 @@
+/// File defining the parser of the .bib files
+/// (@string macros, # concatenation, braced and quoted values, @preamble, @comment)
+///
+use bibliography::*;
+use error::*;
+
+/// The content of a .bib file
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ParsedBib {
+    /// The @string and @preamble blocks, verbatim
+    pub blocks: Vec<String>,
+    /// The entries, with their original text
+    pub entries: Vec<BibEntry>,
+}
+
+/// The months, predefined by BibTeX
+const MONTHS: [(&str, &str); 12] = [
+    ("jan", "January"),
+    ("feb", "February"),
+    ("mar", "March"),
+    ("apr", "April"),
+    ("may", "May"),
+    ("jun", "June"),
+    ("jul", "July"),
+    ("aug", "August"),
+    ("sep", "September"),
+    ("oct", "October"),
+    ("nov", "November"),
+    ("dec", "December"),
+];
+
+struct Parser<'a> {
+    input: &'a str,
+    chars: Vec<(usize, char)>,
+    pos: usize,
+    /// The @string macros defined so far (name in lower case, value)
+    macros: Vec<(String, String)>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Parser {
+            input,
+            chars: input.char_indices().collect(),
+            pos: 0,
+            macros: Vec::new(),
+        }
+    }
+
+    /// Returns the current character
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).map(|c| c.1)
+    }
+
+    /// Returns the offset of the current character in the input
+    fn offset(&self) -> usize {
+        self.chars.get(self.pos).map_or(self.input.len(), |c| c.0)
+    }
+
+    /// Returns an error at the current line
+    fn error<T: AsRef<str>>(&self, msg: T) -> Error {
+        let line = self.input[..self.offset()].matches('\n').count() + 1;
+        Error::InvalidBibtex(line, msg.as_ref().to_string())
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    /// Consumes the character, or returns an error
+    fn expect(&mut self, expected: char) -> Result<()> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(c) => Err(self.error(format!("expected '{}', found '{}'", expected, c))),
+            None => Err(self.error(format!(
+                "expected '{}', found the end of the file",
+                expected
+            ))),
+        }
+    }
+
+    /// Reads a name (entry type, field name, macro), made of letters, digits and -_:.+/
+    fn identifier(&mut self) -> Result<String> {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_alphanumeric() || "-_:.+/'".contains(c))
+        {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(self.error("expected a name"));
+        }
+        Ok(self.chars[start..self.pos].iter().map(|c| c.1).collect())
+    }
+
+    /// Reads the key of an entry, up to the comma
+    fn key(&mut self, close: char) -> Result<String> {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c != ',' && c != close && !c.is_whitespace())
+        {
+            self.pos += 1;
+        }
+        Ok(self.chars[start..self.pos].iter().map(|c| c.1).collect())
+    }
+
+    /// Reads the content of a group of braces (the opening brace is consumed)
+    fn braced(&mut self) -> Result<String> {
+        let mut depth = 0;
+        let mut out = String::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error("unbalanced braces")),
+                Some('}') if depth == 0 => {
+                    self.pos += 1;
+                    return Ok(out);
+                }
+                Some(c) => {
+                    if c == '{' {
+                        depth += 1;
+                    } else if c == '}' {
+                        depth -= 1;
+                    }
+                    out.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    /// Reads a quoted string (the opening quote is consumed)
+    /// The quotes inside braces do not end the string
+    fn quoted(&mut self) -> Result<String> {
+        let mut depth = 0;
+        let mut out = String::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error("unterminated string")),
+                Some('"') if depth == 0 => {
+                    self.pos += 1;
+                    return Ok(out);
+                }
+                Some(c) => {
+                    if c == '{' {
+                        depth += 1;
+                    } else if c == '}' {
+                        if depth == 0 {
+                            return Err(self.error("unbalanced braces"));
+                        }
+                        depth -= 1;
+                    }
+                    out.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    /// Reads a value: braced, quoted, number or macro, concatenated with #
+    fn value(&mut self) -> Result<String> {
+        let mut out = String::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some('{') => {
+                    self.pos += 1;
+                    out.push_str(&self.braced()?);
+                }
+                Some('"') => {
+                    self.pos += 1;
+                    out.push_str(&self.quoted()?);
+                }
+                Some(c) if c.is_ascii_digit() => {
+                    while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                        out.push(self.peek().unwrap());
+                        self.pos += 1;
+                    }
+                }
+                Some(_) => {
+                    let name = self.identifier()?.to_lowercase();
+                    match self.lookup(&name) {
+                        Some(value) => out.push_str(&value),
+                        None => return Err(self.error(format!("unknown @string '{}'", name))),
+                    }
+                }
+                None => return Err(self.error("expected a value")),
+            }
+            self.skip_whitespace();
+            if self.peek() == Some('#') {
+                self.pos += 1;
+            } else {
+                return Ok(out);
+            }
+        }
+    }
+
+    /// Returns the value of a macro (@string or month)
+    fn lookup(&self, name: &str) -> Option<String> {
+        self.macros
+            .iter()
+            .rev()
+            .find(|m| m.0 == name)
+            .map(|m| m.1.clone())
+            .or_else(|| MONTHS.iter().find(|m| m.0 == name).map(|m| m.1.to_string()))
+    }
+
+    /// Returns the closing delimiter of the block, after consuming the opening one
+    fn open(&mut self) -> Result<char> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => {
+                self.pos += 1;
+                Ok('}')
+            }
+            Some('(') => {
+                self.pos += 1;
+                Ok(')')
+            }
+            _ => Err(self.error("expected '{' or '('")),
+        }
+    }
+
+    /// Returns the text of the input from the character start to the current one
+    fn text_from(&self, start: usize) -> String {
+        self.input[self.chars[start].0..self.offset()].to_string()
+    }
+
+    /// Reads the fields of an entry (after the key) up to the closing delimiter
+    fn fields(&mut self, entry: &mut BibEntry, close: char) -> Result<()> {
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(c) if c == close => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some(',') => self.pos += 1,
+                Some(_) => {
+                    let name = self.identifier()?;
+                    self.expect('=')?;
+                    let value = self.value()?;
+                    entry.set_field(name, value);
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(',') => self.pos += 1,
+                        Some(c) if c == close => (),
+                        _ => return Err(self.error("expected ',' after the field")),
+                    }
+                }
+                None => return Err(self.error(format!("expected '{}'", close))),
+            }
+        }
+    }
+
+    /// Parses the whole input
+    fn parse(&mut self) -> Result<ParsedBib> {
+        let mut parsed = ParsedBib::default();
+        loop {
+            // Everything outside the blocks is a comment
+            while self.peek().is_some_and(|c| c != '@') {
+                self.pos += 1;
+            }
+            if self.peek().is_none() {
+                return Ok(parsed);
+            }
+            let start = self.pos;
+            self.pos += 1;
+            let kind = self.identifier()?.to_lowercase();
+            match kind.as_str() {
+                "comment" => {
+                    // @comment{...} is skipped, and a bare @comment comments the rest of the line
+                    self.skip_whitespace();
+                    if self.peek() == Some('{') {
+                        self.pos += 1;
+                        self.braced()?;
+                    }
+                }
+                "preamble" => {
+                    let close = self.open()?;
+                    self.value()?;
+                    self.expect(close)?;
+                    parsed.blocks.push(self.text_from(start));
+                }
+                "string" => {
+                    let close = self.open()?;
+                    let name = self.identifier()?.to_lowercase();
+                    self.expect('=')?;
+                    let value = self.value()?;
+                    self.expect(close)?;
+                    self.macros.push((name, value));
+                    parsed.blocks.push(self.text_from(start));
+                }
+                _ => {
+                    let close = self.open()?;
+                    let key = self.key(close)?;
+                    if key.is_empty() {
+                        return Err(self.error("expected the key of the entry"));
+                    }
+                    let mut entry = BibEntry::new(EntryType::from_name(&kind), key);
+                    self.fields(&mut entry, close)?;
+                    entry.set_raw(self.text_from(start));
+                    parsed.entries.push(entry);
+                }
+            }
+        }
+    }
+}
+
+/// Parses the content of a .bib file
+pub fn parse_bibtex<T: AsRef<str>>(input: T) -> Result<ParsedBib> {
+    Parser::new(input.as_ref()).parse()
+}
+
+#[cfg(test)]
+mod tests_bibtex {
+    use super::*;
+    use writable::*;
+
+    const BIB: &str = "% Shared bibliography
+@String{acm = \"ACM\"}
+@STRING(jacm = acm # \" Journal\")
+@preamble{ \"\\newcommand{\\noop}[1]{}\" }
+
+@comment{ This entry is {not} read }
+
+@Article{lamport94,
+    author    = {Leslie Lamport},
+    title     = \"{\\LaTeX}: A Document {\"}Preparation{\"} System\",
+    journal   = jacm,
+    year      = 1994,
+    month     = jun,
+}
+
+@book{knuth84, author = {Donald E. Knuth}, title = {The {\\TeX}book}, year = \"1984\"}
+";
+
+    #[test]
+    fn parse_entries() {
+        let parsed = parse_bibtex(BIB).unwrap();
+        assert_eq!(parsed.blocks.len(), 3);
+        assert_eq!(parsed.entries.len(), 2);
+        let lamport = &parsed.entries[0];
+        assert_eq!(lamport.get_type(), &EntryType::Article);
+        assert_eq!(lamport.get_key(), "lamport94");
+        assert_eq!(
+            lamport.get_field("title"),
+            Some("{\\LaTeX}: A Document {\"}Preparation{\"} System")
+        );
+        assert_eq!(lamport.get_field("journal"), Some("ACM Journal"));
+        assert_eq!(lamport.get_field("year"), Some("1994"));
+        assert_eq!(lamport.get_field("month"), Some("June"));
+        let knuth = &parsed.entries[1];
+        assert_eq!(knuth.get_field("title"), Some("The {\\TeX}book"));
+        assert_eq!(knuth.get_field("year"), Some("1984"));
+    }
+
+    #[test]
+    fn round_trip() {
+        let parsed = parse_bibtex(BIB).unwrap();
+        let mut lamport = parsed.entries[0].clone();
+        assert_eq!(
+            lamport.to_latex().unwrap(),
+            &BIB[BIB.find("@Article").unwrap()..BIB.find("\n@book").unwrap()]
+        );
+        assert_eq!(parsed.blocks[1], "@STRING(jacm = acm # \" Journal\")");
+        // A modified entry is written again from its fields
+        lamport.set_field("year", "1986");
+        assert!(lamport
+            .to_latex()
+            .unwrap()
+            .starts_with("@article{lamport94,\n"));
+        assert!(lamport
+            .to_latex()
+            .unwrap()
+            .contains("  journal = {ACM Journal},\n"));
+    }
+
+    #[test]
+    fn parse_errors() {
+        match parse_bibtex("@article{a,\n title = {Unbalanced}\n") {
+            Err(Error::InvalidBibtex(line, _)) => assert_eq!(line, 3),
+            _ => panic!("The missing brace should be reported"),
+        }
+        match parse_bibtex("@misc{a, journal = unknown}") {
+            Err(Error::InvalidBibtex(line, msg)) => {
+                assert_eq!(line, 1);
+                assert!(msg.contains("unknown"));
+            }
+            _ => panic!("The unknown macro should be reported"),
+        }
+        assert!(parse_bibtex("@misc{a, title = {T} year = 1}").is_err());
+        assert!(parse_bibtex("@misc{, title = {T}}").is_err());
+        assert_eq!(parse_bibtex("No entry here").unwrap(), ParsedBib::default());
+    }
+}
@@ -2,8 +2,8 @@
 /// for example \begin{center}...\end{center}
 ///
 use core::*;
-use latex_file::LatexFile;
-use std::io::BufWriter;
+use error::*;
+use packages::Packages;
 use std::io::Write;
 use writable::*;
 
@@ -36,20 +36,48 @@ impl Bloc {
     pub fn add(&mut self, element: Core) {
         self.content.push(element);
     }
+
+    /// Returns the content of the bloc
+    pub fn get_content(&self) -> &[Core] {
+        &self.content
+    }
+
+    /// Returns the package defining the environment, if it is not a standard one
+    fn environment_package(&self) -> Option<&str> {
+        match self.bloc_type.trim_end_matches('*') {
+            "align" | "alignat" | "flalign" | "gather" | "multline" | "split" | "cases"
+            | "pmatrix" | "bmatrix" | "vmatrix" | "Vmatrix" => Some("amsmath"),
+            "lstlisting" => Some("listings"),
+            "minted" => Some("minted"),
+            "tikzpicture" => Some("tikz"),
+            "axis" => Some("pgfplots"),
+            "multicols" => Some("multicol"),
+            "longtable" => Some("longtable"),
+            "subfigure" => Some("subcaption"),
+            "wrapfigure" => Some("wrapfig"),
+            "landscape" => Some("lscape"),
+            _ => None,
+        }
+    }
 }
 
 impl Writable for Bloc {
-    fn write_latex(&self, file: &mut LatexFile) {
-        let mut writer = BufWriter::new(file);
-        self.write_to_buffer(&mut writer);
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        writeln!(buf, "\\begin{{{}}}", self.bloc_type)?;
+        for item in self.content.iter() {
+            item.write_to_buffer(buf)?;
+        }
+        writeln!(buf, "\n\\end{{{}}}", self.bloc_type)?;
+        Ok(())
     }
 
-    fn write_to_buffer(&self, mut buf: &mut BufWriter<&mut LatexFile>) {
-        writeln!(&mut buf, "\\begin{{{}}}", self.bloc_type).unwrap();
+    fn add_packages(&self, packages: &mut Packages) {
+        if let Some(package) = self.environment_package() {
+            packages.add_package(package);
+        }
         for item in self.content.iter() {
-            item.write_to_buffer(&mut buf);
+            item.add_packages(packages);
         }
-        writeln!(&mut buf, "\n\\end{{{}}}", self.bloc_type).unwrap();
     }
 }
 
@@ -79,38 +107,46 @@ mod tests_bloc {
     #[test]
     fn test_add_core() {
         let mut b = Core::bloc("center");
-        b.add(Core::bloc("verbatim"));
+        b.add(Core::bloc("verbatim")).unwrap();
     }
 
     #[test]
     fn test_write_simple_bloc_empty() {
-        let mut f = new_latex_file("./tests_results/bloc/bloc_simple_test_empty.tex");
-        f.begin_document();
+        let mut f = new_latex_file("./tests_results/bloc/bloc_simple_test_empty.tex").unwrap();
+        f.begin_document().unwrap();
         let b = Bloc::new_empty("center");
-        b.write_latex(&mut f);
-        f.write_footer();
+        b.write_latex(&mut f).unwrap();
+        f.write_footer().unwrap();
     }
 
     #[test]
     fn test_write_simple_bloc_non_empty() {
-        let mut f = new_latex_file("./tests_results/bloc/bloc_simple_test_non_empty.tex");
-        f.begin_document();
+        let mut f = new_latex_file("./tests_results/bloc/bloc_simple_test_non_empty.tex").unwrap();
+        f.begin_document().unwrap();
         let mut b = Bloc::new_empty("center");
         b.add(Core::text("This is supposed to be centered"));
-        b.write_latex(&mut f);
-        f.write_footer();
+        b.write_latex(&mut f).unwrap();
+        f.write_footer().unwrap();
     }
 
     #[test]
     fn test_write_nested_bloc_non_empty() {
-        let mut f = new_latex_file("./tests_results/bloc/bloc_nested_test_non_empty.tex");
-        f.begin_document();
+        let mut f = new_latex_file("./tests_results/bloc/bloc_nested_test_non_empty.tex").unwrap();
+        f.begin_document().unwrap();
         let mut b = Bloc::new_empty("center");
         let mut b2 = Bloc::new_empty("verbatim");
         b2.add(Core::text("To be or not to be"));
         b.add(Core::Bloc(b2));
-        b.write_latex(&mut f);
-        f.write_footer();
+        b.write_latex(&mut f).unwrap();
+        f.write_footer().unwrap();
     }
 
+    #[test]
+    fn test_write_into_vec() {
+        let mut b = Bloc::new_empty("center");
+        b.add(Core::text("Centered"));
+        let mut out = Vec::new();
+        b.write_to_buffer(&mut out).unwrap();
+        assert_eq!(out, b"\\begin{center}\nCentered\n\\end{center}\n".to_vec());
+    }
 }
@@ -1,7 +1,7 @@
-use latex_file::*;
 /// File defining a method to import content from an other file
 ///
-use std::io::BufWriter;
+use error::*;
+use packages::Packages;
 use std::io::Write;
 use writable::*;
 
@@ -23,17 +23,16 @@ impl Code {
 }
 
 impl Writable for Code {
-    fn write_latex(&self, file: &mut LatexFile) {
-        let mut writer = BufWriter::new(file);
-        self.write_to_buffer(&mut writer);
-    }
-
-    fn write_to_buffer(&self, mut buf: &mut BufWriter<&mut LatexFile>) {
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
         writeln!(
-            &mut buf,
+            buf,
             "\\lstinputlisting[language={}]{{{}}}",
             self.language, self.filename
-        )
-        .unwrap();
+        )?;
+        Ok(())
+    }
+
+    fn add_packages(&self, packages: &mut Packages) {
+        packages.add_package("listings");
     }
 }
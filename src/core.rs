@@ -1,16 +1,24 @@
+use bibliography::Citation;
 use bloc::Bloc;
 use content_from_file::*;
+use csv::CsvOptions;
 use equations::*;
+use error::*;
+use figure::Figure;
+use escape::*;
 use graphics::*;
 use into_tab::*;
-use latex_file::LatexFile;
+use labels::*;
 use math_mode::*;
+use number::Number;
 /// File to define the core of a LaTex file
 ///
+use packages::Packages;
+use plot::Plot;
 use sections::*;
 use std::fs::File;
 use std::io::prelude::*;
-use std::io::BufWriter;
+use table::Table;
 use tabular::*;
 use tag::*;
 use writable::*;
@@ -20,77 +28,136 @@ use writable::*;
 #[derive(Clone)]
 pub enum Core {
     Sec(Section),
+    Text(Text),
     RawText(String),
     Equa(Equation),
+    MultiEqua(MultilineEquation),
     Bloc(Bloc),
     Tag(SingleTag),
     Tab(Tabular),
+    Num(Number),
+    Table(Table),
     Math(MathContent),
     Graph(Graphic),
+    Figure(Figure),
     Code(Code),
     TextFromFile(String),
+    TabFromFile(String, CsvOptions),
+    Ref(Reference),
+    Cite(Citation),
 }
 
 impl Writable for Core {
-    fn write_latex(&self, mut file: &mut LatexFile) {
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
         match *self {
-            Core::Sec(ref section) => section.write_latex(&mut file),
-            Core::RawText(ref text) => text.write_latex(&mut file),
-            Core::Equa(ref eq) => eq.write_latex(&mut file),
-            Core::Bloc(ref bloc) => bloc.write_latex(&mut file),
-            Core::Tag(ref tag) => tag.write_latex(&mut file),
-            Core::Tab(ref tab) => tab.write_latex(&mut file),
-            Core::Math(ref m) => m.write_latex(&mut file),
-            Core::Graph(ref g) => g.write_latex(&mut file),
-            Core::Code(ref c) => c.write_latex(&mut file),
+            Core::Sec(ref section) => section.write_to_buffer(buf),
+            Core::Text(ref text) => text.write_to_buffer(buf),
+            Core::RawText(ref text) => text.write_to_buffer(buf),
+            Core::Equa(ref eq) => eq.write_to_buffer(buf),
+            Core::MultiEqua(ref eq) => eq.write_to_buffer(buf),
+            Core::Bloc(ref bloc) => bloc.write_to_buffer(buf),
+            Core::Tag(ref tag) => tag.write_to_buffer(buf),
+            Core::Tab(ref tab) => tab.write_to_buffer(buf),
+            Core::Num(ref n) => n.write_to_buffer(buf),
+            Core::Table(ref table) => table.write_to_buffer(buf),
+            Core::Math(ref m) => m.write_to_buffer(buf),
+            Core::Graph(ref g) => g.write_to_buffer(buf),
+            Core::Figure(ref f) => f.write_to_buffer(buf),
+            Core::Code(ref c) => c.write_to_buffer(buf),
             Core::TextFromFile(ref filename) => {
-                let mut out_file = File::open(filename).unwrap();
                 let mut contents = String::new();
-                out_file.read_to_string(&mut contents).unwrap();
-                contents.write_latex(&mut file)
+                File::open(filename)
+                    .and_then(|mut out_file| out_file.read_to_string(&mut contents))
+                    .map_err(|e| Error::MissingFile(filename.to_string(), e))?;
+                Text::new(contents).write_to_buffer(buf)
             }
+            Core::TabFromFile(ref filename, ref options) => {
+                Tabular::from_csv_file(filename, options)?.write_to_buffer(buf)
+            }
+            Core::Ref(ref r) => r.write_to_buffer(buf),
+            Core::Cite(ref c) => c.write_to_buffer(buf),
         }
     }
 
-    fn write_to_buffer(&self, mut buf: &mut BufWriter<&mut LatexFile>) {
+    fn add_packages(&self, packages: &mut Packages) {
         match *self {
-            Core::Sec(ref section) => section.write_to_buffer(&mut buf),
-            Core::RawText(ref text) => text.write_to_buffer(&mut buf),
-            Core::Equa(ref eq) => eq.write_to_buffer(&mut buf),
-            Core::Bloc(ref bloc) => bloc.write_to_buffer(&mut buf),
-            Core::Tag(ref tag) => tag.write_to_buffer(&mut buf),
-            Core::Tab(ref tab) => tab.write_to_buffer(&mut buf),
-            Core::Math(ref m) => m.write_to_buffer(&mut buf),
-            Core::Graph(ref g) => g.write_to_buffer(&mut buf),
-            Core::Code(ref c) => c.write_to_buffer(&mut buf),
-            _ => (),
+            Core::Sec(ref section) => section.add_packages(packages),
+            Core::Equa(ref eq) => eq.add_packages(packages),
+            Core::MultiEqua(ref eq) => eq.add_packages(packages),
+            Core::Bloc(ref bloc) => bloc.add_packages(packages),
+            Core::Tag(ref tag) => tag.add_packages(packages),
+            Core::Tab(ref tab) => tab.add_packages(packages),
+            Core::Table(ref table) => table.add_packages(packages),
+            Core::Math(ref m) => m.add_packages(packages),
+            Core::Graph(ref g) => g.add_packages(packages),
+            Core::Figure(ref f) => f.add_packages(packages),
+            Core::Code(ref c) => c.add_packages(packages),
+            Core::Ref(ref r) => r.add_packages(packages),
+            Core::Text(_) | Core::RawText(_) | Core::TextFromFile(_)
+            | Core::TabFromFile(..)
+            | Core::Num(_)
+            | Core::Cite(_) => (),
         }
     }
 }
 
 impl Core {
+    /// Returns a new part
+    pub fn part<T: AsRef<str>>(title: T) -> Self {
+        Core::Sec(Section::new_part(title.as_ref()))
+    }
+
+    /// Returns a new chapter
+    pub fn chapter<T: AsRef<str>>(title: T) -> Self {
+        Core::Sec(Section::new_chapter(title.as_ref()))
+    }
+
     /// Returns a new section
     pub fn section<T: AsRef<str>>(title: T) -> Self {
-        Core::Sec(Section::new_section(title.as_ref().to_string()))
+        Core::Sec(Section::new_section(title.as_ref()))
     }
 
     /// Returns a new subsection
     pub fn subsection<T: AsRef<str>>(title: T) -> Self {
-        Core::Sec(Section::new_subsection(title.as_ref().to_string()))
+        Core::Sec(Section::new_subsection(title.as_ref()))
     }
 
     /// Returns a new subsubsection
     pub fn subsubsection<T: AsRef<str>>(title: T) -> Self {
-        Core::Sec(Section::new_subsubsection(title.as_ref().to_string()))
+        Core::Sec(Section::new_subsubsection(title.as_ref()))
     }
 
     /// Returns a new paragraph
     pub fn paragraph<T: AsRef<str>>(title: T) -> Self {
-        Core::Sec(Section::new_paragraph(title.as_ref().to_string()))
+        Core::Sec(Section::new_paragraph(title.as_ref()))
+    }
+
+    /// Returns a new subparagraph
+    pub fn subparagraph<T: AsRef<str>>(title: T) -> Self {
+        Core::Sec(Section::new_subparagraph(title.as_ref()))
+    }
+
+    /// Enables or disables the numbering of the section
+    pub fn set_starred(&mut self, starred: bool) {
+        if let Core::Sec(ref mut section) = *self {
+            section.set_starred(starred)
+        }
     }
 
-    /// Return a new text
-    pub fn text<T: AsRef<str>>(raw_text: T) -> Self {
+    /// Changes the short title of the section (for the table of contents)
+    pub fn set_short_title<T: AsRef<str>>(&mut self, short_title: T) {
+        if let Core::Sec(ref mut section) = *self {
+            section.set_short_title(short_title)
+        }
+    }
+
+    /// Return a new text, the LaTeX special characters are escaped
+    pub fn text<T: AsRef<str>>(text: T) -> Self {
+        Core::Text(Text::new(text))
+    }
+
+    /// Return a new raw text, written verbatim (trusted LaTeX code)
+    pub fn raw<T: AsRef<str>>(raw_text: T) -> Self {
         Core::RawText(raw_text.as_ref().to_string())
     }
 
@@ -99,9 +166,14 @@ impl Core {
         Core::Equa(eq)
     }
 
+    /// Returns a new equation on several lines (align, gather, multline, split)
+    pub fn multiline_equation(eq: MultilineEquation) -> Self {
+        Core::MultiEqua(eq)
+    }
+
     /// Returns a new Bloc
     pub fn bloc<T: AsRef<str>>(title: T) -> Self {
-        Core::Bloc(Bloc::new_empty(title.as_ref().to_string()))
+        Core::Bloc(Bloc::new_empty(title.as_ref()))
     }
 
     /// Returns a new Tab
@@ -109,6 +181,11 @@ impl Core {
         Core::Tab(Tabular::new(content))
     }
 
+    /// Returns a new table (a tabular in a float) with a caption
+    pub fn table<T: AsRef<str>>(tabular: Tabular, caption: T) -> Self {
+        Core::Table(Table::with_caption(tabular, caption))
+    }
+
     /// Returns a new \item tag
     pub fn item(content: Core) -> Self {
         Core::Tag(SingleTag::item(content))
@@ -127,6 +204,23 @@ impl Core {
         ))
     }
 
+    /// Returns an image written without a float, nor caption (a bare \\includegraphics)
+    pub fn inline_graphic<T: AsRef<str>>(filename: T) -> Self {
+        Core::Graph(Graphic::inline(filename))
+    }
+
+    /// Returns a figure drawing the plot (pgfplots)
+    pub fn plot<T: AsRef<str>>(plot: Plot, caption: T) -> Self {
+        let mut graphic = Graphic::from_plot(plot);
+        graphic.set_caption(caption);
+        Core::Graph(graphic)
+    }
+
+    /// Returns a figure with several images side by side, each one with its own caption
+    pub fn figure<T: AsRef<str>>(subfigures: Vec<Graphic>, caption: T) -> Self {
+        Core::Figure(Figure::with_caption(subfigures, caption))
+    }
+
     /// Change the scale of the graphic
     pub fn set_scale(&mut self, new_scale: f64) {
         if let Core::Graph(ref mut graph) = *self {
@@ -142,17 +236,112 @@ impl Core {
         ))
     }
 
+    /// Returns a new number, aligned on the decimal point in the S columns of a tabular
+    pub fn number(value: f64) -> Self {
+        Core::Num(Number::new(value))
+    }
+
     /// Returns a new text from file element
     pub fn text_from_file<T: AsRef<str>>(filename: T) -> Self {
         Core::TextFromFile(filename.as_ref().to_string())
     }
 
+    /// Returns a new tabular from a CSV file, read when the document is written
+    pub fn tab_from_csv<T: AsRef<str>>(filename: T, options: CsvOptions) -> Self {
+        Core::TabFromFile(filename.as_ref().to_string(), options)
+    }
+
+    /// Returns a reference to a labelled element (\\eqref for equations, \\ref otherwise)
+    pub fn reference(label: &Label) -> Self {
+        Core::Ref(Reference::new(label))
+    }
+
+    /// Returns a reference to the page of a labelled element
+    pub fn page_reference(label: &Label) -> Self {
+        Core::Ref(Reference::with_style(label, RefStyle::PageRef))
+    }
+
+    /// Returns a reference to a labelled element, with its name (Figure 1, Section 2, ...)
+    pub fn auto_reference(label: &Label) -> Self {
+        Core::Ref(Reference::with_style(label, RefStyle::AutoRef))
+    }
+
+    /// Labels the element, and returns the handle to refer to it
+    /// Only the sections, equations, figures and tables can be labelled
+    /// (a tabular is labelled for the Table containing it)
+    pub fn set_label<T: AsRef<str>>(&mut self, name: T) -> Result<Label> {
+        match *self {
+            Core::Sec(ref mut section) => Ok(section.set_label(name)),
+            Core::Equa(ref mut eq) => Ok(eq.set_label(name)),
+            Core::MultiEqua(ref mut eq) => Ok(eq.set_label(name)),
+            Core::Graph(ref mut g) => Ok(g.set_label(name)),
+            Core::Figure(ref mut f) => Ok(f.set_label(name)),
+            Core::Tab(ref mut tab) => Ok(tab.set_label(name)),
+            Core::Table(ref mut table) => Ok(table.set_label(name)),
+            _ => Err(Error::InvalidStructure(
+                "This type of data can not be labelled".to_string(),
+            )),
+        }
+    }
+
+    /// Returns the label of the element
+    pub fn get_label(&self) -> Option<&Label> {
+        match *self {
+            Core::Sec(ref section) => section.get_label(),
+            Core::Equa(ref eq) => eq.get_label(),
+            Core::MultiEqua(ref eq) => eq.get_label(),
+            Core::Graph(ref g) => g.get_label(),
+            Core::Figure(ref f) => f.get_label(),
+            Core::Tab(ref tab) => tab.get_label(),
+            Core::Table(ref table) => table.get_label(),
+            _ => None,
+        }
+    }
+
+    /// Calls f on the element and on every element it contains (depth first)
+    pub fn walk<F: FnMut(&Core)>(&self, f: &mut F) {
+        f(self);
+        match *self {
+            Core::Sec(ref section) => {
+                for item in section.get_content().iter() {
+                    item.walk(f);
+                }
+            }
+            Core::Bloc(ref bloc) => {
+                for item in bloc.get_content().iter() {
+                    item.walk(f);
+                }
+            }
+            Core::Tag(ref tag) => tag.get_content().walk(f),
+            Core::Tab(ref tab) => walk_tabular(tab, f),
+            Core::Table(ref table) => walk_tabular(table.get_tabular(), f),
+            _ => (),
+        }
+    }
+
     /// Add an element to the content, if possible
-    pub fn add(&mut self, element: Core) {
+    pub fn add(&mut self, element: Core) -> Result<()> {
         match *self {
             Core::Sec(ref mut section) => section.add_content(element),
             Core::Bloc(ref mut bloc) => bloc.add(element),
-            _ => panic!("No method 'add' for this type of data"),
+            _ => {
+                return Err(Error::InvalidStructure(
+                    "No method 'add' for this type of data".to_string(),
+                ))
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Calls f on every cell of the tabular (depth first)
+fn walk_tabular<F: FnMut(&Core)>(tab: &Tabular, f: &mut F) {
+    for cell in tab.get_header().unwrap_or(&[]).iter() {
+        cell.get_content().walk(f);
+    }
+    for line in tab.get_content().iter() {
+        for cell in line.iter() {
+            cell.get_content().walk(f);
         }
     }
 }
@@ -162,11 +351,23 @@ mod tests_raw_text {
     use super::*;
     use latex_file::*;
 
+    #[test]
+    fn escaped_text() {
+        let t = Core::text("100% of R&D");
+        assert_eq!(t.to_latex().unwrap(), "100\\% of R\\&D");
+    }
+
+    #[test]
+    fn raw_text() {
+        let t = Core::raw("\\textbf{100\\%}");
+        assert_eq!(t.to_latex().unwrap(), "\\textbf{100\\%}");
+    }
+
     #[test]
     fn simple_write_text() {
-        let mut f = new_latex_file("./tests_results/raw_texts/simple_write.tex");
+        let mut f = new_latex_file("./tests_results/raw_texts/simple_write.tex").unwrap();
         let t1 = Core::text("Quentin");
-        t1.write_latex(&mut f);
+        t1.write_latex(&mut f).unwrap();
     }
 }
 
@@ -175,118 +376,164 @@ mod tests_core {
     use super::*;
     use latex_file::*;
 
+    #[test]
+    fn test_add_to_text() {
+        let mut t = Core::text("Text");
+        match t.add(Core::text("Other text")) {
+            Err(Error::InvalidStructure(_)) => (),
+            _ => panic!("Adding content to a text should fail"),
+        }
+    }
+
+    #[test]
+    fn test_missing_text_file() {
+        let text = Core::text_from_file("this_file_does_not_exist.txt");
+        match text.to_latex() {
+            Err(Error::MissingFile(filename, _)) => {
+                assert_eq!(filename, "this_file_does_not_exist.txt")
+            }
+            _ => panic!("A missing file should be reported"),
+        }
+    }
+
+    #[test]
+    fn test_tab_from_csv() {
+        let tab = Core::tab_from_csv("this_file_does_not_exist.csv", CsvOptions::new());
+        match tab.to_latex() {
+            Err(Error::MissingFile(filename, _)) => {
+                assert_eq!(filename, "this_file_does_not_exist.csv")
+            }
+            _ => panic!("A missing file should be reported"),
+        }
+        std::fs::write("./tests_results/core/data.csv", "x;y\n1;2\n").unwrap();
+        let mut options = CsvOptions::new();
+        options.set_delimiter(';');
+        let tab = Core::tab_from_csv("./tests_results/core/data.csv", options);
+        assert_eq!(
+            tab.to_latex().unwrap(),
+            "\\begin{tabular}{| c | c |}\n \\hline\nx & y \\\\\n \\hline\n1 & 2 \\\\\n \\hline\n\\end{tabular}\n"
+        );
+    }
+
     #[test]
     fn test_enumerate() {
-        let mut f = new_latex_file("./tests_results/core/enumerate.tex");
-        f.begin_document();
+        let mut f = new_latex_file("./tests_results/core/enumerate.tex").unwrap();
+        f.begin_document().unwrap();
         let mut enumerate = Core::bloc("enumerate");
         for i in 0..5 {
-            enumerate.add(Core::item(Core::text(format!("Blabla {}", i))));
+            enumerate
+                .add(Core::item(Core::text(format!("Blabla {}", i))))
+                .unwrap();
         }
-        enumerate.write_latex(&mut f);
-        f.write_footer();
+        enumerate.write_latex(&mut f).unwrap();
+        f.write_footer().unwrap();
     }
 
     #[test]
     fn test_tabular_one_dim() {
-        let mut f = new_latex_file("./tests_results/core/tabular_one_dim.tex");
-        f.begin_document();
+        let mut f = new_latex_file("./tests_results/core/tabular_one_dim.tex").unwrap();
+        f.begin_document().unwrap();
         let mut vec = Vec::new();
         for i in 0..5 {
             vec.push(Core::text(i.to_string()));
         }
         // let vec = (0..5).map(|i| Core::text(i.to_string())).collect();
         let tab = Core::tab(&vec);
-        tab.write_latex(&mut f);
-        f.write_footer();
+        tab.write_latex(&mut f).unwrap();
+        f.write_footer().unwrap();
     }
 
     #[test]
     fn test_tabular_two_dims() {
-        let mut f = new_latex_file("./tests_results/core/tabular_two_dims.tex");
-        f.begin_document();
-        let mut vec_2d = Vec::new();
-        for j in 0..6 {
-            let mut vec = Vec::new();
-            for i in 0..5 {
-                vec.push(Core::text((i + j).to_string()));
-            }
-            vec_2d.push(vec);
-        }
-        // let vec = (0..5).map(|i| Core::text(i.to_string())).collect();
+        let mut f = new_latex_file("./tests_results/core/tabular_two_dims.tex").unwrap();
+        f.begin_document().unwrap();
+        let vec_2d: Vec<Vec<usize>> = (0..6).map(|j| (0..5).map(|i| i + j).collect()).collect();
         let tab = Core::tab(&vec_2d);
-        tab.write_latex(&mut f);
-        f.write_footer();
+        tab.write_latex(&mut f).unwrap();
+        f.write_footer().unwrap();
     }
 
     #[test]
     fn test_math_mode_simple() {
-        let mut f = new_latex_file("./tests_results/core/math_mode_simple.tex");
-        f.begin_document();
+        let mut f = new_latex_file("./tests_results/core/math_mode_simple.tex").unwrap();
+        f.begin_document().unwrap();
         let m = Core::math("1 + 2 = 3");
-        m.write_latex(&mut f);
-        f.write_footer();
+        m.write_latex(&mut f).unwrap();
+        f.write_footer().unwrap();
     }
 
     #[test]
     fn test_math_mode_symbol() {
-        let mut f = new_latex_file("./tests_results/core/math_mode_symbol.tex");
-        f.begin_document();
+        let mut f = new_latex_file("./tests_results/core/math_mode_symbol.tex").unwrap();
+        f.begin_document().unwrap();
         let m = Core::math(r"1 \leq 2 = 3");
-        m.write_latex(&mut f);
-        f.write_footer();
+        m.write_latex(&mut f).unwrap();
+        f.write_footer().unwrap();
     }
 
     #[test]
     fn test_math_mode_in_text() {
-        let mut f = new_latex_file("./tests_results/core/math_mode_in_text.tex");
-        f.begin_document();
+        let mut f = new_latex_file("./tests_results/core/math_mode_in_text.tex").unwrap();
+        f.begin_document().unwrap();
         let mut p = Core::paragraph("");
-        p.add(Core::text("This is the most interesting equation:"));
+        p.add(Core::text("This is the most interesting equation:"))
+            .unwrap();
         let m = Core::math("1 + 2 = 3");
-        p.add(m);
-        p.write_latex(&mut f);
-        f.write_footer();
+        p.add(m).unwrap();
+        p.write_latex(&mut f).unwrap();
+        f.write_footer().unwrap();
     }
 
     #[test]
     fn test_include_graphics() {
-        let mut f = new_latex_file("./tests_results/core/include_graphics.tex");
+        let mut f = new_latex_file("./tests_results/core/include_graphics.tex").unwrap();
         f.add_package("graphicx");
-        f.begin_document();
+        f.begin_document().unwrap();
         let im = Core::graphic("rust_logo.jpg", "This is the Rust logo!");
-        im.write_latex(&mut f);
-        f.write_footer();
+        im.write_latex(&mut f).unwrap();
+        f.write_footer().unwrap();
     }
 
     #[test]
     fn test_include_graphics_scale() {
-        let mut f = new_latex_file("./tests_results/core/include_graphics_scale.tex");
+        let mut f = new_latex_file("./tests_results/core/include_graphics_scale.tex").unwrap();
         f.add_package("graphicx");
-        f.begin_document();
+        f.begin_document().unwrap();
         let mut im = Core::graphic("rust_logo.jpg", "This is the Rust logo!");
         im.set_scale(0.5);
-        im.write_latex(&mut f);
-        f.write_footer();
+        im.write_latex(&mut f).unwrap();
+        f.write_footer().unwrap();
+    }
+
+    #[test]
+    fn test_include_graphics_packages() {
+        let mut f = new_latex_file("./tests_results/core/include_graphics_packages.tex").unwrap();
+        let mut sec = Core::section("Logo");
+        sec.add(Core::graphic("rust_logo.jpg", "This is the Rust logo!"))
+            .unwrap();
+        f.add_packages_of(&sec);
+        f.begin_document().unwrap();
+        sec.write_latex(&mut f).unwrap();
+        f.write_footer().unwrap();
     }
 
     #[test]
     fn test_include_code() {
-        let mut f = new_latex_file("./tests_results/core/include_code.tex");
+        let mut f = new_latex_file("./tests_results/core/include_code.tex").unwrap();
         f.add_package("listings");
-        f.begin_document();
+        f.begin_document().unwrap();
         let code = Core::code("../../test.c", "C");
-        code.write_latex(&mut f);
-        f.write_footer();
+        code.write_latex(&mut f).unwrap();
+        f.write_footer().unwrap();
     }
 
     #[test]
     fn test_include_text() {
-        let mut f = new_latex_file("./tests_results/core/include_text.tex");
-        f.begin_document();
+        let mut f = new_latex_file("./tests_results/core/include_text.tex").unwrap();
+        f.begin_document().unwrap();
         let text = Core::text_from_file("text.txt");
-        text.write_latex(&mut f);
-        f.write_footer();
+        text.write_latex(&mut f).unwrap();
+        f.write_footer().unwrap();
     }
 
 }
@@ -0,0 +1,319 @@
+/// File defining the import of CSV files into tabulars
+/// (quoted fields, delimiters, header row, selection of the columns and number formatting)
+///
+use core::*;
+use error::*;
+use std::fs::File;
+use std::io::Read;
+use tabular::Tabular;
+
+pub use number::NumberFormat;
+
+/// A column of a CSV file, by index (from 0) or by name (with a header row)
+#[derive(Clone, Debug, PartialEq)]
+pub enum CsvColumn {
+    Index(usize),
+    Name(String),
+}
+
+impl From<usize> for CsvColumn {
+    fn from(index: usize) -> Self {
+        CsvColumn::Index(index)
+    }
+}
+
+impl<'a> From<&'a str> for CsvColumn {
+    fn from(name: &'a str) -> Self {
+        CsvColumn::Name(name.to_string())
+    }
+}
+
+impl From<String> for CsvColumn {
+    fn from(name: String) -> Self {
+        CsvColumn::Name(name)
+    }
+}
+
+/// The options of the import of a CSV file
+#[derive(Clone, Debug, PartialEq)]
+pub struct CsvOptions {
+    /// The separator of the fields (',' by default)
+    delimiter: char,
+    /// If true, the first record is the header row of the tabular
+    header: bool,
+    /// The columns to keep, in order (all of them if None)
+    columns: Option<Vec<CsvColumn>>,
+    /// The formatting of the numbers, by column
+    formats: Vec<(CsvColumn, NumberFormat)>,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: ',',
+            header: true,
+            columns: None,
+            formats: Vec::new(),
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Returns the default options: ',' as delimiter, and a header row
+    pub fn new() -> Self {
+        CsvOptions::default()
+    }
+
+    /// Changes the separator of the fields (';', '\t', ...)
+    pub fn set_delimiter(&mut self, delimiter: char) {
+        self.delimiter = delimiter;
+    }
+
+    /// Enables or disables the header row
+    pub fn set_header(&mut self, header: bool) {
+        self.header = header;
+    }
+
+    /// Keeps only these columns, in this order
+    pub fn select_columns<T: Into<CsvColumn>>(&mut self, columns: Vec<T>) {
+        self.columns = Some(columns.into_iter().map(|c| c.into()).collect());
+    }
+
+    /// Formats the numbers of a column
+    pub fn set_number_format<T: Into<CsvColumn>>(&mut self, column: T, format: NumberFormat) {
+        let column = column.into();
+        self.formats.retain(|f| f.0 != column);
+        self.formats.push((column, format));
+    }
+
+    /// Returns the index of a column
+    fn index_of(&self, column: &CsvColumn, header: Option<&Vec<String>>) -> Result<usize> {
+        match *column {
+            CsvColumn::Index(i) => Ok(i),
+            CsvColumn::Name(ref name) => match header {
+                Some(names) => names.iter().position(|n| n == name).ok_or_else(|| {
+                    Error::InvalidStructure(format!("The CSV has no column '{}'", name))
+                }),
+                None => Err(Error::InvalidStructure(format!(
+                    "The column '{}' can only be found with a header row",
+                    name
+                ))),
+            },
+        }
+    }
+}
+
+/// Parses the content of a CSV file into records
+/// Quoted fields can contain the delimiter, new lines and doubled quotes (""),
+/// the blank lines are skipped
+pub fn parse_csv<T: AsRef<str>>(input: T, delimiter: char) -> Result<Vec<Vec<String>>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut chars = input.as_ref().chars().peekable();
+    let mut line = 1;
+    // True if the current field was quoted
+    let mut quoted = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if field.is_empty() && !quoted => {
+                quoted = true;
+                let start = line;
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            field.push('"');
+                        }
+                        Some('"') => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            field.push(c);
+                        }
+                        None => {
+                            return Err(Error::InvalidCsv(
+                                start,
+                                "unterminated quoted field".to_string(),
+                            ))
+                        }
+                    }
+                }
+            }
+            c if c == delimiter => {
+                record.push(field);
+                field = String::new();
+                quoted = false;
+            }
+            '\r' if chars.peek() == Some(&'\n') => (),
+            // The blank lines are not records
+            '\n' if record.is_empty() && field.is_empty() && !quoted => line += 1,
+            '\n' => {
+                record.push(field);
+                records.push(record);
+                record = Vec::new();
+                field = String::new();
+                quoted = false;
+                line += 1;
+            }
+            c if quoted => {
+                return Err(Error::InvalidCsv(
+                    line,
+                    format!("unexpected '{}' after a quoted field", c),
+                ))
+            }
+            c => field.push(c),
+        }
+    }
+    if quoted || !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+impl Tabular {
+    /// Returns a new Tabular with the records of a CSV
+    /// The cells are escaped, except the formatted numbers
+    pub fn from_csv<R: Read>(mut reader: R, options: &CsvOptions) -> Result<Self> {
+        let mut content = String::new();
+        reader.read_to_string(&mut content)?;
+        let mut records = parse_csv(content, options.delimiter)?.into_iter();
+        let header = if options.header { records.next() } else { None };
+        let columns: Option<Vec<usize>> = match options.columns {
+            Some(ref columns) => Some(
+                columns
+                    .iter()
+                    .map(|c| options.index_of(c, header.as_ref()))
+                    .collect::<Result<_>>()?,
+            ),
+            None => None,
+        };
+        // A selected column after the last one of every record is an error, not an empty column
+        let width = header
+            .iter()
+            .chain(records.as_slice())
+            .map(|r| r.len())
+            .max()
+            .unwrap_or(0);
+        if let Some(&i) = columns.iter().flatten().find(|&&i| i >= width) {
+            return Err(Error::InvalidCsv(
+                1,
+                format!("no column {}, the records have {} fields", i, width),
+            ));
+        }
+        let mut formats = Vec::new();
+        for (column, format) in options.formats.iter() {
+            formats.push((options.index_of(column, header.as_ref())?, format));
+        }
+        // Returns the cells of the selected columns
+        let select = |record: &[String], format: bool| -> Vec<Core> {
+            let indices: Vec<usize> = match columns {
+                Some(ref columns) => columns.clone(),
+                None => (0..record.len()).collect(),
+            };
+            indices
+                .iter()
+                .map(|&i| {
+                    let value = record.get(i).map_or("", |v| v.as_str());
+                    let number = formats
+                        .iter()
+                        .find(|f| f.0 == i && format)
+                        .and_then(|f| f.1.format(value));
+                    match number {
+                        Some(number) => Core::raw(number),
+                        None => Core::text(value),
+                    }
+                })
+                .collect()
+        };
+        let rows: Vec<Vec<Core>> = records.map(|r| select(&r, true)).collect();
+        let mut tab = Tabular::new(&rows);
+        if let Some(header) = header {
+            tab.set_header(select(&header, false));
+        }
+        Ok(tab)
+    }
+
+    /// Returns a new Tabular with the records of a CSV file
+    pub fn from_csv_file<T: AsRef<str>>(filename: T, options: &CsvOptions) -> Result<Self> {
+        let f = File::open(filename.as_ref())
+            .map_err(|e| Error::MissingFile(filename.as_ref().to_string(), e))?;
+        Tabular::from_csv(f, options)
+    }
+}
+
+#[cfg(test)]
+mod tests_csv {
+    use super::*;
+    use writable::*;
+
+    #[test]
+    fn parse() {
+        let records =
+            parse_csv("a,\"b, c\",\"say \"\"hi\"\"\"\r\n1,\"multi\nline\",\n", ',').unwrap();
+        assert_eq!(
+            records,
+            vec![
+                vec!["a", "b, c", "say \"hi\""],
+                vec!["1", "multi\nline", ""],
+            ]
+        );
+        assert_eq!(parse_csv("a;b", ';').unwrap(), vec![vec!["a", "b"]]);
+        assert!(parse_csv("", ',').unwrap().is_empty());
+        assert_eq!(
+            parse_csv("a\n\r\n\nb,\n\n", ',').unwrap(),
+            vec![vec!["a"], vec!["b", ""]]
+        );
+        match parse_csv("a\n\n\"b", ',') {
+            Err(Error::InvalidCsv(line, _)) => assert_eq!(line, 3),
+            _ => panic!("The unterminated quote should be reported"),
+        }
+        match parse_csv("a\n\"b", ',') {
+            Err(Error::InvalidCsv(line, _)) => assert_eq!(line, 2),
+            _ => panic!("The unterminated quote should be reported"),
+        }
+        assert!(parse_csv("\"a\"b", ',').is_err());
+    }
+
+    #[test]
+    fn csv_to_tabular() {
+        let csv = "name,time,runs\nseq_bench,1234.5678,10\npar,0.5,20\n";
+        let mut options = CsvOptions::new();
+        options.select_columns(vec!["runs", "name", "time"]);
+        options.set_number_format("time", NumberFormat::fixed(1));
+        let tab = Tabular::from_csv(csv.as_bytes(), &options).unwrap();
+        assert_eq!(
+            tab.to_latex().unwrap(),
+            "\\begin{tabular}{| c | c | c |}\n \\hline\n\
+             runs & name & time \\\\\n \\hline\n\
+             10 & seq\\_bench & 1234.6 \\\\\n \\hline\n\
+             20 & par & 0.5 \\\\\n \\hline\n\
+             \\end{tabular}\n"
+        );
+    }
+
+    #[test]
+    fn csv_without_header() {
+        let mut options = CsvOptions::new();
+        options.set_header(false);
+        options.set_delimiter('\t');
+        options.select_columns(vec![1]);
+        let tab = Tabular::from_csv("a\tb\nc\n".as_bytes(), &options).unwrap();
+        assert_eq!(
+            tab.to_latex().unwrap(),
+            "\\begin{tabular}{| c |}\n \\hline\nb \\\\\n \\hline\n \\\\\n \\hline\n\\end{tabular}\n"
+        );
+        options.select_columns(vec![0, 2]);
+        match Tabular::from_csv("a\tb\nc\n".as_bytes(), &options) {
+            Err(Error::InvalidCsv(_, message)) => assert!(message.contains("column 2")),
+            _ => panic!("The CSV has no third column"),
+        }
+        options.select_columns(vec!["a"]);
+        match Tabular::from_csv("a\n".as_bytes(), &options) {
+            Err(Error::InvalidStructure(_)) => (),
+            _ => panic!("A column can only be found by name with a header"),
+        }
+    }
+}
@@ -1,18 +1,17 @@
-/// File for displaying elements in LaTeX
-///
+//! File for displaying elements in LaTeX
 
 /// Returns the \frac of the two arguments
 pub fn frac<A: AsRef<str>, B: AsRef<str>>(top: A, bottom: B) -> String {
     format!(
         "\\frac{{{}}}{{{}}}",
-        top.as_ref().to_string(),
-        bottom.as_ref().to_string()
+        top.as_ref(),
+        bottom.as_ref()
     )
 }
 
 /// Texttt for a text
 pub fn texttt<T: AsRef<str>>(text: T) -> String {
-    format!("\\texttt{{{}}}", text.as_ref().to_string())
+    format!("\\texttt{{{}}}", text.as_ref())
 }
 
 /*
@@ -21,8 +20,8 @@ pub fn texttt<T: AsRef<str>>(text: T) -> String {
 pub fn single_arg<A: AsRef<str>, B: AsRef<str>>(name: A, arg: B) -> String {
     format!(
         "\\{}{{{}}}",
-        name.as_ref().to_string(),
-        arg.as_ref().to_string()
+        name.as_ref(),
+        arg.as_ref()
     )
 }
 */
@@ -34,7 +33,7 @@ pub fn single_arg<A: AsRef<str>, B: AsRef<str>>(name: A, arg: B) -> String {
 //     A: AsRef<str>,
 //     F: Fn(String) -> String,
 // {
-//     Box::new(move |arg: String| format!("\\{}{{{}}}", name.as_ref().to_string(), arg))
+//     Box::new(move |arg: String| format!("\\{}{{{}}}", name.as_ref(), arg))
 //         as Box<Fn(String) -> String>
 // }
 //
@@ -43,8 +42,8 @@ pub fn single_arg<A: AsRef<str>, B: AsRef<str>>(name: A, arg: B) -> String {
 pub fn two_args<A: AsRef<str>, B: AsRef<str>, C: AsRef<str>>(name: A, arg1: B, arg2: C) -> String {
     format!(
         "\\{}[{}]{{{}}}",
-        name.as_ref().to_string(),
-        arg1.as_ref().to_string(),
-        arg2.as_ref().to_string()
+        name.as_ref(),
+        arg1.as_ref(),
+        arg2.as_ref()
     )
 }
@@ -0,0 +1,491 @@
+/// File defining an in-memory LaTeX document
+/// The whole tree is kept in memory and rendered in one call
+///
+use assets::*;
+use bibliography::*;
+use core::*;
+use document_class::DocumentClass;
+use error::*;
+use labels::check_references;
+use latex_file::latex_file_from_writer;
+use outline::Outline;
+use packages::Packages;
+use preamble::Preamble;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use writable::*;
+
+#[derive(Clone, Default)]
+pub struct Document {
+    /// The class, title, authors and packages of the document
+    preamble: Preamble,
+    /// The content of the document
+    body: Vec<Core>,
+    /// The entries cited in the document, printed at the end
+    bibliography: Option<Bibliography>,
+    /// If true, the images are checked when the document is written to a file
+    check_assets: bool,
+}
+
+impl Document {
+    /// Returns a new empty Document
+    pub fn new() -> Self {
+        Document::default()
+    }
+
+    /// Change the class of the document (article, report, book, ...)
+    pub fn document_class(&mut self, class: DocumentClass) {
+        self.preamble.document_class(class);
+    }
+
+    /// Adds an option to the class of the document (a4paper, twocolumn, draft, ...)
+    pub fn add_class_option<T: AsRef<str>>(&mut self, option: T) {
+        self.preamble.document_class_mut().add_option(option);
+    }
+
+    /// Change the title of the document
+    pub fn title<T: AsRef<str>>(&mut self, new_title: T) {
+        self.preamble.title(new_title);
+    }
+
+    /// Change the title of the document, written verbatim (trusted LaTeX code)
+    pub fn title_raw<T: AsRef<str>>(&mut self, new_title: T) {
+        self.preamble.title_raw(new_title);
+    }
+
+    /// Change the author of the document
+    pub fn author<T: AsRef<str>>(&mut self, new_author: T) {
+        self.preamble.author(new_author);
+    }
+
+    /// Change the author of the document, written verbatim (e.g. with \and or \thanks)
+    pub fn author_raw<T: AsRef<str>>(&mut self, new_author: T) {
+        self.preamble.author_raw(new_author);
+    }
+
+    /// Removes the title of the document
+    pub fn remove_title(&mut self) {
+        self.preamble.remove_title();
+    }
+
+    /// Removes the author of the document
+    pub fn remove_author(&mut self) {
+        self.preamble.remove_author();
+    }
+
+    /// Adds a package
+    pub fn add_package<T: AsRef<str>>(&mut self, package: T) {
+        self.preamble.add_package(package);
+    }
+
+    /// Adds a package with options (e.g. \usepackage[utf8]{inputenc})
+    pub fn add_package_with_options<T1: AsRef<str>, T2: AsRef<str>>(
+        &mut self,
+        package: T1,
+        options: Vec<T2>,
+    ) {
+        self.preamble.add_package_with_options(package, options);
+    }
+
+    /// Returns the packages, to change their load order
+    pub fn packages_mut(&mut self) -> &mut Packages {
+        self.preamble.packages_mut()
+    }
+
+    /// Adds a list of packages
+    pub fn add_package_list<T: AsRef<str>>(&mut self, packages: Vec<T>) {
+        self.preamble.add_package_list(packages);
+    }
+
+    /// Writes a table of contents after the title
+    pub fn set_table_of_contents(&mut self, enabled: bool) {
+        self.preamble.set_table_of_contents(enabled);
+    }
+
+    /// Writes a list of figures after the title
+    pub fn set_list_of_figures(&mut self, enabled: bool) {
+        self.preamble.set_list_of_figures(enabled);
+    }
+
+    /// Writes a list of tables after the title
+    pub fn set_list_of_tables(&mut self, enabled: bool) {
+        self.preamble.set_list_of_tables(enabled);
+    }
+
+    /// Returns the outline of the document (titles, levels and numbers of the sections)
+    pub fn outline(&self) -> Outline {
+        Outline::new(&self.body, self.preamble.get_document_class())
+    }
+
+    /// Sets the bibliography of the document, printed at the end
+    pub fn set_bibliography(&mut self, bibliography: Bibliography) {
+        self.bibliography = Some(bibliography);
+    }
+
+    /// Returns the bibliography of the document
+    pub fn bibliography(&self) -> Option<&Bibliography> {
+        self.bibliography.as_ref()
+    }
+
+    /// Returns the bibliography of the document, to add entries
+    pub fn bibliography_mut(&mut self) -> Option<&mut Bibliography> {
+        self.bibliography.as_mut()
+    }
+
+    /// Removes the bibliography of the document
+    pub fn remove_bibliography(&mut self) {
+        self.bibliography = None;
+    }
+
+    /// Adds an element at the end of the body
+    pub fn add(&mut self, element: Core) {
+        self.body.push(element);
+    }
+
+    /// Adds a directory where the images are searched (\\graphicspath)
+    pub fn add_graphics_path<T: AsRef<str>>(&mut self, dir: T) {
+        self.preamble.add_graphics_path(dir);
+    }
+
+    /// Enables or disables the check of the images in write_to_file
+    pub fn set_check_assets(&mut self, enabled: bool) {
+        self.check_assets = enabled;
+    }
+
+    /// Returns the files of the images, for a document written in the directory
+    /// The missing images and the unsupported extensions are reported
+    pub fn check_assets<P: AsRef<Path>>(&self, dir: P) -> Result<Vec<Asset>> {
+        self.assets(dir).check(&self.body)
+    }
+
+    /// Copies or links the images of a document written in the directory
+    /// into the bundle directory, at the same place relative to the document
+    pub fn bundle_assets<P1: AsRef<Path>, P2: AsRef<Path>>(
+        &self,
+        dir: P1,
+        bundle: P2,
+        mode: BundleMode,
+    ) -> Result<Vec<Asset>> {
+        self.assets(dir).bundle(&self.body, bundle, mode)
+    }
+
+    fn assets<P: AsRef<Path>>(&self, dir: P) -> Assets {
+        let mut assets = Assets::new(dir);
+        assets.set_graphics_path(self.preamble.get_graphics_path().to_vec());
+        assets
+    }
+
+    /// Returns the preamble of the document
+    pub fn preamble(&self) -> &Preamble {
+        &self.preamble
+    }
+
+    /// Returns the content of the document
+    pub fn body(&self) -> &[Core] {
+        &self.body
+    }
+
+    /// Returns the content of the document, to modify it
+    pub fn body_mut(&mut self) -> &mut Vec<Core> {
+        &mut self.body
+    }
+
+    /// Renders the whole document (header, preamble, body and footer) into the writer
+    /// The packages needed by the elements of the body are added to the preamble
+    /// The duplicate labels, the dangling references, the missing citations
+    /// and the unused entries are reported before writing anything
+    pub fn render<W: Write + ?Sized>(&self, writer: &mut W) -> Result<()> {
+        check_references(&self.body)?;
+        match self.bibliography {
+            Some(ref bib) => bib.check_citations(&self.body)?,
+            None => check_no_citation(&self.body)?,
+        }
+        let mut preamble = self.preamble.clone();
+        self.add_packages(preamble.packages_mut());
+        if let Some(command) = self.bibliography.as_ref().and_then(|b| b.preamble_command()) {
+            preamble.add_command(command);
+        }
+        let mut f = latex_file_from_writer(writer)?;
+        f.set_preamble(preamble);
+        f.begin_document()?;
+        for element in self.body.iter() {
+            element.write_latex(&mut f)?;
+        }
+        if let Some(ref bib) = self.bibliography {
+            writeln!(f)?;
+            bib.write_latex(&mut f)?;
+        }
+        f.write_footer()
+    }
+
+    /// Renders the whole document into a String
+    pub fn render_to_string(&self) -> Result<String> {
+        self.to_latex()
+    }
+
+    /// Renders the whole document into a new file
+    /// The .bib file of the bibliography is written in the same directory
+    /// The images are checked first, if enabled
+    pub fn write_to_file<T: AsRef<str>>(&self, filename: T) -> Result<()> {
+        let dir = Path::new(filename.as_ref()).parent().unwrap_or_else(|| Path::new(""));
+        if self.check_assets {
+            self.check_assets(dir)?;
+        }
+        // Nothing is written if the document can not be rendered
+        let mut latex = Vec::new();
+        self.render(&mut latex)?;
+        File::create(filename.as_ref())?.write_all(&latex)?;
+        if let Some(ref bib) = self.bibliography {
+            let bib_file = dir.join(format!("{}.bib", bib.get_filename()));
+            bib.write_bib_file(bib_file.to_string_lossy())?;
+        }
+        Ok(())
+    }
+}
+
+impl Writable for Document {
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        self.render(buf)
+    }
+
+    fn add_packages(&self, packages: &mut Packages) {
+        for element in self.body.iter() {
+            element.add_packages(packages);
+        }
+        if let Some(ref bib) = self.bibliography {
+            bib.add_packages(packages);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_document {
+    use super::*;
+
+    #[test]
+    fn empty_document() {
+        let doc = Document::new();
+        assert_eq!(
+            doc.render_to_string().unwrap(),
+            "\\documentclass[a4paper,11pt]{article}\n\\date{}\n\\begin{document}\n\\end{document}\n"
+        );
+    }
+
+    #[test]
+    fn document_with_title() {
+        let mut doc = Document::new();
+        doc.title("Title");
+        doc.author("Quentin");
+        doc.add_package("graphicx");
+        assert_eq!(
+            doc.render_to_string().unwrap(),
+            "\\documentclass[a4paper,11pt]{article}\n\
+             \\usepackage{graphicx}\n\
+             \\title{Title}\n\
+             \\author{Quentin}\n\
+             \\date{}\n\
+             \\begin{document}\n\
+             \\maketitle\n\
+             \\end{document}\n"
+        );
+    }
+
+    #[test]
+    fn document_with_body() {
+        let mut doc = Document::new();
+        let mut sec = Core::section("Section1");
+        sec.add(Core::text("Hello")).unwrap();
+        doc.add(sec);
+        assert_eq!(doc.body().len(), 1);
+        assert_eq!(
+            doc.render_to_string().unwrap(),
+            "\\documentclass[a4paper,11pt]{article}\n\
+             \\date{}\n\
+             \\begin{document}\n\
+             \\section{Section1}\n\
+             Hello\n\
+             \\end{document}\n"
+        );
+    }
+
+    #[test]
+    fn report_document() {
+        let mut doc = Document::new();
+        doc.document_class(DocumentClass::report());
+        doc.add_class_option("a4paper");
+        doc.add_class_option("12pt");
+        assert!(doc
+            .render_to_string()
+            .unwrap()
+            .starts_with("\\documentclass[a4paper,12pt]{report}\n\\date{}\n"));
+    }
+
+    #[test]
+    fn report_with_chapters() {
+        let mut doc = Document::new();
+        doc.document_class(DocumentClass::report());
+        let mut preface = Core::chapter("Preface");
+        preface.set_starred(true);
+        doc.add(preface);
+        let mut chapter = Core::chapter("Introduction to the subject");
+        chapter.set_short_title("Introduction");
+        chapter.add(Core::section("Context")).unwrap();
+        doc.add(chapter);
+        assert_eq!(
+            doc.render_to_string().unwrap(),
+            "\\documentclass{report}\n\
+             \\date{}\n\
+             \\begin{document}\n\
+             \\chapter*{Preface}\n\n\
+             \\chapter[Introduction]{Introduction to the subject}\n\
+             \\section{Context}\n\n\n\
+             \\end{document}\n"
+        );
+    }
+
+    #[test]
+    fn required_packages() {
+        let mut doc = Document::new();
+        doc.add_package("hyperref");
+        doc.add_package_with_options("graphicx", vec!["draft"]);
+        let mut sec = Core::section("Section1");
+        sec.add(Core::graphic("rust_logo.jpg", "Logo")).unwrap();
+        sec.add(Core::code("test.c", "C")).unwrap();
+        doc.add(sec);
+        let out = doc.render_to_string().unwrap();
+        assert!(out.starts_with(
+            "\\documentclass[a4paper,11pt]{article}\n\
+             \\usepackage[draft]{graphicx}\n\
+             \\usepackage{listings}\n\
+             \\usepackage{hyperref}\n"
+        ));
+        assert_eq!(doc.preamble().get_packages().len(), 2);
+    }
+
+    #[test]
+    fn front_matter() {
+        let mut doc = Document::new();
+        doc.title("Title");
+        doc.set_table_of_contents(true);
+        doc.set_list_of_figures(true);
+        doc.set_list_of_tables(true);
+        doc.add(Core::section("Section1"));
+        assert!(doc.render_to_string().unwrap().contains(
+            "\\begin{document}\n\\maketitle\n\\tableofcontents\n\\listoffigures\n\\listoftables\n\\section{Section1}\n"
+        ));
+        assert_eq!(doc.outline().to_string(), "1 Section1\n");
+    }
+
+    #[test]
+    fn dangling_reference() {
+        let mut doc = Document::new();
+        let mut sec = Core::section("Introduction");
+        let intro = sec.set_label("intro").unwrap();
+        doc.add(Core::reference(&intro));
+        assert!(doc.render_to_string().is_err());
+        doc.add(sec);
+        assert!(doc
+            .render_to_string()
+            .unwrap()
+            .contains("\\ref{sec:intro}\\section{Introduction}\\label{sec:intro}\n"));
+    }
+
+    #[test]
+    fn bibliography() {
+        let mut doc = Document::new();
+        let mut bib = Bibliography::new(BibBackend::Biblatex);
+        let mut entry = BibEntry::book("knuth84");
+        entry.set_title("The \\TeX book");
+        let knuth = bib.add(entry).unwrap();
+        doc.add(Core::text("See "));
+        doc.add(bib.parencite(&knuth));
+        assert!(doc.render_to_string().is_err());
+        doc.set_bibliography(bib);
+        assert_eq!(
+            doc.render_to_string().unwrap(),
+            "\\documentclass[a4paper,11pt]{article}\n\
+             \\usepackage[backend=biber,style=numeric]{biblatex}\n\
+             \\addbibresource{references.bib}\n\
+             \\date{}\n\
+             \\begin{document}\n\
+             See \\parencite{knuth84}\n\
+             \\printbibliography\n\
+             \\end{document}\n"
+        );
+        doc.bibliography_mut()
+            .unwrap()
+            .add(BibEntry::misc("unused"))
+            .unwrap();
+        assert!(doc.render_to_string().is_ok());
+        doc.bibliography_mut().unwrap().set_allow_unused(false);
+        match doc.render_to_string() {
+            Err(Error::UnusedEntry(key)) => assert_eq!(key, "unused"),
+            _ => panic!("The unused entry should be reported"),
+        }
+    }
+
+    #[test]
+    fn write_bib_next_to_tex() {
+        let mut doc = Document::new();
+        let mut bib = Bibliography::new(BibBackend::Natbib);
+        bib.set_filename("document_refs");
+        let key = bib.add(BibEntry::misc("misc")).unwrap();
+        doc.add(bib.cite(&key));
+        doc.set_bibliography(bib);
+        doc.write_to_file("./tests_results/document/bibliography.tex")
+            .unwrap();
+        let bib = ::std::fs::read_to_string("./tests_results/document/document_refs.bib").unwrap();
+        assert_eq!(bib, "@misc{misc,\n}\n");
+    }
+
+    #[test]
+    fn failed_render_keeps_the_file() {
+        let path = ::std::env::temp_dir().join("tex_rs_failed_render.tex");
+        let path = path.to_string_lossy();
+        let mut doc = Document::new();
+        doc.add(Core::text("Kept"));
+        doc.write_to_file(&path).unwrap();
+        let mut broken = Document::new();
+        broken.add(Core::reference(&::labels::Label::new(::labels::LabelKind::Section, "nowhere")));
+        match broken.write_to_file(&path) {
+            Err(Error::DanglingReference(key)) => assert_eq!(key, "sec:nowhere"),
+            _ => panic!("The reference points to no label"),
+        }
+        let kept = ::std::fs::read_to_string(&*path).unwrap();
+        assert!(kept.contains("Kept"));
+    }
+
+    #[test]
+    fn check_images() {
+        let mut doc = Document::new();
+        doc.add_graphics_path("figures");
+        doc.add(Core::inline_graphic("missing_logo"));
+        assert!(doc
+            .render_to_string()
+            .unwrap()
+            .contains("\\usepackage{graphicx}\n\\graphicspath{{figures/}}\n"));
+        doc.write_to_file("./tests_results/document/images.tex").unwrap();
+        doc.set_check_assets(true);
+        match doc.write_to_file("./tests_results/document/images.tex") {
+            Err(Error::MissingAsset(name)) => assert_eq!(name, "missing_logo"),
+            _ => panic!("The missing image should be reported"),
+        }
+    }
+
+    #[test]
+    fn render_into_fmt_write() {
+        let mut doc = Document::new();
+        doc.add(Core::text("Hello"));
+        let mut out = String::new();
+        doc.write_to_fmt(&mut out).unwrap();
+        assert_eq!(out, doc.render_to_string().unwrap());
+    }
+
+    #[test]
+    fn render_twice() {
+        let mut doc = Document::new();
+        doc.add(Core::text("Hello"));
+        assert_eq!(doc.render_to_string().unwrap(), doc.render_to_string().unwrap());
+    }
+}
@@ -0,0 +1,167 @@
+/// File defining the class of a LaTeX document (article, report, book, ...)
+/// and its options (paper size, font size, ...)
+///
+use error::*;
+use std::io::Write;
+use writable::*;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DocumentClass {
+    /// The name of the class
+    name: String,
+    /// The options of the class (a4paper, 11pt, twocolumn, draft, ...)
+    options: Vec<String>,
+}
+
+impl Default for DocumentClass {
+    fn default() -> Self {
+        DocumentClass::with_options("article", vec!["a4paper", "11pt"])
+    }
+}
+
+impl DocumentClass {
+    /// Returns a new DocumentClass without options
+    pub fn new<T: AsRef<str>>(name: T) -> Self {
+        DocumentClass {
+            name: name.as_ref().to_string(),
+            options: Vec::new(),
+        }
+    }
+
+    /// Returns a new DocumentClass with a list of options
+    pub fn with_options<T1: AsRef<str>, T2: AsRef<str>>(name: T1, options: Vec<T2>) -> Self {
+        let mut class = DocumentClass::new(name);
+        class.add_option_list(options);
+        class
+    }
+
+    /// Returns the article class
+    pub fn article() -> Self {
+        DocumentClass::new("article")
+    }
+
+    /// Returns the report class
+    pub fn report() -> Self {
+        DocumentClass::new("report")
+    }
+
+    /// Returns the book class
+    pub fn book() -> Self {
+        DocumentClass::new("book")
+    }
+
+    /// Returns the beamer class
+    pub fn beamer() -> Self {
+        DocumentClass::new("beamer")
+    }
+
+    /// Returns the letter class
+    pub fn letter() -> Self {
+        DocumentClass::new("letter")
+    }
+
+    /// Returns the scrartcl class (KOMA-Script article)
+    pub fn scrartcl() -> Self {
+        DocumentClass::new("scrartcl")
+    }
+
+    /// Returns the memoir class
+    pub fn memoir() -> Self {
+        DocumentClass::new("memoir")
+    }
+
+    /// Returns the name of the class
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the options of the class
+    pub fn get_options(&self) -> &[String] {
+        &self.options
+    }
+
+    /// Adds an option to the class, if it is not already there
+    pub fn add_option<T: AsRef<str>>(&mut self, option: T) {
+        let option = option.as_ref().to_string();
+        if !self.options.contains(&option) {
+            self.options.push(option);
+        }
+    }
+
+    /// Adds a list of options to the class
+    pub fn add_option_list<T: AsRef<str>>(&mut self, options: Vec<T>) {
+        for option in options.iter() {
+            self.add_option(option);
+        }
+    }
+
+    /// Removes an option of the class
+    pub fn remove_option<T: AsRef<str>>(&mut self, option: T) {
+        self.options.retain(|o| o != option.as_ref());
+    }
+
+    /// Returns true if the class has chapters (report, book, memoir, ...)
+    pub fn has_chapters(&self) -> bool {
+        matches!(
+            self.name.as_ref(),
+            "report" | "book" | "memoir" | "scrreprt" | "scrbook"
+        )
+    }
+}
+
+impl Writable for DocumentClass {
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        if self.options.is_empty() {
+            writeln!(buf, "\\documentclass{{{}}}", self.name)?;
+        } else {
+            writeln!(
+                buf,
+                "\\documentclass[{}]{{{}}}",
+                self.options.join(","),
+                self.name
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests_document_class {
+    use super::*;
+
+    #[test]
+    fn default_class() {
+        assert_eq!(
+            DocumentClass::default().to_latex().unwrap(),
+            "\\documentclass[a4paper,11pt]{article}\n"
+        );
+    }
+
+    #[test]
+    fn class_without_options() {
+        assert_eq!(
+            DocumentClass::report().to_latex().unwrap(),
+            "\\documentclass{report}\n"
+        );
+    }
+
+    #[test]
+    fn class_with_options() {
+        let mut class = DocumentClass::with_options("scrartcl", vec!["a5paper", "draft"]);
+        class.add_option("twocolumn");
+        class.add_option("draft");
+        class.remove_option("a5paper");
+        assert_eq!(
+            class.to_latex().unwrap(),
+            "\\documentclass[draft,twocolumn]{scrartcl}\n"
+        );
+    }
+
+    #[test]
+    fn chapters() {
+        assert!(DocumentClass::report().has_chapters());
+        assert!(DocumentClass::book().has_chapters());
+        assert!(!DocumentClass::article().has_chapters());
+        assert!(!DocumentClass::new("beamer").has_chapters());
+    }
+}
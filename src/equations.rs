@@ -1,9 +1,13 @@
-use latex_file::*;
+use error::*;
+use escape::Text;
+use expr::Expr;
+use labels::*;
 use operators::*;
 /// File defining the structure of an equation
 ///
-use std::io::BufWriter;
+use packages::Packages;
 use std::io::Write;
+use std::ops::{Deref, DerefMut};
 use symbols::*;
 use writable::*;
 
@@ -12,54 +16,427 @@ pub enum EquationElements {
     Text(String),
     Symb(Symbols),
     Operator(Operators),
+    /// An expression, built with Expr or the math! macro
+    Expr(Expr),
+    /// The point where the lines of an align are aligned (&)
+    AlignPoint,
 }
 
 impl EquationElements {
     fn get_enum(elem: String) -> Self {
-        if is_op(&elem) {
-            EquationElements::Symb(Symbols::get_symbol(elem))
-        } else {
-            EquationElements::Text(elem)
+        if elem == "&" {
+            return EquationElements::AlignPoint;
+        }
+        match Symbols::get_symbol(elem.clone()) {
+            Ok(symb) => EquationElements::Symb(symb),
+            Err(_) => EquationElements::Text(elem),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Equation {
+    /// The elements of the equation
+    elements: Vec<EquationElements>,
+    /// The label of the equation
+    label: Option<Label>,
+}
+
+impl Equation {
+    /// Returns a new Equation
+    pub fn new(elements: Vec<EquationElements>) -> Self {
+        Equation {
+            elements,
+            label: None,
         }
     }
+
+    /// Labels the equation, and returns the handle to refer to it
+    pub fn set_label<T: AsRef<str>>(&mut self, name: T) -> Label {
+        let label = Label::new(LabelKind::Equation, name);
+        self.label = Some(label.clone());
+        label
+    }
+
+    /// Returns the label of the equation
+    pub fn get_label(&self) -> Option<&Label> {
+        self.label.as_ref()
+    }
+}
+
+impl Deref for Equation {
+    type Target = Vec<EquationElements>;
+
+    fn deref(&self) -> &Vec<EquationElements> {
+        &self.elements
+    }
+}
+
+impl DerefMut for Equation {
+    fn deref_mut(&mut self) -> &mut Vec<EquationElements> {
+        &mut self.elements
+    }
 }
 
-pub type Equation = Vec<EquationElements>;
+impl From<Expr> for Equation {
+    fn from(expr: Expr) -> Self {
+        Equation::new(vec![EquationElements::Expr(expr)])
+    }
+}
 
 /// Returns an Equation from a vector of str
 pub fn new_equation<T: AsRef<str>>(vec: &[T]) -> Equation {
-    vec.iter()
-        .map(|s| EquationElements::get_enum(s.as_ref().to_string()))
-        .collect()
+    Equation::new(
+        vec.iter()
+            .map(|s| EquationElements::get_enum(s.as_ref().to_string()))
+            .collect(),
+    )
 }
 
 impl Writable for EquationElements {
-    fn write_latex(&self, file: &mut LatexFile) {
-        let mut writer = BufWriter::new(file);
-        self.write_to_buffer(&mut writer);
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        match *self {
+            EquationElements::Text(ref s) => write!(buf, "{} ", s)?,
+            EquationElements::Symb(ref s) => write!(buf, "{} ", s.latex_code())?,
+            EquationElements::Operator(ref s) => write!(buf, "{} ", s.latex_code())?,
+            EquationElements::Expr(ref e) => write!(buf, "{} ", e)?,
+            EquationElements::AlignPoint => write!(buf, "& ")?,
+        }
+        Ok(())
     }
 
-    fn write_to_buffer(&self, mut buf: &mut BufWriter<&mut LatexFile>) {
+    fn add_packages(&self, packages: &mut Packages) {
         match *self {
-            EquationElements::Text(ref s) => write!(&mut buf, "{} ", s).unwrap(),
-            EquationElements::Symb(ref s) => write!(&mut buf, "{} ", s.latex_code()).unwrap(),
-            EquationElements::Operator(ref s) => write!(&mut buf, "{} ", s.latex_code()).unwrap(),
+            EquationElements::Symb(ref s) => {
+                if let Some(package) = s.get_package() {
+                    packages.add_package(package);
+                }
+            }
+            EquationElements::Operator(ref op) => op.add_packages(packages),
+            EquationElements::Expr(ref e) => e.add_packages(packages),
+            _ => (),
         }
     }
 }
 
 impl Writable for Equation {
-    fn write_latex(&self, file: &mut LatexFile) {
-        let mut writer = BufWriter::new(file);
-        self.write_to_buffer(&mut writer);
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        writeln!(buf, "\\begin{{equation}}")?;
+        if let Some(ref label) = self.label {
+            label.write_to_buffer(buf)?;
+            writeln!(buf)?;
+        }
+        write!(buf, "\\displaystyle ")?;
+        for item in self.iter() {
+            item.write_to_buffer(buf)?;
+        }
+        writeln!(buf, "\n\\end{{equation}}",)?;
+        Ok(())
     }
 
-    fn write_to_buffer(&self, mut buf: &mut BufWriter<&mut LatexFile>) {
-        write!(&mut buf, "\\begin{{equation}}\n\\displaystyle ",).unwrap();
+    fn add_packages(&self, packages: &mut Packages) {
         for item in self.iter() {
-            item.write_to_buffer(&mut buf);
+            item.add_packages(packages);
+        }
+    }
+}
+
+/// The number of a line of a multiline equation
+#[derive(Clone, Debug, PartialEq)]
+pub enum LineNumber {
+    /// Numbered if the environment is
+    Auto,
+    /// Not numbered (\nonumber)
+    NoNumber,
+    /// Numbered with the text instead of the counter (\tag)
+    Tag(Text),
+}
+
+/// A line of a multiline equation, with its own number and label
+#[derive(Clone)]
+pub struct EquationLine {
+    elements: Vec<EquationElements>,
+    number: LineNumber,
+    label: Option<Label>,
+}
+
+impl EquationLine {
+    /// Returns a new line, the alignment points are EquationElements::AlignPoint (or "&")
+    pub fn new(elements: Vec<EquationElements>) -> Self {
+        EquationLine {
+            elements,
+            number: LineNumber::Auto,
+            label: None,
+        }
+    }
+
+    /// Removes the number of the line (\nonumber)
+    pub fn set_nonumber(&mut self) {
+        self.number = LineNumber::NoNumber;
+    }
+
+    /// Numbers the line with the text (\tag)
+    pub fn set_tag<T: AsRef<str>>(&mut self, tag: T) {
+        self.number = LineNumber::Tag(Text::new(tag));
+    }
+
+    /// Returns the number of the line
+    pub fn get_number(&self) -> &LineNumber {
+        &self.number
+    }
+
+    /// Labels the line, and returns the handle to refer to it
+    pub fn set_label<T: AsRef<str>>(&mut self, name: T) -> Label {
+        let label = Label::new(LabelKind::Equation, name);
+        self.label = Some(label.clone());
+        label
+    }
+
+    /// Returns the label of the line
+    pub fn get_label(&self) -> Option<&Label> {
+        self.label.as_ref()
+    }
+
+    /// Writes the content of the line, with its label and its number
+    /// The alignment points are left out of the environments with a single column
+    fn write_line<W: Write + ?Sized>(&self, buf: &mut W, aligned: bool) -> Result<()> {
+        let mut items = Vec::new();
+        for element in self.elements.iter() {
+            if !aligned && matches!(*element, EquationElements::AlignPoint) {
+                continue;
+            }
+            items.push(element.to_latex()?.trim().to_string());
+        }
+        write!(buf, "{}", items.join(" "))?;
+        if let Some(ref label) = self.label {
+            write!(buf, " ")?;
+            label.write_to_buffer(buf)?;
+        }
+        match self.number {
+            LineNumber::Auto => (),
+            LineNumber::NoNumber => write!(buf, " \\nonumber")?,
+            LineNumber::Tag(ref tag) => write!(buf, " \\tag{{{}}}", tag)?,
+        }
+        Ok(())
+    }
+}
+
+/// The line is aligned at the first relation of the expression: a &= b
+impl From<Expr> for EquationLine {
+    fn from(expr: Expr) -> Self {
+        match expr {
+            Expr::Relation(first, rest) => {
+                let mut elements = vec![EquationElements::Expr(*first)];
+                for (i, (symbol, side)) in rest.into_iter().enumerate() {
+                    if i == 0 {
+                        elements.push(EquationElements::AlignPoint);
+                    }
+                    elements.push(EquationElements::Symb(symbol));
+                    elements.push(EquationElements::Expr(side));
+                }
+                EquationLine::new(elements)
+            }
+            expr => EquationLine::new(vec![EquationElements::Expr(expr)]),
+        }
+    }
+}
+
+impl From<Equation> for EquationLine {
+    fn from(equation: Equation) -> Self {
+        EquationLine::new(equation.elements)
+    }
+}
+
+/// The environments of amsmath for the equations on several lines
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MultilineKind {
+    /// Lines aligned at their alignment points, each one numbered (align)
+    Align,
+    /// Centered lines, each one numbered, the alignment points are left out (gather)
+    Gather,
+    /// A long equation broken into lines, with a single number,
+    /// the alignment points are left out (multline)
+    Multline,
+    /// Aligned lines in an equation, with a single number (split)
+    Split,
+}
+
+impl MultilineKind {
+    /// Returns the name of the environment
+    pub fn environment(&self) -> &'static str {
+        match *self {
+            MultilineKind::Align => "align",
+            MultilineKind::Gather => "gather",
+            MultilineKind::Multline => "multline",
+            MultilineKind::Split => "split",
+        }
+    }
+
+    /// Returns true if the lines are aligned at their alignment points (&)
+    pub fn is_aligned(&self) -> bool {
+        matches!(*self, MultilineKind::Align | MultilineKind::Split)
+    }
+}
+
+/// An equation written on several lines
+#[derive(Clone)]
+pub struct MultilineEquation {
+    kind: MultilineKind,
+    lines: Vec<EquationLine>,
+    /// The starred environment is used if false
+    numbered: bool,
+    /// The label of the whole equation (split only)
+    label: Option<Label>,
+}
+
+impl MultilineEquation {
+    /// Returns a new numbered MultilineEquation
+    pub fn new(kind: MultilineKind, lines: Vec<EquationLine>) -> Self {
+        MultilineEquation {
+            kind,
+            lines,
+            numbered: true,
+            label: None,
+        }
+    }
+
+    /// Returns an align with a line for each relation of the chain:
+    /// a = b = c gives a &= b \\ &= c
+    pub fn derivation(expr: Expr) -> Self {
+        let lines = match expr {
+            Expr::Relation(first, rest) => rest
+                .into_iter()
+                .enumerate()
+                .map(|(i, (symbol, side))| {
+                    let mut elements = Vec::new();
+                    if i == 0 {
+                        elements.push(EquationElements::Expr((*first).clone()));
+                    }
+                    elements.push(EquationElements::AlignPoint);
+                    elements.push(EquationElements::Symb(symbol));
+                    elements.push(EquationElements::Expr(side));
+                    EquationLine::new(elements)
+                })
+                .collect(),
+            expr => vec![EquationLine::from(expr)],
+        };
+        MultilineEquation::new(MultilineKind::Align, lines)
+    }
+
+    /// Adds a line at the end of the equation
+    pub fn add(&mut self, line: EquationLine) {
+        self.lines.push(line);
+    }
+
+    /// Returns the lines of the equation
+    pub fn get_lines(&self) -> &[EquationLine] {
+        &self.lines
+    }
+
+    /// Returns the lines of the equation, to number or label them
+    pub fn get_lines_mut(&mut self) -> &mut [EquationLine] {
+        &mut self.lines
+    }
+
+    /// Changes if the equation is numbered (align or align*)
+    pub fn set_numbered(&mut self, numbered: bool) {
+        self.numbered = numbered;
+    }
+
+    /// Labels the whole equation (only for a split, the other ones label their lines),
+    /// and returns the handle to refer to it
+    pub fn set_label<T: AsRef<str>>(&mut self, name: T) -> Label {
+        let label = Label::new(LabelKind::Equation, name);
+        self.label = Some(label.clone());
+        label
+    }
+
+    /// Returns the label of the whole equation
+    pub fn get_label(&self) -> Option<&Label> {
+        self.label.as_ref()
+    }
+
+    /// Returns the labels of the equation and of its lines
+    pub fn get_labels(&self) -> Vec<&Label> {
+        self.label
+            .iter()
+            .chain(self.lines.iter().filter_map(|l| l.get_label()))
+            .collect()
+    }
+
+    /// Checks that the numbers and the labels can be written in the environment
+    fn check(&self) -> Result<()> {
+        let numbered_lines = self
+            .lines
+            .iter()
+            .filter(|l| l.label.is_some() || l.number != LineNumber::Auto)
+            .count();
+        if self.kind == MultilineKind::Split {
+            if numbered_lines > 0 {
+                return Err(Error::InvalidStructure(
+                    "The lines of a split share the number of the equation".to_string(),
+                ));
+            }
+        } else if self.label.is_some() {
+            return Err(Error::InvalidStructure(format!(
+                "A {} can not be labelled as a whole, label its lines",
+                self.kind.environment()
+            )));
+        }
+        if self.kind == MultilineKind::Multline && numbered_lines > 1 {
+            return Err(Error::InvalidStructure(
+                "A multline has a single number".to_string(),
+            ));
+        }
+        for line in self.lines.iter().filter(|l| l.label.is_some()) {
+            let tagged = matches!(line.number, LineNumber::Tag(_));
+            if !tagged && (!self.numbered || line.number == LineNumber::NoNumber) {
+                return Err(Error::InvalidStructure(
+                    "A line without number can not be labelled".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Writable for MultilineEquation {
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        self.check()?;
+        let star = if self.numbered { "" } else { "*" };
+        if self.kind == MultilineKind::Split {
+            writeln!(buf, "\\begin{{equation{}}}", star)?;
+            if let Some(ref label) = self.label {
+                label.write_to_buffer(buf)?;
+                writeln!(buf)?;
+            }
+            writeln!(buf, "\\begin{{split}}")?;
+        } else {
+            writeln!(buf, "\\begin{{{}{}}}", self.kind.environment(), star)?;
+        }
+        for (i, line) in self.lines.iter().enumerate() {
+            line.write_line(buf, self.kind.is_aligned())?;
+            if i + 1 < self.lines.len() {
+                write!(buf, " \\\\")?;
+            }
+            writeln!(buf)?;
+        }
+        if self.kind == MultilineKind::Split {
+            writeln!(buf, "\\end{{split}}")?;
+            writeln!(buf, "\\end{{equation{}}}", star)?;
+        } else {
+            writeln!(buf, "\\end{{{}{}}}", self.kind.environment(), star)?;
+        }
+        Ok(())
+    }
+
+    fn add_packages(&self, packages: &mut Packages) {
+        packages.add_package("amsmath");
+        for line in self.lines.iter() {
+            for element in line.elements.iter() {
+                element.add_packages(packages);
+            }
         }
-        writeln!(&mut buf, "\n\\end{{equation}}",).unwrap();
     }
 }
 
@@ -67,6 +444,7 @@ impl Writable for Equation {
 mod tests_equations {
     use super::*;
     use displays::*;
+    use latex_file::*;
 
     #[test]
     fn strings_to_equation() {
@@ -77,31 +455,113 @@ mod tests_equations {
 
     #[test]
     fn simple_write() {
-        let mut f = new_latex_file("./tests_results/equations/simple_eq.tex");
-        f.begin_document();
+        let mut f = new_latex_file("./tests_results/equations/simple_eq.tex").unwrap();
+        f.begin_document().unwrap();
         let vec = vec!["a", "=", "b"];
         let eq = new_equation(&vec);
-        eq.write_latex(&mut f);
-        f.write_footer();
+        eq.write_latex(&mut f).unwrap();
+        f.write_footer().unwrap();
     }
 
     #[test]
     fn multiple_equals() {
-        let mut f = new_latex_file("./tests_results/equations/multiple_equals.tex");
-        f.begin_document();
-        let eq = new_equation(&vec!["1", ">=", "0", "=", "x"]);
-        eq.write_latex(&mut f);
-        f.write_footer();
+        let mut f = new_latex_file("./tests_results/equations/multiple_equals.tex").unwrap();
+        f.begin_document().unwrap();
+        let eq = new_equation(&["1", ">=", "0", "=", "x"]);
+        eq.write_latex(&mut f).unwrap();
+        f.write_footer().unwrap();
     }
 
     #[test]
     fn multiple_frac() {
-        let mut f = new_latex_file("./tests_results/equations/frac.tex");
-        f.begin_document();
+        let mut f = new_latex_file("./tests_results/equations/frac.tex").unwrap();
+        f.begin_document().unwrap();
         let v = vec![frac("4", "2"), "=".to_string(), "2".to_string()];
         let eq = new_equation(&v);
-        eq.write_latex(&mut f);
-        f.write_footer();
+        eq.write_latex(&mut f).unwrap();
+        f.write_footer().unwrap();
+    }
+
+    #[test]
+    fn table_symbols() {
+        let eq = new_equation(&["x", "∈", "RR", "==>", "x", "~~", "0"]);
+        let latex = eq.to_latex().unwrap();
+        assert!(latex.contains("x  \\in   \\mathbb{R}   \\implies  x  \\approx  0"));
+        let mut packages = Packages::new();
+        eq.add_packages(&mut packages);
+        assert!(packages.contains("amssymb") && packages.contains("amsmath"));
+    }
+
+    #[test]
+    fn derivation() {
+        let mut eq = MultilineEquation::derivation(math!(
+            (a + b) ^ 2 = (a + b) * (a + b) = a ^ 2 + 2 * a * b + b ^ 2
+        ));
+        let first = eq.get_lines_mut()[0].set_label("expand");
+        eq.get_lines_mut()[0].set_nonumber();
+        assert!(eq.to_latex().is_err());
+        eq.get_lines_mut()[0].set_tag("*");
+        eq.get_lines_mut()[1].set_nonumber();
+        assert_eq!(first.key(), "eq:expand");
+        assert_eq!(
+            eq.to_latex().unwrap(),
+            "\\begin{align}\n\
+             (a + b)^{2} & = (a + b) (a + b) \\label{eq:expand} \\tag{*} \\\\\n\
+             & = a^{2} + 2 a b + b^{2} \\nonumber\n\
+             \\end{align}\n"
+        );
+        let mut packages = Packages::new();
+        eq.add_packages(&mut packages);
+        assert!(packages.contains("amsmath"));
+    }
+
+    #[test]
+    fn multiline_environments() {
+        let lines = vec![
+            EquationLine::from(new_equation(&["x", "&", "=", "1"])),
+            EquationLine::from(math!(y = 2)),
+        ];
+        let mut gather = MultilineEquation::new(MultilineKind::Gather, lines.clone());
+        gather.set_numbered(false);
+        assert_eq!(
+            gather.to_latex().unwrap(),
+            "\\begin{gather*}\nx = 1 \\\\\ny = 2\n\\end{gather*}\n"
+        );
+        gather.get_lines_mut()[1].set_label("y");
+        assert!(gather.to_latex().is_err());
+        gather.set_numbered(true);
+        gather.set_label("both");
+        assert!(gather.to_latex().is_err());
+
+        let mut split = MultilineEquation::new(MultilineKind::Split, lines.clone());
+        split.set_label("split");
+        assert_eq!(
+            split.to_latex().unwrap(),
+            "\\begin{equation}\n\\label{eq:split}\n\\begin{split}\nx & = 1 \\\\\ny & = 2\n\\end{split}\n\\end{equation}\n"
+        );
+        split.get_lines_mut()[0].set_tag("1a");
+        assert!(split.to_latex().is_err());
+
+        let mut multline = MultilineEquation::new(MultilineKind::Multline, lines);
+        multline.get_lines_mut()[0].set_label("a");
+        multline.get_lines_mut()[1].set_label("b");
+        assert!(multline.to_latex().is_err());
+        multline.get_lines_mut()[0] = EquationLine::from(math!(x));
+        assert!(multline
+            .to_latex()
+            .unwrap()
+            .starts_with("\\begin{multline}\nx \\\\\ny = 2 \\label{eq:b}\n"));
+        assert_eq!(multline.get_labels().len(), 1);
+    }
+
+    #[test]
+    fn expression() {
+        let mut eq = Equation::from(math!(sum(i = 0..n, x[i]^2) / n <= sqrt(y)));
+        eq.set_label("mean");
+        assert_eq!(
+            eq.to_latex().unwrap(),
+            "\\begin{equation}\n\\label{eq:mean}\n\\displaystyle \\frac{\\sum_{i = 0}^{n} x_{i}^{2}}{n} \\leq \\sqrt{y} \n\\end{equation}\n"
+        );
     }
 
 }
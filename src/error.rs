@@ -0,0 +1,102 @@
+/// File defining the errors returned by tex_rs
+///
+use std::error;
+use std::fmt;
+use std::io;
+use std::result;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The LaTeX code could not be written in the sink
+    Io(io::Error),
+    /// A file needed by the document (text, code, ...) could not be read
+    MissingFile(String, io::Error),
+    /// The structure of the document is not valid (adding content to a text, empty tabular, ...)
+    InvalidStructure(String),
+    /// The symbol is not a known symbol
+    UnknownSymbol(String),
+    /// Two elements have the same label
+    DuplicateLabel(String),
+    /// A reference points to a label which is not in the document
+    DanglingReference(String),
+    /// Two entries of the bibliography have the same key
+    DuplicateEntry(String),
+    /// A citation points to a key which is not in the bibliography
+    MissingCitation(String),
+    /// An entry of the bibliography is never cited
+    UnusedEntry(String),
+    /// A .bib file could not be parsed (line, message)
+    InvalidBibtex(usize, String),
+    /// A CSV file could not be parsed (line, message)
+    InvalidCsv(usize, String),
+    /// An image of the document was not found
+    MissingAsset(String),
+    /// An image of the document has an extension which graphicx can not include
+    UnsupportedAsset(String),
+    /// The images can not be bundled into the directory (the directory of the document, ...)
+    InvalidBundle(String),
+}
+
+/// Result type of tex_rs
+pub type Result<T> = result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref e) => write!(f, "IO error: {}", e),
+            Error::MissingFile(ref filename, ref e) => {
+                write!(f, "Could not read the file {}: {}", filename, e)
+            }
+            Error::InvalidStructure(ref msg) => write!(f, "Invalid structure: {}", msg),
+            Error::UnknownSymbol(ref symb) => {
+                write!(f, "The symbol {} is not a valid symbol", symb)
+            }
+            Error::DuplicateLabel(ref key) => write!(f, "The label {} is defined twice", key),
+            Error::DanglingReference(ref key) => {
+                write!(f, "The reference to {} points to no label", key)
+            }
+            Error::DuplicateEntry(ref key) => {
+                write!(f, "The bibliography has two entries with the key {}", key)
+            }
+            Error::MissingCitation(ref key) => {
+                write!(f, "The key {} is cited but is not in the bibliography", key)
+            }
+            Error::UnusedEntry(ref key) => {
+                write!(f, "The entry {} of the bibliography is never cited", key)
+            }
+            Error::InvalidBibtex(line, ref msg) => {
+                write!(f, "Invalid BibTeX at line {}: {}", line, msg)
+            }
+            Error::InvalidCsv(line, ref msg) => write!(f, "Invalid CSV at line {}: {}", line, msg),
+            Error::MissingAsset(ref filename) => write!(f, "The image {} was not found", filename),
+            Error::UnsupportedAsset(ref filename) => {
+                write!(f, "The image {} has an unsupported extension", filename)
+            }
+            Error::InvalidBundle(ref dir) => {
+                write!(f, "The images can not be bundled into {}", dir)
+            }
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref e) => Some(e),
+            Error::MissingFile(_, ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<fmt::Error> for Error {
+    fn from(e: fmt::Error) -> Self {
+        Error::Io(io::Error::other(e))
+    }
+}
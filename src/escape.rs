@@ -0,0 +1,137 @@
+/// File defining the escaping of the LaTeX special characters
+///
+use error::*;
+use std::fmt;
+use std::io::Write;
+use writable::*;
+
+/// Returns the text with every LaTeX special character escaped
+pub fn escape_latex<T: AsRef<str>>(text: T) -> String {
+    let text = text.as_ref();
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '{' => escaped.push_str("\\{"),
+            '}' => escaped.push_str("\\}"),
+            '$' => escaped.push_str("\\$"),
+            '&' => escaped.push_str("\\&"),
+            '%' => escaped.push_str("\\%"),
+            '#' => escaped.push_str("\\#"),
+            '_' => escaped.push_str("\\_"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// A text which is escaped when written, unless it is raw (trusted LaTeX code)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Text {
+    /// The content, as given by the user
+    content: String,
+    /// If true, the content is written verbatim
+    raw: bool,
+}
+
+impl Text {
+    /// Returns a new Text, escaped when written
+    pub fn new<T: AsRef<str>>(content: T) -> Self {
+        Text {
+            content: content.as_ref().to_string(),
+            raw: false,
+        }
+    }
+
+    /// Returns a new raw Text, written verbatim
+    pub fn raw<T: AsRef<str>>(content: T) -> Self {
+        Text {
+            content: content.as_ref().to_string(),
+            raw: true,
+        }
+    }
+
+    /// Returns the content, as given by the user
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    /// Returns true if the content is written verbatim
+    pub fn is_raw(&self) -> bool {
+        self.raw
+    }
+
+    /// Enables or disables the escaping of the content
+    pub fn set_raw(&mut self, raw: bool) {
+        self.raw = raw;
+    }
+}
+
+impl fmt::Display for Text {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.raw {
+            write!(f, "{}", self.content)
+        } else {
+            write!(f, "{}", escape_latex(&self.content))
+        }
+    }
+}
+
+impl Writable for Text {
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        write!(buf, "{}", self)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests_escape {
+    use super::*;
+
+    #[test]
+    fn nothing_to_escape() {
+        assert_eq!(escape_latex("To be or not to be"), "To be or not to be");
+    }
+
+    #[test]
+    fn escape_every_special_character() {
+        assert_eq!(escape_latex("%"), "\\%");
+        assert_eq!(escape_latex("&"), "\\&");
+        assert_eq!(escape_latex("_"), "\\_");
+        assert_eq!(escape_latex("#"), "\\#");
+        assert_eq!(escape_latex("$"), "\\$");
+        assert_eq!(escape_latex("{"), "\\{");
+        assert_eq!(escape_latex("}"), "\\}");
+        assert_eq!(escape_latex("~"), "\\textasciitilde{}");
+        assert_eq!(escape_latex("^"), "\\textasciicircum{}");
+        assert_eq!(escape_latex("\\"), "\\textbackslash{}");
+    }
+
+    #[test]
+    fn escape_command_injection() {
+        assert_eq!(
+            escape_latex("\\input{/etc/passwd}"),
+            "\\textbackslash{}input\\{/etc/passwd\\}"
+        );
+    }
+
+    #[test]
+    fn escape_in_sentence() {
+        assert_eq!(
+            escape_latex("50% of R&D_budget costs $10"),
+            "50\\% of R\\&D\\_budget costs \\$10"
+        );
+    }
+
+    #[test]
+    fn text_display() {
+        assert_eq!(Text::new("a_b").to_string(), "a\\_b");
+        assert_eq!(Text::raw("\\textbf{a}").to_string(), "\\textbf{a}");
+        let mut t = Text::new("$x$");
+        t.set_raw(true);
+        assert_eq!(t.to_string(), "$x$");
+        assert_eq!(t.content(), "$x$");
+    }
+}
@@ -0,0 +1,1230 @@
+/// File defining the math expressions: a tree of numbers, variables, operations,
+/// fractions, powers, ... written with the parentheses needed by the precedence
+/// of the operators, and the math! macro building them from a Rust-like syntax
+///
+use error::*;
+use escape::escape_latex;
+use matrix::Matrix;
+use operators::VarOrImm;
+use packages::Packages;
+use std::fmt;
+use std::io::Write;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use symbols::{MathSymbol, SymbolKind, Symbols};
+use writable::*;
+
+/// The functions written with their own command (\sin, \log, ...)
+const FUNCTIONS: [&str; 16] = [
+    "sin", "cos", "tan", "sinh", "cosh", "tanh", "arcsin", "arccos", "arctan", "log", "ln", "exp",
+    "det", "gcd", "min", "max",
+];
+
+/// The binary operations
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+}
+
+/// The delimiters of a group
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Delimiter {
+    /// ( )
+    Paren,
+    /// [ ]
+    Bracket,
+    /// \{ \}
+    Brace,
+    /// | |
+    Abs,
+    /// \| \|
+    Norm,
+}
+
+impl Delimiter {
+    /// Returns the left and the right delimiters
+    fn codes(&self) -> (&'static str, &'static str) {
+        match *self {
+            Delimiter::Paren => ("(", ")"),
+            Delimiter::Bracket => ("[", "]"),
+            Delimiter::Brace => ("\\{", "\\}"),
+            Delimiter::Abs => ("|", "|"),
+            Delimiter::Norm => ("\\|", "\\|"),
+        }
+    }
+}
+
+/// The big operators, with bounds and a body
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BigOp {
+    Sum,
+    Product,
+    /// \bigcup
+    Union,
+    /// \bigcap
+    Intersection,
+    /// An integral over n variables (\int, \iint, \iiint, then \idotsint)
+    Integral(usize),
+    /// \oint
+    ContourIntegral,
+    /// \lim, the lower bound is the value the variable tends to
+    Limit,
+    Max,
+    Min,
+    Sup,
+    Inf,
+    ArgMin,
+    ArgMax,
+}
+
+impl BigOp {
+    /// Returns the LaTeX command of the operator
+    pub fn command(&self) -> &'static str {
+        match *self {
+            BigOp::Sum => "\\sum",
+            BigOp::Product => "\\prod",
+            BigOp::Union => "\\bigcup",
+            BigOp::Intersection => "\\bigcap",
+            BigOp::Integral(1) => "\\int",
+            BigOp::Integral(2) => "\\iint",
+            BigOp::Integral(3) => "\\iiint",
+            BigOp::Integral(_) => "\\idotsint",
+            BigOp::ContourIntegral => "\\oint",
+            BigOp::Limit => "\\lim",
+            BigOp::Max => "\\max",
+            BigOp::Min => "\\min",
+            BigOp::Sup => "\\sup",
+            BigOp::Inf => "\\inf",
+            BigOp::ArgMin => "\\operatorname*{arg\\,min}",
+            BigOp::ArgMax => "\\operatorname*{arg\\,max}",
+        }
+    }
+
+    /// Returns true for the integrals, which end with the differentials (dx)
+    pub fn is_integral(&self) -> bool {
+        matches!(*self, BigOp::Integral(_) | BigOp::ContourIntegral)
+    }
+}
+
+/// Where the bounds of a big operator are written
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LimitsPlacement {
+    /// As LaTeX does: below and above in the equations, on the side in the text
+    Auto,
+    /// Always below and above (\limits)
+    Limits,
+    /// Always on the side, like in the text (\nolimits)
+    Inline,
+    /// The operator and its bounds are written as in the equations (\displaystyle)
+    Display,
+}
+
+/// The bounds of a big operator: the variables, the range or the set they cover
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bounds {
+    variables: Vec<Expr>,
+    /// The start of the range (the value the variable tends to for a limit)
+    lower: Option<Expr>,
+    upper: Option<Expr>,
+    /// The set covered by the variables (x \in S, or the domain of an integral)
+    domain: Option<Expr>,
+    placement: LimitsPlacement,
+}
+
+impl Bounds {
+    /// Returns empty bounds
+    pub fn new() -> Self {
+        Bounds {
+            variables: Vec::new(),
+            lower: None,
+            upper: None,
+            domain: None,
+            placement: LimitsPlacement::Auto,
+        }
+    }
+
+    /// Returns the bounds made of the variable only: \sum_{i}, \int f \,dx
+    pub fn variable(variable: Expr) -> Self {
+        let mut bounds = Bounds::new();
+        bounds.add_variable(variable);
+        bounds
+    }
+
+    /// Returns the bounds starting at the lower value: \sum_{i = 1}, \lim_{x \to 0}
+    pub fn lower(variable: Expr, lower: Expr) -> Self {
+        let mut bounds = Bounds::variable(variable);
+        bounds.set_lower(lower);
+        bounds
+    }
+
+    /// Returns the range of the variable: \sum_{i = 1}^{n}, \int_{0}^{1} f \,dx
+    pub fn range(variable: Expr, lower: Expr, upper: Expr) -> Self {
+        let mut bounds = Bounds::lower(variable, lower);
+        bounds.set_upper(upper);
+        bounds
+    }
+
+    /// Returns the variables covering the set: \max_{x \in S}, \iint_{D} f \,dx \,dy
+    pub fn domain(variables: Vec<Expr>, domain: Expr) -> Self {
+        let mut bounds = Bounds::new();
+        bounds.variables = variables;
+        bounds.set_domain(domain);
+        bounds
+    }
+
+    /// Adds a variable (a differential for the integrals)
+    pub fn add_variable(&mut self, variable: Expr) {
+        self.variables.push(variable);
+    }
+
+    /// Changes the lower bound
+    pub fn set_lower(&mut self, lower: Expr) {
+        self.lower = Some(lower);
+    }
+
+    /// Changes the upper bound
+    pub fn set_upper(&mut self, upper: Expr) {
+        self.upper = Some(upper);
+    }
+
+    /// Changes the set covered by the variables
+    pub fn set_domain(&mut self, domain: Expr) {
+        self.domain = Some(domain);
+    }
+
+    /// Changes where the bounds are written
+    pub fn set_placement(&mut self, placement: LimitsPlacement) {
+        self.placement = placement;
+    }
+
+    /// Returns where the bounds are written
+    pub fn get_placement(&self) -> LimitsPlacement {
+        self.placement
+    }
+
+    /// Returns what is written below the operator
+    fn subscript(&self, op: BigOp) -> Option<String> {
+        let variables: Vec<String> = self.variables.iter().map(|v| v.to_string()).collect();
+        let variables = variables.join(", ");
+        if op.is_integral() {
+            return self
+                .lower
+                .as_ref()
+                .or(self.domain.as_ref())
+                .map(|e| e.to_string());
+        }
+        match (variables.is_empty(), &self.lower, &self.domain) {
+            (false, Some(lower), _) if op == BigOp::Limit => {
+                Some(format!("{} \\to {}", variables, lower))
+            }
+            (false, Some(lower), _) => Some(format!("{} = {}", variables, lower)),
+            (false, None, Some(domain)) => Some(format!("{} \\in {}", variables, domain)),
+            (false, None, None) => Some(variables),
+            (true, Some(e), _) | (true, None, Some(e)) => Some(e.to_string()),
+            (true, None, None) => None,
+        }
+    }
+
+    /// Returns the expressions of the bounds
+    fn expressions(&self) -> Vec<&Expr> {
+        let mut expressions: Vec<&Expr> = self.variables.iter().collect();
+        expressions.extend(self.lower.as_ref());
+        expressions.extend(self.upper.as_ref());
+        expressions.extend(self.domain.as_ref());
+        expressions
+    }
+
+    /// Returns the expressions of the bounds
+    fn expressions_mut(&mut self) -> Vec<&mut Expr> {
+        let mut expressions: Vec<&mut Expr> = self.variables.iter_mut().collect();
+        expressions.extend(self.lower.as_mut());
+        expressions.extend(self.upper.as_mut());
+        expressions.extend(self.domain.as_mut());
+        expressions
+    }
+}
+
+impl Default for Bounds {
+    fn default() -> Self {
+        Bounds::new()
+    }
+}
+
+/// A math expression
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    /// A number, as written
+    Num(String),
+    /// A variable (the greek letters and the letters of the symbol table are written
+    /// with their command: alpha is \alpha, RR is \mathbb{R}, ...)
+    Var(String),
+    /// Some text, written with \text
+    Text(String),
+    /// -x
+    Neg(Box<Expr>),
+    /// a + b, a - b, a b
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    /// \frac{a}{b}
+    Frac(Box<Expr>, Box<Expr>),
+    /// a^{b}
+    Pow(Box<Expr>, Box<Expr>),
+    /// a_{b}
+    Index(Box<Expr>, Box<Expr>),
+    /// The root of the expression, square root if no index is given
+    Root(Box<Expr>, Option<Box<Expr>>),
+    /// A function applied to its arguments: f(x, y)
+    Apply(String, Vec<Expr>),
+    /// An expression between delimiters, sized to the expression
+    Group(Delimiter, Box<Expr>),
+    /// A big operator with its bounds and its body
+    BigOp(BigOp, Box<Bounds>, Box<Expr>),
+    /// A set given by its elements: \{a, b\}
+    Set(Vec<Expr>),
+    /// A piecewise definition: the values and their conditions (cases of amsmath)
+    Cases(Vec<(Expr, Expr)>),
+    /// A matrix or a vector
+    Matrix(Box<Matrix>),
+    /// A chain of relations: a = b <= c
+    Relation(Box<Expr>, Vec<(Symbols, Expr)>),
+}
+
+impl Expr {
+    /// Returns a number, written as given
+    pub fn num<T: fmt::Display>(value: T) -> Self {
+        Expr::Num(value.to_string())
+    }
+
+    /// Returns a variable; a name with an underscore is a subscripted variable (x_1 is x_{1})
+    pub fn var<T: AsRef<str>>(name: T) -> Self {
+        let name = name.as_ref();
+        match name.find('_') {
+            Some(i) if i > 0 && i + 1 < name.len() => {
+                let index = &name[i + 1..];
+                let index = if index.chars().all(|c| c.is_ascii_digit()) {
+                    Expr::num(index)
+                } else {
+                    Expr::var(index)
+                };
+                Expr::index(Expr::var(&name[..i]), index)
+            }
+            _ => Expr::Var(name.to_string()),
+        }
+    }
+
+    /// Returns some text, written with \text
+    pub fn text<T: AsRef<str>>(text: T) -> Self {
+        Expr::Text(text.as_ref().to_string())
+    }
+
+    /// Returns the expression of a literal of the math! macro: a number or a "text"
+    pub fn literal(literal: &str) -> Self {
+        if literal.starts_with('"') && literal.ends_with('"') && literal.len() >= 2 {
+            Expr::text(&literal[1..literal.len() - 1])
+        } else {
+            number_literal(literal)
+        }
+    }
+
+    /// Returns the fraction top / bottom
+    pub fn frac(top: Expr, bottom: Expr) -> Self {
+        Expr::Frac(Box::new(top), Box::new(bottom))
+    }
+
+    /// Returns base^exponent
+    pub fn pow(base: Expr, exponent: Expr) -> Self {
+        Expr::Pow(Box::new(base), Box::new(exponent))
+    }
+
+    /// Returns base_index
+    pub fn index(base: Expr, index: Expr) -> Self {
+        Expr::Index(Box::new(base), Box::new(index))
+    }
+
+    /// Returns the square root of the expression
+    pub fn sqrt(expr: Expr) -> Self {
+        Expr::Root(Box::new(expr), None)
+    }
+
+    /// Returns the n-th root of the expression
+    pub fn root(n: Expr, expr: Expr) -> Self {
+        Expr::Root(Box::new(expr), Some(Box::new(n)))
+    }
+
+    /// Returns the function applied to the arguments
+    pub fn apply<T: AsRef<str>>(function: T, arguments: Vec<Expr>) -> Self {
+        Expr::Apply(function.as_ref().to_string(), arguments)
+    }
+
+    /// Returns the expression between delimiters
+    pub fn group(delimiter: Delimiter, expr: Expr) -> Self {
+        Expr::Group(delimiter, Box::new(expr))
+    }
+
+    /// Returns a big operator with its bounds and its body
+    pub fn big_op(op: BigOp, bounds: Bounds, body: Expr) -> Self {
+        Expr::BigOp(op, Box::new(bounds), Box::new(body))
+    }
+
+    /// Returns infinity
+    pub fn infinity() -> Self {
+        Expr::var("infty")
+    }
+
+    /// Returns the set of the elements
+    pub fn set(elements: Vec<Expr>) -> Self {
+        Expr::Set(elements)
+    }
+
+    /// Returns the piecewise definition, each value with its condition
+    pub fn cases(cases: Vec<(Expr, Expr)>) -> Self {
+        Expr::Cases(cases)
+    }
+
+    /// Changes where the bounds of the big operators of the expression are written
+    pub fn set_limits_placement(&mut self, placement: LimitsPlacement) {
+        if let Expr::BigOp(_, ref mut bounds, _) = *self {
+            bounds.set_placement(placement);
+        }
+        for child in self.children_mut() {
+            child.set_limits_placement(placement);
+        }
+    }
+
+    /// Returns the sub-expressions of the expression
+    fn children(&self) -> Vec<&Expr> {
+        match *self {
+            Expr::Num(_) | Expr::Var(_) | Expr::Text(_) => Vec::new(),
+            Expr::Neg(ref e) | Expr::Group(_, ref e) | Expr::Root(ref e, None) => vec![&**e],
+            Expr::Binary(_, ref a, ref b)
+            | Expr::Frac(ref a, ref b)
+            | Expr::Pow(ref a, ref b)
+            | Expr::Index(ref a, ref b)
+            | Expr::Root(ref a, Some(ref b)) => vec![&**a, &**b],
+            Expr::Apply(_, ref args) | Expr::Set(ref args) => args.iter().collect(),
+            Expr::BigOp(_, ref bounds, ref body) => {
+                let mut children = bounds.expressions();
+                children.push(&**body);
+                children
+            }
+            Expr::Cases(ref cases) => cases.iter().flat_map(|c| vec![&c.0, &c.1]).collect(),
+            Expr::Matrix(ref matrix) => matrix.get_rows().iter().flat_map(|r| r.iter()).collect(),
+            Expr::Relation(ref first, ref rest) => {
+                let mut children = vec![&**first];
+                children.extend(rest.iter().map(|r| &r.1));
+                children
+            }
+        }
+    }
+
+    /// Returns the sub-expressions of the expression
+    fn children_mut(&mut self) -> Vec<&mut Expr> {
+        match *self {
+            Expr::Num(_) | Expr::Var(_) | Expr::Text(_) => Vec::new(),
+            Expr::Neg(ref mut e) | Expr::Group(_, ref mut e) | Expr::Root(ref mut e, None) => {
+                vec![&mut **e]
+            }
+            Expr::Binary(_, ref mut a, ref mut b)
+            | Expr::Frac(ref mut a, ref mut b)
+            | Expr::Pow(ref mut a, ref mut b)
+            | Expr::Index(ref mut a, ref mut b)
+            | Expr::Root(ref mut a, Some(ref mut b)) => vec![&mut **a, &mut **b],
+            Expr::Apply(_, ref mut args) | Expr::Set(ref mut args) => args.iter_mut().collect(),
+            Expr::BigOp(_, ref mut bounds, ref mut body) => {
+                let mut children = bounds.expressions_mut();
+                children.push(&mut **body);
+                children
+            }
+            Expr::Cases(ref mut cases) => cases
+                .iter_mut()
+                .flat_map(|c| vec![&mut c.0, &mut c.1])
+                .collect(),
+            Expr::Matrix(ref mut matrix) => matrix
+                .get_rows_mut()
+                .iter_mut()
+                .flat_map(|r| r.iter_mut())
+                .collect(),
+            Expr::Relation(ref mut first, ref mut rest) => {
+                let mut children = vec![&mut **first];
+                children.extend(rest.iter_mut().map(|r| &mut r.1));
+                children
+            }
+        }
+    }
+
+    /// Returns the chain of relations from the first side: first symbol side symbol side ...
+    pub fn relation(first: Expr, rest: Vec<(Symbols, Expr)>) -> Self {
+        Expr::Relation(Box::new(first), rest)
+    }
+
+    /// Returns the precedence of the expression: the lower, the looser it binds
+    /// (0 for the relations, 1 for the sums, 2 for the products, 3 for the atoms)
+    fn precedence(&self) -> u8 {
+        match *self {
+            Expr::Relation(..) => 0,
+            Expr::Binary(BinaryOp::Add, ..) | Expr::Binary(BinaryOp::Sub, ..) => 1,
+            Expr::Binary(BinaryOp::Mul, ..) | Expr::Neg(_) | Expr::BigOp(..) => 2,
+            _ => 3,
+        }
+    }
+
+    /// Returns true if the expression ends with a big operator, which would take
+    /// in its body what is written after the expression
+    fn is_open(&self) -> bool {
+        match *self {
+            // The differentials close the integrals, and the braces the display style
+            Expr::BigOp(op, ref bounds, _) => {
+                !op.is_integral() && bounds.placement != LimitsPlacement::Display
+            }
+            Expr::Neg(ref e) => !neg_parentheses(e) && e.is_open(),
+            Expr::Binary(op, _, ref b) => !right_parentheses(op, b) && b.is_open(),
+            _ => false,
+        }
+    }
+
+    /// Returns true if the expression is taller than a line (fractions, big operators, ...)
+    fn is_tall(&self) -> bool {
+        match *self {
+            Expr::Frac(..) | Expr::BigOp(..) | Expr::Cases(_) | Expr::Matrix(_) => true,
+            Expr::Num(_) | Expr::Var(_) | Expr::Text(_) => false,
+            Expr::Neg(ref e) | Expr::Root(ref e, _) | Expr::Group(_, ref e) => e.is_tall(),
+            Expr::Binary(_, ref a, ref b) | Expr::Pow(ref a, ref b) | Expr::Index(ref a, ref b) => {
+                a.is_tall() || b.is_tall()
+            }
+            Expr::Apply(_, ref args) | Expr::Set(ref args) => args.iter().any(|a| a.is_tall()),
+            Expr::Relation(ref first, ref rest) => {
+                first.is_tall() || rest.iter().any(|r| r.1.is_tall())
+            }
+        }
+    }
+
+    /// Returns the expression, between parentheses if needed
+    fn operand(&self, parentheses: bool) -> String {
+        if parentheses {
+            delimit(Delimiter::Paren, self)
+        } else {
+            self.to_string()
+        }
+    }
+}
+
+/// Suffixes giving the type of a number literal
+const LITERAL_SUFFIXES: [&str; 14] = [
+    "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize", "f32",
+    "f64",
+];
+
+/// Returns the value of a number literal, without the _ separators and the type suffix,
+/// in base 10 and with the exponent written as a power of ten
+fn number_literal(literal: &str) -> Expr {
+    let digits: String = literal.chars().filter(|&c| c != '_').collect();
+    let radix = match digits.get(..2) {
+        Some("0x") => 16,
+        Some("0o") => 8,
+        Some("0b") => 2,
+        _ => 10,
+    };
+    // f32 and f64 are hexadecimal digits in 0x literals
+    let suffix = LITERAL_SUFFIXES
+        .iter()
+        .filter(|s| radix != 16 || !s.starts_with('f'))
+        .find(|s| digits.len() > s.len() && digits.ends_with(*s));
+    let digits = match suffix {
+        Some(suffix) => &digits[..digits.len() - suffix.len()],
+        None => &digits[..],
+    };
+    if radix != 10 {
+        return match u128::from_str_radix(&digits[2..], radix) {
+            Ok(value) => Expr::num(value),
+            Err(_) => Expr::num(digits),
+        };
+    }
+    match digits.find(['e', 'E']) {
+        Some(i) => {
+            let exponent = digits[i + 1..].trim_start_matches('+');
+            let power = Expr::pow(Expr::num(10), Expr::num(exponent));
+            match &digits[..i] {
+                "1" => power,
+                mantissa => Expr::num(mantissa) * power,
+            }
+        }
+        None => Expr::num(digits),
+    }
+}
+
+/// Returns true if the operand of - needs parentheses
+fn neg_parentheses(e: &Expr) -> bool {
+    e.precedence() < 2 || matches!(*e, Expr::Neg(_))
+}
+
+/// Returns true if the right operand of the operation needs parentheses
+fn right_parentheses(op: BinaryOp, b: &Expr) -> bool {
+    let loose = match op {
+        BinaryOp::Add => b.precedence() < 1,
+        BinaryOp::Sub => b.precedence() <= 1,
+        BinaryOp::Mul => b.precedence() < 2,
+    };
+    loose || matches!(*b, Expr::Neg(_))
+}
+
+/// Writes the expression between the delimiters, sized with \left \right if it is tall
+fn delimit(delimiter: Delimiter, expr: &Expr) -> String {
+    let (left, right) = delimiter.codes();
+    if expr.is_tall() {
+        format!("\\left{} {} \\right{}", left, expr, right)
+    } else {
+        format!("{}{}{}", left, expr, right)
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Expr::Num(ref n) => write!(f, "{}", n),
+            Expr::Var(ref name) => match MathSymbol::find(name) {
+                // Greek letters, \infty, \mathbb{R}, ... but not the operators
+                Some(s) if s.kind == SymbolKind::Greek || s.kind == SymbolKind::Letter => {
+                    write!(f, "{}", s.latex)
+                }
+                _ if name.chars().count() > 1 => {
+                    write!(f, "\\mathit{{{}}}", escape_latex(name))
+                }
+                _ => write!(f, "{}", escape_latex(name)),
+            },
+            Expr::Text(ref text) => write!(f, "\\text{{{}}}", escape_latex(text)),
+            Expr::Neg(ref e) => write!(f, "-{}", e.operand(neg_parentheses(e))),
+            Expr::Binary(op, ref a, ref b) => {
+                // A big operator on the left would take the right operand in its body
+                let left = a.precedence() < op_precedence(op) || a.is_open();
+                let a = a.operand(left);
+                let b = b.operand(right_parentheses(op, b));
+                match op {
+                    BinaryOp::Add => write!(f, "{} + {}", a, b),
+                    BinaryOp::Sub => write!(f, "{} - {}", a, b),
+                    // Two numbers side by side would read as one
+                    BinaryOp::Mul if b.starts_with(|c: char| c.is_ascii_digit()) => {
+                        write!(f, "{} \\cdot {}", a, b)
+                    }
+                    BinaryOp::Mul => write!(f, "{} {}", a, b),
+                }
+            }
+            Expr::Frac(ref a, ref b) => write!(f, "\\frac{{{}}}{{{}}}", a, b),
+            Expr::Pow(ref a, ref b) => {
+                let parentheses =
+                    a.precedence() < 3 || matches!(**a, Expr::Frac(..) | Expr::Pow(..));
+                write!(f, "{}^{{{}}}", a.operand(parentheses), b)
+            }
+            Expr::Index(ref a, ref b) => {
+                let parentheses = a.precedence() < 3 || matches!(**a, Expr::Frac(..));
+                write!(f, "{}_{{{}}}", a.operand(parentheses), b)
+            }
+            Expr::Root(ref e, None) => write!(f, "\\sqrt{{{}}}", e),
+            Expr::Root(ref e, Some(ref n)) => write!(f, "\\sqrt[{}]{{{}}}", n, e),
+            Expr::Apply(ref name, ref args) => {
+                if FUNCTIONS.contains(&name.as_str()) {
+                    write!(f, "\\{}", name)?;
+                } else if name.chars().count() > 1 {
+                    write!(f, "\\operatorname{{{}}}", name)?;
+                } else {
+                    write!(f, "{}", name)?;
+                }
+                let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+                let tall = self.is_tall();
+                if tall {
+                    write!(f, "\\left( {} \\right)", args.join(", "))
+                } else {
+                    write!(f, "({})", args.join(", "))
+                }
+            }
+            Expr::Group(delimiter, ref e) => write!(f, "{}", delimit(delimiter, e)),
+            Expr::Set(ref elements) if elements.is_empty() => write!(f, "\\emptyset"),
+            Expr::Matrix(ref matrix) => write!(f, "{}", matrix),
+            Expr::Cases(ref cases) => {
+                let rows: Vec<String> =
+                    cases.iter().map(|c| format!("{} & {}", c.0, c.1)).collect();
+                write!(f, "\\begin{{cases}} {} \\end{{cases}}", rows.join(" \\\\ "))
+            }
+            Expr::Set(ref elements) => {
+                let items: Vec<String> = elements.iter().map(|e| e.to_string()).collect();
+                if self.is_tall() {
+                    write!(f, "\\left\\{{ {} \\right\\}}", items.join(", "))
+                } else {
+                    write!(f, "\\{{{}\\}}", items.join(", "))
+                }
+            }
+            Expr::BigOp(op, ref bounds, ref body) => {
+                let mut code = op.command().to_string();
+                match bounds.placement {
+                    LimitsPlacement::Limits => code.push_str("\\limits"),
+                    LimitsPlacement::Inline => code.push_str("\\nolimits"),
+                    _ => (),
+                }
+                if let Some(subscript) = bounds.subscript(op) {
+                    code.push_str(&format!("_{{{}}}", subscript));
+                }
+                if let Some(ref upper) = bounds.upper {
+                    code.push_str(&format!("^{{{}}}", upper));
+                }
+                code.push_str(&format!(" {}", body.operand(body.precedence() <= 1)));
+                if op.is_integral() {
+                    for variable in bounds.variables.iter() {
+                        code.push_str(&format!(" \\,d{}", variable));
+                    }
+                }
+                if bounds.placement == LimitsPlacement::Display {
+                    write!(f, "{{\\displaystyle {}}}", code)
+                } else {
+                    write!(f, "{}", code)
+                }
+            }
+            Expr::Relation(ref first, ref rest) => {
+                write!(f, "{}", first.operand(first.precedence() == 0))?;
+                for (symbol, side) in rest.iter() {
+                    write!(
+                        f,
+                        "{}{}",
+                        symbol.latex_code(),
+                        side.operand(side.precedence() == 0)
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Returns the precedence of the operation
+fn op_precedence(op: BinaryOp) -> u8 {
+    match op {
+        BinaryOp::Add | BinaryOp::Sub => 1,
+        BinaryOp::Mul => 2,
+    }
+}
+
+impl Writable for Expr {
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        write!(buf, "{}", self)?;
+        Ok(())
+    }
+
+    fn add_packages(&self, packages: &mut Packages) {
+        let package = match *self {
+            Expr::Var(ref name) => match MathSymbol::find(name) {
+                Some(s) if s.kind == SymbolKind::Greek || s.kind == SymbolKind::Letter => s.package,
+                _ => None,
+            },
+            // \text, \operatorname, the multiple integrals, cases and the matrices
+            Expr::Text(_) | Expr::Cases(_) | Expr::Matrix(_) => Some("amsmath"),
+            Expr::Apply(ref name, _)
+                if !FUNCTIONS.contains(&name.as_str()) && name.chars().count() > 1 =>
+            {
+                Some("amsmath")
+            }
+            Expr::BigOp(op, _, _) => match op {
+                BigOp::Integral(n) if n > 1 => Some("amsmath"),
+                BigOp::ArgMin | BigOp::ArgMax => Some("amsmath"),
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some(package) = package {
+            packages.add_package(package);
+        }
+        if let Expr::Relation(_, ref rest) = *self {
+            for package in rest.iter().filter_map(|r| r.0.get_package()) {
+                packages.add_package(package);
+            }
+        }
+        for child in self.children() {
+            child.add_packages(packages);
+        }
+    }
+}
+
+impl From<VarOrImm> for Expr {
+    fn from(value: VarOrImm) -> Self {
+        match value {
+            VarOrImm::Var(name) => Expr::var(name),
+            VarOrImm::Imm(n) => Expr::num(n),
+        }
+    }
+}
+
+impl Add for Expr {
+    type Output = Expr;
+
+    fn add(self, other: Expr) -> Expr {
+        Expr::Binary(BinaryOp::Add, Box::new(self), Box::new(other))
+    }
+}
+
+impl Sub for Expr {
+    type Output = Expr;
+
+    fn sub(self, other: Expr) -> Expr {
+        Expr::Binary(BinaryOp::Sub, Box::new(self), Box::new(other))
+    }
+}
+
+impl Mul for Expr {
+    type Output = Expr;
+
+    fn mul(self, other: Expr) -> Expr {
+        Expr::Binary(BinaryOp::Mul, Box::new(self), Box::new(other))
+    }
+}
+
+/// a / b is written as a fraction
+impl Div for Expr {
+    type Output = Expr;
+
+    fn div(self, other: Expr) -> Expr {
+        Expr::frac(self, other)
+    }
+}
+
+impl Neg for Expr {
+    type Output = Expr;
+
+    fn neg(self) -> Expr {
+        Expr::Neg(Box::new(self))
+    }
+}
+
+/// Builds an Expr from a Rust-like expression, checked at compile time:
+///
+/// - numbers, variables (`x`, `alpha`, `x_1`) and "text",
+/// - `+`, `-`, `*`, `/` (a fraction) with the precedence of Rust, `^` for the powers,
+///   `x[i]` for the subscripts and parentheses to group,
+/// - `sqrt(x)`, `root(n, x)`, `abs(x)`, `norm(x)`, `{a, b}` for the sets,
+///   and any other function `f(x, y)`,
+/// - the big operators `sum`, `prod`, `union`, `inter`, `int`, `iint`, `iiint`, `oint`, `lim`,
+///   `max`, `min`, `sup`, `inf`, `argmin` and `argmax`, with their bounds before the body:
+///   `sum(i = 0..n, body)`, `lim(x -> 0, body)`, `max(x in S, body)`, `iint((x, y) in D, body)`,
+///   `prod(i, body)` or `sum(body)` (`inf` alone is infinity),
+/// - `cases(value => condition, ...)` for the piecewise definitions,
+/// - the relations `=`, `==`, `!=`, `<`, `<=`, `>`, `>=`, which can be chained.
+///
+/// `math!(sum(i = 0..n, x[i]^2) / n <= sqrt(y))`
+#[macro_export]
+macro_rules! math {
+    // ----- Relations: the sides are split on the relation symbols -----
+    (@rel [$($done:tt)*] [$($cur:tt)+] <= $($rest:tt)+) => {
+        $crate::math!(@rel [$($done)* [$($cur)+] LessOrEquals] [] $($rest)+)
+    };
+    (@rel [$($done:tt)*] [$($cur:tt)+] >= $($rest:tt)+) => {
+        $crate::math!(@rel [$($done)* [$($cur)+] MoreOrEquals] [] $($rest)+)
+    };
+    (@rel [$($done:tt)*] [$($cur:tt)+] == $($rest:tt)+) => {
+        $crate::math!(@rel [$($done)* [$($cur)+] Equals] [] $($rest)+)
+    };
+    (@rel [$($done:tt)*] [$($cur:tt)+] != $($rest:tt)+) => {
+        $crate::math!(@rel [$($done)* [$($cur)+] Diff] [] $($rest)+)
+    };
+    (@rel [$($done:tt)*] [$($cur:tt)+] = $($rest:tt)+) => {
+        $crate::math!(@rel [$($done)* [$($cur)+] Equals] [] $($rest)+)
+    };
+    (@rel [$($done:tt)*] [$($cur:tt)+] < $($rest:tt)+) => {
+        $crate::math!(@rel [$($done)* [$($cur)+] Less] [] $($rest)+)
+    };
+    (@rel [$($done:tt)*] [$($cur:tt)+] > $($rest:tt)+) => {
+        $crate::math!(@rel [$($done)* [$($cur)+] More] [] $($rest)+)
+    };
+    (@rel [$($done:tt)*] [$($cur:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::math!(@rel [$($done)*] [$($cur)* $t] $($rest)*)
+    };
+    (@rel [] [$($cur:tt)+]) => {
+        $crate::math!(@arith [] [] $($cur)+)
+    };
+    (@rel [$($done:tt)+] [$($cur:tt)+]) => {
+        $crate::math!(@chain $($done)+ [$($cur)+])
+    };
+    (@chain [$($first:tt)+] $($symbol:ident [$($side:tt)+])+) => {
+        $crate::expr::Expr::relation(
+            $crate::math!(@arith [] [] $($first)+),
+            vec![$(($crate::symbols::Symbols::$symbol, $crate::math!(@arith [] [] $($side)+)),)+],
+        )
+    };
+
+    // ----- Arithmetic: the last atom is kept aside for ^ and [] -----
+    (@arith [$($out:tt)*] [$last:tt]) => {
+        $($out)* $last
+    };
+    (@arith [$($out:tt)*] [$last:tt] ^ - $e:tt $($rest:tt)*) => {
+        $crate::math!(@arith [$($out)*]
+            [($crate::expr::Expr::pow($last, -$crate::math!(@atom $e)))] $($rest)*)
+    };
+    (@arith [$($out:tt)*] [$last:tt] ^ $e:tt $($rest:tt)*) => {
+        $crate::math!(@arith [$($out)*]
+            [($crate::expr::Expr::pow($last, $crate::math!(@atom $e)))] $($rest)*)
+    };
+    (@arith [$($out:tt)*] [$last:tt] [$($i:tt)+] $($rest:tt)*) => {
+        $crate::math!(@arith [$($out)*]
+            [($crate::expr::Expr::index($last, $crate::math!($($i)+)))] $($rest)*)
+    };
+    (@arith [$($out:tt)*] [$last:tt] + $($rest:tt)+) => {
+        $crate::math!(@arith [$($out)* $last +] [] $($rest)+)
+    };
+    (@arith [$($out:tt)*] [$last:tt] - $($rest:tt)+) => {
+        $crate::math!(@arith [$($out)* $last -] [] $($rest)+)
+    };
+    (@arith [$($out:tt)*] [$last:tt] * $($rest:tt)+) => {
+        $crate::math!(@arith [$($out)* $last *] [] $($rest)+)
+    };
+    (@arith [$($out:tt)*] [$last:tt] / $($rest:tt)+) => {
+        $crate::math!(@arith [$($out)* $last /] [] $($rest)+)
+    };
+    (@arith [$($out:tt)*] [] - $($rest:tt)+) => {
+        $crate::math!(@arith [$($out)* -] [] $($rest)+)
+    };
+    (@arith [$($out:tt)*] [] $f:ident ($($args:tt)+) $($rest:tt)*) => {
+        $crate::math!(@arith [$($out)*] [($crate::math!(@call $f $($args)+))] $($rest)*)
+    };
+    (@arith [$($out:tt)*] [] $e:tt $($rest:tt)*) => {
+        $crate::math!(@arith [$($out)*] [($crate::math!(@atom $e))] $($rest)*)
+    };
+
+    // ----- Atoms -----
+    (@atom ($($inner:tt)+)) => {
+        $crate::math!($($inner)+)
+    };
+    (@atom {}) => {
+        $crate::expr::Expr::set(Vec::new())
+    };
+    (@atom {$($elements:tt)+}) => {
+        $crate::expr::Expr::set($crate::math!(@args [] [] $($elements)+))
+    };
+    (@atom $x:ident) => {
+        $crate::expr::Expr::var(stringify!($x))
+    };
+    (@atom $n:literal) => {
+        $crate::expr::Expr::literal(stringify!($n))
+    };
+
+    // ----- Functions -----
+    (@call sqrt $($x:tt)+) => {
+        $crate::expr::Expr::sqrt($crate::math!($($x)+))
+    };
+    (@call root $n:tt, $($x:tt)+) => {
+        $crate::expr::Expr::root($crate::math!(@atom $n), $crate::math!($($x)+))
+    };
+    (@call abs $($x:tt)+) => {
+        $crate::expr::Expr::group($crate::expr::Delimiter::Abs, $crate::math!($($x)+))
+    };
+    (@call norm $($x:tt)+) => {
+        $crate::expr::Expr::group($crate::expr::Delimiter::Norm, $crate::math!($($x)+))
+    };
+    (@call sum $($args:tt)+) => {
+        $crate::math!(@bigop [Sum] $($args)+)
+    };
+    (@call prod $($args:tt)+) => {
+        $crate::math!(@bigop [Product] $($args)+)
+    };
+    (@call union $($args:tt)+) => {
+        $crate::math!(@bigop [Union] $($args)+)
+    };
+    (@call inter $($args:tt)+) => {
+        $crate::math!(@bigop [Intersection] $($args)+)
+    };
+    (@call int $($args:tt)+) => {
+        $crate::math!(@bigop [Integral(1)] $($args)+)
+    };
+    (@call iint $($args:tt)+) => {
+        $crate::math!(@bigop [Integral(2)] $($args)+)
+    };
+    (@call iiint $($args:tt)+) => {
+        $crate::math!(@bigop [Integral(3)] $($args)+)
+    };
+    (@call oint $($args:tt)+) => {
+        $crate::math!(@bigop [ContourIntegral] $($args)+)
+    };
+    (@call lim $($args:tt)+) => {
+        $crate::math!(@bigop [Limit] $($args)+)
+    };
+    (@call sup $($args:tt)+) => {
+        $crate::math!(@bigop [Sup] $($args)+)
+    };
+    (@call inf $($args:tt)+) => {
+        $crate::math!(@bigop [Inf] $($args)+)
+    };
+    (@call argmin $($args:tt)+) => {
+        $crate::math!(@bigop [ArgMin] $($args)+)
+    };
+    (@call argmax $($args:tt)+) => {
+        $crate::math!(@bigop [ArgMax] $($args)+)
+    };
+    // max(a, b) is a function, max(x in S, f) an operator
+    (@call max $v:tt in $($args:tt)+) => {
+        $crate::math!(@bigop [Max] $v in $($args)+)
+    };
+    (@call min $v:tt in $($args:tt)+) => {
+        $crate::math!(@bigop [Min] $v in $($args)+)
+    };
+    (@call cases $($args:tt)+) => {
+        $crate::expr::Expr::cases($crate::math!(@cases [] [] $($args)+))
+    };
+    (@call $f:ident $($args:tt)+) => {
+        $crate::expr::Expr::apply(stringify!($f), $crate::math!(@args [] [] $($args)+))
+    };
+
+    // ----- Big operators: op(i = a..b, body), op(x -> a, body), op(x in S, body),
+    // op((x, y) in D, body), op(i, body) or op(body) -----
+    (@bigop [$($op:tt)+] ($($v:ident),+) in $($rest:tt)+) => {
+        $crate::math!(@domain [$($op)+] [$($crate::expr::Expr::var(stringify!($v))),+] [] $($rest)+)
+    };
+    (@bigop [$($op:tt)+] $v:ident in $($rest:tt)+) => {
+        $crate::math!(@domain [$($op)+] [$crate::expr::Expr::var(stringify!($v))] [] $($rest)+)
+    };
+    (@bigop [$($op:tt)+] $v:ident = $($rest:tt)+) => {
+        $crate::math!(@lower [$($op)+] $v [] $($rest)+)
+    };
+    (@bigop [$($op:tt)+] $v:ident -> $($rest:tt)+) => {
+        $crate::math!(@lower [$($op)+] $v [] $($rest)+)
+    };
+    (@bigop [$($op:tt)+] $v:ident, $($body:tt)+) => {
+        $crate::expr::Expr::big_op(
+            $crate::expr::BigOp::$($op)+,
+            $crate::expr::Bounds::variable($crate::expr::Expr::var(stringify!($v))),
+            $crate::math!($($body)+),
+        )
+    };
+    (@bigop [$($op:tt)+] $($body:tt)+) => {
+        $crate::expr::Expr::big_op($crate::expr::BigOp::$($op)+, $crate::expr::Bounds::new(), $crate::math!($($body)+))
+    };
+    (@domain [$($op:tt)+] [$($v:tt)+] [$($set:tt)+] , $($body:tt)+) => {
+        $crate::expr::Expr::big_op(
+            $crate::expr::BigOp::$($op)+,
+            $crate::expr::Bounds::domain(vec![$($v)+], $crate::math!($($set)+)),
+            $crate::math!($($body)+),
+        )
+    };
+    (@domain [$($op:tt)+] [$($v:tt)+] [$($set:tt)*] $t:tt $($rest:tt)+) => {
+        $crate::math!(@domain [$($op)+] [$($v)+] [$($set)* $t] $($rest)+)
+    };
+    (@lower [$($op:tt)+] $v:ident [$($lo:tt)+] .. $($rest:tt)+) => {
+        $crate::math!(@upper [$($op)+] $v [$($lo)+] [] $($rest)+)
+    };
+    (@lower [$($op:tt)+] $v:ident [$($lo:tt)+] ..= $($rest:tt)+) => {
+        $crate::math!(@upper [$($op)+] $v [$($lo)+] [] $($rest)+)
+    };
+    (@lower [$($op:tt)+] $v:ident [$($lo:tt)+] , $($body:tt)+) => {
+        $crate::expr::Expr::big_op(
+            $crate::expr::BigOp::$($op)+,
+            $crate::expr::Bounds::lower(
+                $crate::expr::Expr::var(stringify!($v)),
+                $crate::math!($($lo)+),
+            ),
+            $crate::math!($($body)+),
+        )
+    };
+    (@lower [$($op:tt)+] $v:ident [$($lo:tt)*] $t:tt $($rest:tt)+) => {
+        $crate::math!(@lower [$($op)+] $v [$($lo)* $t] $($rest)+)
+    };
+    (@upper [$($op:tt)+] $v:ident [$($lo:tt)+] [$($hi:tt)+] , $($body:tt)+) => {
+        $crate::expr::Expr::big_op(
+            $crate::expr::BigOp::$($op)+,
+            $crate::expr::Bounds::range(
+                $crate::expr::Expr::var(stringify!($v)),
+                $crate::math!($($lo)+),
+                $crate::math!($($hi)+),
+            ),
+            $crate::math!($($body)+),
+        )
+    };
+    (@upper [$($op:tt)+] $v:ident [$($lo:tt)+] [$($hi:tt)*] $t:tt $($rest:tt)+) => {
+        $crate::math!(@upper [$($op)+] $v [$($lo)+] [$($hi)* $t] $($rest)+)
+    };
+
+    // ----- Cases: value => condition, split on the commas -----
+    (@cases [$($done:tt)*] [$($v:tt)+] => $($rest:tt)+) => {
+        $crate::math!(@case [$($done)*] [$($v)+] [] $($rest)+)
+    };
+    (@cases [$($done:tt)*] [$($v:tt)*] $t:tt $($rest:tt)+) => {
+        $crate::math!(@cases [$($done)*] [$($v)* $t] $($rest)+)
+    };
+    (@case [$($done:tt)*] [$($v:tt)+] [$($c:tt)+] , $($rest:tt)+) => {
+        $crate::math!(@cases [$($done)* ({$($v)+} {$($c)+})] [] $($rest)+)
+    };
+    (@case [$(({$($dv:tt)+} {$($dc:tt)+}))*] [$($v:tt)+] [$($c:tt)+]) => {
+        vec![
+            $(($crate::math!($($dv)+), $crate::math!($($dc)+)),)*
+            ($crate::math!($($v)+), $crate::math!($($c)+))
+        ]
+    };
+    (@case [$($done:tt)*] [$($v:tt)+] [$($c:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::math!(@case [$($done)*] [$($v)+] [$($c)* $t] $($rest)*)
+    };
+
+    // ----- Arguments of a function, split on the commas -----
+    (@args [$($done:tt)*] [$($cur:tt)+] , $($rest:tt)+) => {
+        $crate::math!(@args [$($done)* {$($cur)+}] [] $($rest)+)
+    };
+    (@args [$($done:tt)*] [$($cur:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::math!(@args [$($done)*] [$($cur)* $t] $($rest)*)
+    };
+    (@args [$({$($arg:tt)+})*] [$($cur:tt)+]) => {
+        vec![$($crate::math!($($arg)+),)* $crate::math!($($cur)+)]
+    };
+
+    ($($t:tt)+) => {
+        $crate::math!(@rel [] [] $($t)+)
+    };
+}
+
+#[cfg(test)]
+mod tests_expr {
+    use super::*;
+
+    #[test]
+    fn precedence() {
+        let (a, b, c) = (Expr::var("a"), Expr::var("b"), Expr::var("c"));
+        assert_eq!((a.clone() + b.clone()) * c.clone(), math!((a + b) * c));
+        assert_eq!(math!((a + b) * c).to_string(), "(a + b) c");
+        assert_eq!(math!(a + b * c).to_string(), "a + b c");
+        assert_eq!(math!(a - (b - c)).to_string(), "a - (b - c)");
+        assert_eq!(math!((a - b) - c).to_string(), "a - b - c");
+        assert_eq!(math!(a * -b).to_string(), "a (-b)");
+        assert_eq!(math!(-(a + b)).to_string(), "-(a + b)");
+        assert_eq!(math!(2 * 3).to_string(), "2 \\cdot 3");
+        assert_eq!(math!((a + b) ^ 2).to_string(), "(a + b)^{2}");
+        assert_eq!(math!(-x ^ 2).to_string(), "-x^{2}");
+        assert_eq!(math!(x ^ -1).to_string(), "x^{-1}");
+        assert_eq!(
+            math!((a / b) ^ 2).to_string(),
+            "\\left( \\frac{a}{b} \\right)^{2}"
+        );
+        assert_eq!(math!((a + b) / c).to_string(), "\\frac{a + b}{c}");
+    }
+
+    #[test]
+    fn atoms_and_functions() {
+        assert_eq!(
+            math!(alpha_1 + x[i + 1]).to_string(),
+            "\\alpha_{1} + x_{i + 1}"
+        );
+        assert_eq!(
+            math!(sin(theta) + f(x, y)).to_string(),
+            "\\sin(\\theta) + f(x, y)"
+        );
+        assert_eq!(math!(speed).to_string(), "\\mathit{speed}");
+        assert_eq!(Expr::var("x%y").to_string(), "\\mathit{x\\%y}");
+        assert_eq!(Expr::var("#").to_string(), "\\#");
+        assert_eq!(
+            math!(root(3, x) + abs(a / b)).to_string(),
+            "\\sqrt[3]{x} + \\left| \\frac{a}{b} \\right|"
+        );
+        assert_eq!(math!(f(x) = "n/a").to_string(), "f(x) = \\text{n/a}");
+        assert_eq!(math!(a * sum(i, x[i])).to_string(), "a \\sum_{i} x_{i}");
+        assert_eq!(math!(2.5e3 * inf).to_string(), "2.5 \\cdot 10^{3} \\infty");
+        assert_eq!(
+            math!(x[n] = ell * RR).to_string(),
+            "x_{n} = \\ell \\mathbb{R}"
+        );
+        let mut packages = Packages::new();
+        math!(varkappa + calA).add_packages(&mut packages);
+        assert!(packages.contains("amssymb"));
+        let mut packages = Packages::new();
+        Expr::var("matrix}").add_packages(&mut packages);
+        math!(sin(x) + f(x) <= y).add_packages(&mut packages);
+        assert!(!packages.contains("amsmath"));
+        let implies = Symbols::get_symbol("==>".to_string()).unwrap();
+        Expr::relation(Expr::var("x"), vec![(implies, Expr::var("y"))]).add_packages(&mut packages);
+        assert!(packages.contains("amsmath"));
+    }
+
+    #[test]
+    fn number_literals() {
+        assert_eq!(math!(1_000 * x).to_string(), "1000 x");
+        assert_eq!(math!(2.5e3 + 1u32).to_string(), "2.5 \\cdot 10^{3} + 1");
+        assert_eq!(math!(1e-6 * 0.5f64).to_string(), "10^{-6} \\cdot 0.5");
+        assert_eq!(math!(0xff + 0b101u8).to_string(), "255 + 5");
+        assert_eq!(math!(0x1f32).to_string(), "7986");
+    }
+
+    #[test]
+    fn big_operators_and_relations() {
+        assert_eq!(
+            math!(sum(i = 0..n, x[i] ^ 2) / n <= sqrt(y)).to_string(),
+            "\\frac{\\sum_{i = 0}^{n} x_{i}^{2}}{n} \\leq \\sqrt{y}"
+        );
+        assert_eq!(
+            math!(prod(k, a[k] + 1) + 1).to_string(),
+            "\\left( \\prod_{k} (a_{k} + 1) \\right) + 1"
+        );
+        assert_eq!(math!(0 < x <= 1 != y).to_string(), "0 < x \\leq 1 \\neq y");
+        assert_eq!(math!(a == b).to_string(), "a = b");
+    }
+
+    #[test]
+    fn operators_bounds() {
+        assert_eq!(
+            math!(int(x = 0..inf, exp(-x) * x) = 1).to_string(),
+            "\\int_{0}^{\\infty} \\exp(-x) x \\,dx = 1"
+        );
+        assert_eq!(
+            math!(iint((x, y) in D, f(x, y)) + 1).to_string(),
+            "\\iint_{D} f(x, y) \\,dx \\,dy + 1"
+        );
+        assert_eq!(math!(oint(z, g(z))).to_string(), "\\oint g(z) \\,dz");
+        assert_eq!(
+            math!(lim(n -> inf, (1 + 1 / n) ^ n)).to_string(),
+            "\\lim_{n \\to \\infty} \\left( 1 + \\frac{1}{n} \\right)^{n}"
+        );
+        assert_eq!(
+            math!(union(i = 1..=n, A[i]) - inter(i, B[i])).to_string(),
+            "\\left( \\bigcup_{i = 1}^{n} A_{i} \\right) - \\bigcap_{i} B_{i}"
+        );
+        assert_eq!(
+            math!(max(x in {1, 2}, x) = max(1, 2)).to_string(),
+            "\\max_{x \\in \\{1, 2\\}} x = \\max(1, 2)"
+        );
+        assert_eq!(
+            math!(sup(x in {}, x)).to_string(),
+            "\\sup_{x \\in \\emptyset} x"
+        );
+        assert_eq!(
+            math!(argmin(theta, L(theta))).to_string(),
+            "\\operatorname*{arg\\,min}_{\\theta} L(\\theta)"
+        );
+        let mut packages = Packages::new();
+        math!(iint((x, y) in D, 1)).add_packages(&mut packages);
+        assert!(packages.contains("amsmath"));
+    }
+
+    #[test]
+    fn cases() {
+        let sign = math!(sgn(x) = cases(-1 => x < 0, 0 => x == 0, 1 => "otherwise"));
+        assert_eq!(
+            sign.to_string(),
+            "\\operatorname{sgn}(x) = \\begin{cases} -1 & x < 0 \\\\ 0 & x = 0 \\\\ 1 & \\text{otherwise} \\end{cases}"
+        );
+        let mut packages = Packages::new();
+        Expr::cases(vec![(Expr::num(0), Expr::var("x"))]).add_packages(&mut packages);
+        assert!(packages.contains("amsmath"));
+    }
+
+    #[test]
+    fn limits_placement() {
+        let mut sum = Expr::big_op(
+            BigOp::Sum,
+            Bounds::range(Expr::var("k"), Expr::num(1), Expr::infinity()),
+            Expr::frac(Expr::num(1), Expr::pow(Expr::var("k"), Expr::num(2))),
+        );
+        assert_eq!(sum.to_string(), "\\sum_{k = 1}^{\\infty} \\frac{1}{k^{2}}");
+        sum.set_limits_placement(LimitsPlacement::Limits);
+        assert_eq!(
+            sum.to_string(),
+            "\\sum\\limits_{k = 1}^{\\infty} \\frac{1}{k^{2}}"
+        );
+        sum.set_limits_placement(LimitsPlacement::Inline);
+        assert!(sum.to_string().starts_with("\\sum\\nolimits_"));
+        let mut eq = math!(x = sum(i, y[i]) + sum(j, z[j]));
+        eq.set_limits_placement(LimitsPlacement::Display);
+        assert_eq!(
+            eq.to_string(),
+            "x = {\\displaystyle \\sum_{i} y_{i}} + {\\displaystyle \\sum_{j} z_{j}}"
+        );
+        let bound: Expr = VarOrImm::new(10).into();
+        assert_eq!(bound, Expr::num(10));
+    }
+}
@@ -0,0 +1,217 @@
+/// File defining the figures made of several images side by side (subfigures of subcaption)
+///
+use error::*;
+use escape::Text;
+use graphics::*;
+use labels::*;
+use packages::Packages;
+use std::io::Write;
+use writable::*;
+
+#[derive(Clone)]
+pub struct Figure {
+    /// The images, each one in a subfigure with its own caption and label
+    subfigures: Vec<Graphic>,
+    /// The number of subfigures per row (all of them on one row if None)
+    columns: Option<usize>,
+    caption: Option<Text>,
+    /// The caption in the list of figures
+    short_caption: Option<Text>,
+    /// The placement of the float (htbp by default)
+    placement: Option<String>,
+    label: Option<Label>,
+}
+
+impl Figure {
+    /// Returns a new Figure, placed with [htbp]
+    pub fn new(subfigures: Vec<Graphic>) -> Self {
+        Figure {
+            subfigures,
+            columns: None,
+            caption: None,
+            short_caption: None,
+            placement: Some("htbp".to_string()),
+            label: None,
+        }
+    }
+
+    /// Returns a new Figure with a caption
+    pub fn with_caption<T: AsRef<str>>(subfigures: Vec<Graphic>, caption: T) -> Self {
+        let mut figure = Figure::new(subfigures);
+        figure.set_caption(caption);
+        figure
+    }
+
+    /// Adds an image at the end of the figure
+    pub fn add(&mut self, subfigure: Graphic) {
+        self.subfigures.push(subfigure);
+    }
+
+    /// Returns the images of the figure
+    pub fn get_subfigures(&self) -> &[Graphic] {
+        &self.subfigures
+    }
+
+    /// Changes the number of images per row
+    pub fn set_columns(&mut self, columns: usize) {
+        self.columns = Some(columns);
+    }
+
+    /// Changes the caption of the figure
+    pub fn set_caption<T: AsRef<str>>(&mut self, caption: T) {
+        self.caption = Some(Text::new(caption));
+    }
+
+    /// Changes the caption written in the list of figures
+    pub fn set_short_caption<T: AsRef<str>>(&mut self, caption: T) {
+        self.short_caption = Some(Text::new(caption));
+    }
+
+    /// Changes the placement of the float (h, t, b, p, H, ...)
+    pub fn set_placement<T: AsRef<str>>(&mut self, placement: T) {
+        self.placement = Some(placement.as_ref().to_string());
+    }
+
+    /// Removes the placement of the float (LaTeX then uses its default)
+    pub fn remove_placement(&mut self) {
+        self.placement = None;
+    }
+
+    /// Labels the figure, and returns the handle to refer to it
+    pub fn set_label<T: AsRef<str>>(&mut self, name: T) -> Label {
+        let label = Label::new(LabelKind::Figure, name);
+        self.label = Some(label.clone());
+        label
+    }
+
+    /// Returns the label of the figure
+    pub fn get_label(&self) -> Option<&Label> {
+        self.label.as_ref()
+    }
+
+    /// Returns the labels of the subfigures
+    pub fn get_subfigure_labels(&self) -> Vec<&Label> {
+        self.subfigures
+            .iter()
+            .filter_map(|g| g.get_label())
+            .collect()
+    }
+
+    /// Returns the width of a subfigure, leaving some space between the images of a row
+    fn subfigure_width(&self) -> Length {
+        let columns = self.columns.unwrap_or(self.subfigures.len()).max(1) as f64;
+        let width = (1.0 - 0.02 * (columns - 1.0)) / columns;
+        Length::Linewidth((width * 100.0).round() / 100.0)
+    }
+}
+
+impl Writable for Figure {
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        let caption = caption_line(
+            self.caption.as_ref(),
+            self.short_caption.as_ref(),
+            self.label.as_ref(),
+        )?;
+        match self.placement {
+            Some(ref placement) => writeln!(buf, "\\begin{{figure}}[{}]", placement)?,
+            None => writeln!(buf, "\\begin{{figure}}")?,
+        }
+        writeln!(buf, "\\centering")?;
+        let width = self.subfigure_width();
+        let columns = self.columns.unwrap_or(self.subfigures.len()).max(1);
+        for (i, subfigure) in self.subfigures.iter().enumerate() {
+            if i > 0 && i % columns == 0 {
+                writeln!(buf, "\\par\\medskip")?;
+            } else if i > 0 {
+                writeln!(buf, "\\hfill")?;
+            }
+            writeln!(buf, "\\begin{{subfigure}}[b]{{{}}}", width)?;
+            writeln!(buf, "\\centering")?;
+            subfigure.write_image(buf, Some(&Length::Linewidth(1.0)))?;
+            writeln!(buf)?;
+            if let Some(caption) = subfigure.caption_line()? {
+                writeln!(buf, "{}", caption)?;
+            }
+            writeln!(buf, "\\end{{subfigure}}")?;
+        }
+        if let Some(caption) = caption {
+            writeln!(buf, "{}", caption)?;
+        }
+        writeln!(buf, "\\end{{figure}}")?;
+        Ok(())
+    }
+
+    fn add_packages(&self, packages: &mut Packages) {
+        for subfigure in self.subfigures.iter() {
+            subfigure.add_packages(packages);
+        }
+        packages.add_package("subcaption");
+        if self.placement.as_ref().is_some_and(|p| p.contains('H')) {
+            packages.add_package("float");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_figure {
+    use super::*;
+    use plot::Plot;
+
+    #[test]
+    fn subfigures() {
+        let mut left = Graphic::inline("left.png");
+        left.set_caption("Left");
+        left.set_label("left");
+        let mut right = Graphic::inline("right.png");
+        right.set_caption("Right");
+        right.set_height(Length::fixed("3cm"));
+        let mut figure = Figure::with_caption(vec![left, right], "Both sides");
+        figure.set_label("both");
+        assert_eq!(
+            figure.to_latex().unwrap(),
+            "\\begin{figure}[htbp]\n\
+             \\centering\n\
+             \\begin{subfigure}[b]{0.49\\linewidth}\n\
+             \\centering\n\
+             \\includegraphics[width=\\linewidth]{left.png}\n\
+             \\caption{Left}\\label{fig:left}\n\
+             \\end{subfigure}\n\
+             \\hfill\n\
+             \\begin{subfigure}[b]{0.49\\linewidth}\n\
+             \\centering\n\
+             \\includegraphics[height=3cm]{right.png}\n\
+             \\caption{Right}\n\
+             \\end{subfigure}\n\
+             \\caption{Both sides}\\label{fig:both}\n\
+             \\end{figure}\n"
+        );
+        assert_eq!(figure.get_subfigure_labels().len(), 1);
+        let mut packages = Packages::new();
+        figure.add_packages(&mut packages);
+        assert!(packages.contains("subcaption"));
+    }
+
+    #[test]
+    fn plot_subfigure() {
+        let figure = Figure::new(vec![Graphic::from_plot(Plot::new())]);
+        assert!(figure
+            .to_latex()
+            .unwrap()
+            .contains("\\begin{tikzpicture}\n\\begin{axis}[width=\\linewidth]\n\\end{axis}"));
+    }
+
+    #[test]
+    fn grid() {
+        let images = (0..4)
+            .map(|i| Graphic::inline(format!("{}.png", i)))
+            .collect();
+        let mut figure = Figure::new(images);
+        figure.set_columns(2);
+        figure.remove_placement();
+        let latex = figure.to_latex().unwrap();
+        assert!(latex.starts_with("\\begin{figure}\n"));
+        assert_eq!(latex.matches("\\par\\medskip").count(), 1);
+        assert_eq!(latex.matches("\\hfill").count(), 2);
+        assert!(!latex.contains("\\caption"));
+    }
+}
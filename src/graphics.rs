@@ -1,39 +1,443 @@
 /// Implements the includegraphics command
 ///
 ///
-use latex_file::LatexFile;
-use std::io::BufWriter;
+use error::*;
+use escape::Text;
+use labels::*;
+use packages::Packages;
+use plot::Plot;
+use std::fmt;
 use std::io::Write;
 use writable::*;
 
+/// A length: a fraction of the width of the line or of the text, or an absolute length
+#[derive(Clone, Debug, PartialEq)]
+pub enum Length {
+    /// A fraction of \linewidth (0.5 is half the line)
+    Linewidth(f64),
+    /// A fraction of \textwidth
+    Textwidth(f64),
+    /// A length written verbatim (3cm, 2in, 100pt, ...)
+    Fixed(String),
+}
+
+impl Length {
+    /// Returns a length written verbatim (3cm, 2in, 100pt, ...)
+    pub fn fixed<T: AsRef<str>>(length: T) -> Self {
+        Length::Fixed(length.as_ref().to_string())
+    }
+}
+
+impl fmt::Display for Length {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Length::Linewidth(1.0) => write!(f, "\\linewidth"),
+            Length::Linewidth(w) => write!(f, "{}\\linewidth", w),
+            Length::Textwidth(1.0) => write!(f, "\\textwidth"),
+            Length::Textwidth(w) => write!(f, "{}\\textwidth", w),
+            Length::Fixed(ref length) => write!(f, "{}", length),
+        }
+    }
+}
+
+/// What a graphic shows: an image file, or a plot drawn by pgfplots
+#[derive(Clone)]
+enum Source {
+    File(String),
+    Plot(Box<Plot>),
+}
+
 #[derive(Clone)]
 pub struct Graphic {
-    filename: String,
-    scale: f64,
-    description: String,
+    source: Source,
+    /// The scale of the image (1 if no size is given)
+    scale: Option<f64>,
+    width: Option<Length>,
+    height: Option<Length>,
+    /// The rotation, in degrees
+    angle: Option<f64>,
+    /// The lengths trimmed from the left, bottom, right and top of the image
+    trim: Option<Vec<String>>,
+    /// If true, the trimmed parts are not drawn
+    clip: bool,
+    caption: Option<Text>,
+    /// The caption in the list of figures
+    short_caption: Option<Text>,
+    /// The placement of the float (none by default)
+    placement: Option<String>,
+    /// If true, the image is written without a float (a bare \includegraphics)
+    inline: bool,
+    label: Option<Label>,
 }
 
 impl Graphic {
     pub fn new(filename: String, description: String) -> Self {
+        let mut graphic = Graphic::inline(filename);
+        graphic.inline = false;
+        graphic.caption = Some(Text::new(description));
+        graphic
+    }
+
+    /// Returns a new Graphic written without a float, nor caption (a bare \includegraphics)
+    pub fn inline<T: AsRef<str>>(filename: T) -> Self {
+        Graphic::with_source(Source::File(filename.as_ref().to_string()))
+    }
+
+    /// Returns a new figure drawing the plot, without caption
+    /// (the width and the height of the graphic are the ones of the axis)
+    pub fn from_plot(plot: Plot) -> Self {
+        let mut graphic = Graphic::with_source(Source::Plot(Box::new(plot)));
+        graphic.inline = false;
+        graphic
+    }
+
+    fn with_source(source: Source) -> Self {
         Graphic {
-            filename,
-            scale: 1.0,
-            description,
+            source,
+            scale: None,
+            width: None,
+            height: None,
+            angle: None,
+            trim: None,
+            clip: false,
+            caption: None,
+            short_caption: None,
+            placement: None,
+            inline: true,
+            label: None,
+        }
+    }
+
+    /// Returns the name of the image file (None for a plot)
+    pub fn get_filename(&self) -> Option<&str> {
+        match self.source {
+            Source::File(ref filename) => Some(filename),
+            Source::Plot(_) => None,
+        }
+    }
+
+    /// Returns the plot drawn by the graphic
+    pub fn get_plot(&self) -> Option<&Plot> {
+        match self.source {
+            Source::File(_) => None,
+            Source::Plot(ref plot) => Some(plot),
         }
     }
 
     pub fn set_scale(&mut self, new_scale: f64) {
-        self.scale = new_scale;
+        self.scale = Some(new_scale);
+    }
+
+    /// Changes the width of the image (the height follows, unless it is also given)
+    pub fn set_width(&mut self, width: Length) {
+        self.width = Some(width);
+    }
+
+    /// Changes the height of the image (the width follows, unless it is also given)
+    pub fn set_height(&mut self, height: Length) {
+        self.height = Some(height);
+    }
+
+    /// Rotates the image, in degrees (counterclockwise)
+    pub fn set_angle(&mut self, angle: f64) {
+        self.angle = Some(angle);
+    }
+
+    /// Trims the image (e.g. "1cm"), from the left, bottom, right and top
+    /// (only for the image files)
+    pub fn set_trim<T: AsRef<str>>(&mut self, left: T, bottom: T, right: T, top: T) {
+        self.trim = Some(
+            [left, bottom, right, top]
+                .iter()
+                .map(|l| l.as_ref().to_string())
+                .collect(),
+        );
+    }
+
+    /// Enables or disables the clipping of the trimmed parts
+    pub fn set_clip(&mut self, clip: bool) {
+        self.clip = clip;
+    }
+
+    /// Changes the caption of the figure
+    pub fn set_caption<T: AsRef<str>>(&mut self, caption: T) {
+        self.caption = Some(Text::new(caption));
+    }
+
+    /// Removes the caption of the figure
+    pub fn remove_caption(&mut self) {
+        self.caption = None;
+    }
+
+    /// Returns the caption of the figure
+    pub fn get_caption(&self) -> Option<&str> {
+        self.caption.as_ref().map(|c| c.content())
+    }
+
+    /// Changes the caption written in the list of figures
+    pub fn set_short_caption<T: AsRef<str>>(&mut self, caption: T) {
+        self.short_caption = Some(Text::new(caption));
+    }
+
+    /// Changes the placement of the float (h, t, b, p, H, ...)
+    pub fn set_placement<T: AsRef<str>>(&mut self, placement: T) {
+        self.placement = Some(placement.as_ref().to_string());
+    }
+
+    /// Writes the image without a float, or in a figure
+    pub fn set_inline(&mut self, inline: bool) {
+        self.inline = inline;
+    }
+
+    /// Returns true if the image is written without a float
+    pub fn is_inline(&self) -> bool {
+        self.inline
+    }
+
+    /// Labels the figure, and returns the handle to refer to it
+    pub fn set_label<T: AsRef<str>>(&mut self, name: T) -> Label {
+        let label = Label::new(LabelKind::Figure, name);
+        self.label = Some(label.clone());
+        label
+    }
+
+    /// Returns the label of the figure
+    pub fn get_label(&self) -> Option<&Label> {
+        self.label.as_ref()
+    }
+
+    /// Enables or disables the escaping of the caption (to put LaTeX code in it)
+    pub fn set_raw_description(&mut self, raw: bool) {
+        if let Some(ref mut caption) = self.caption {
+            caption.set_raw(raw);
+        }
+        if let Some(ref mut caption) = self.short_caption {
+            caption.set_raw(raw);
+        }
+    }
+
+    /// Writes the \includegraphics command, or the tikzpicture of the plot
+    /// Without a size, the image takes the default width (or scale=1)
+    pub fn write_image<W: Write + ?Sized>(
+        &self,
+        buf: &mut W,
+        default_width: Option<&Length>,
+    ) -> Result<()> {
+        let filename = match self.source {
+            Source::File(ref filename) => filename,
+            Source::Plot(ref plot) => {
+                let mut picture = Vec::new();
+                if let Some(scale) = self.scale {
+                    picture.push(format!("scale={}", scale));
+                }
+                if let Some(angle) = self.angle {
+                    picture.push(format!("rotate={}", angle));
+                }
+                let sized = self.width.is_some() || self.height.is_some() || self.scale.is_some();
+                let width = self
+                    .width
+                    .as_ref()
+                    .or(if sized { None } else { default_width });
+                return plot.write_picture(buf, &picture, width, self.height.as_ref());
+            }
+        };
+        let mut options = Vec::new();
+        if let Some(ref width) = self.width {
+            options.push(format!("width={}", width));
+        }
+        if let Some(ref height) = self.height {
+            options.push(format!("height={}", height));
+        }
+        if let Some(scale) = self.scale {
+            options.push(format!("scale={}", scale));
+        }
+        if options.is_empty() {
+            match default_width {
+                Some(width) => options.push(format!("width={}", width)),
+                None => options.push("scale=1".to_string()),
+            }
+        }
+        if let Some(angle) = self.angle {
+            options.push(format!("angle={}", angle));
+        }
+        if let Some(ref trim) = self.trim {
+            options.push(format!("trim={}", trim.join(" ")));
+        }
+        if self.clip {
+            options.push("clip".to_string());
+        }
+        write!(
+            buf,
+            "\\includegraphics[{}]{{{}}}",
+            options.join(", "),
+            filename
+        )?;
+        Ok(())
+    }
+
+    /// Returns the caption and the label, as written on one line
+    pub fn caption_line(&self) -> Result<Option<String>> {
+        caption_line(
+            self.caption.as_ref(),
+            self.short_caption.as_ref(),
+            self.label.as_ref(),
+        )
+    }
+}
+
+/// Returns the caption (with the short caption) and the label of a figure, on one line
+/// A label needs a caption, which gives its number
+pub fn caption_line(
+    caption: Option<&Text>,
+    short_caption: Option<&Text>,
+    label: Option<&Label>,
+) -> Result<Option<String>> {
+    match caption {
+        Some(caption) => {
+            let mut line = match short_caption {
+                Some(short) => format!("\\caption[{}]{{{}}}", short, caption),
+                None => format!("\\caption{{{}}}", caption),
+            };
+            if let Some(label) = label {
+                line.push_str(&label.to_latex()?);
+            }
+            Ok(Some(line))
+        }
+        None => match label {
+            Some(label) => Err(Error::InvalidStructure(format!(
+                "The figure {} needs a caption to be referenced",
+                label.key()
+            ))),
+            None => Ok(None),
+        },
     }
 }
 
 impl Writable for Graphic {
-    fn write_latex(&self, file: &mut LatexFile) {
-        let mut writer = BufWriter::new(file);
-        self.write_to_buffer(&mut writer);
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        if self.inline {
+            if self.caption.is_some() || self.label.is_some() {
+                return Err(Error::InvalidStructure(format!(
+                    "The image {} is written without a float, it can not have a caption nor a label",
+                    self.get_filename().unwrap_or("(plot)")
+                )));
+            }
+            return self.write_image(buf, None);
+        }
+        match self.placement {
+            Some(ref placement) => write!(buf, "\\begin{{figure}}[{}]\n\t", placement)?,
+            None => write!(buf, "\\begin{{figure}}\n\t")?,
+        }
+        self.write_image(buf, None)?;
+        if let Some(caption) = self.caption_line()? {
+            write!(buf, "\n\t{}", caption)?;
+        }
+        writeln!(buf, "\n\\end{{figure}}")?;
+        Ok(())
+    }
+
+    fn add_packages(&self, packages: &mut Packages) {
+        match self.source {
+            Source::File(_) => packages.add_package("graphicx"),
+            Source::Plot(ref plot) => plot.add_packages(packages),
+        }
+        if self.placement.as_ref().is_some_and(|p| p.contains('H')) {
+            packages.add_package("float");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_graphics {
+    use super::*;
+    use plot::Series;
+
+    #[test]
+    fn escaped_caption() {
+        let mut g = Graphic::new("logo.jpg".to_string(), "The 1st_logo".to_string());
+        assert_eq!(
+            g.to_latex().unwrap(),
+            "\\begin{figure}\n\t\\includegraphics[scale=1]{logo.jpg}\n\t\\caption{The 1st\\_logo}\n\\end{figure}\n"
+        );
+        g.set_raw_description(true);
+        assert!(g.to_latex().unwrap().contains("\\caption{The 1st_logo}"));
+    }
+
+    #[test]
+    fn labelled_figure() {
+        let mut g = Graphic::new("logo.jpg".to_string(), "Logo".to_string());
+        g.set_label("logo");
+        assert!(g
+            .to_latex()
+            .unwrap()
+            .contains("\\caption{Logo}\\label{fig:logo}\n\\end{figure}"));
+    }
+
+    #[test]
+    fn sized_figure() {
+        let mut g = Graphic::new("plot.pdf".to_string(), "Results".to_string());
+        g.set_width(Length::Linewidth(0.8));
+        g.set_height(Length::fixed("5cm"));
+        g.set_angle(90.0);
+        g.set_trim("1cm", "0", "1cm", "0");
+        g.set_clip(true);
+        g.set_placement("htbp");
+        g.set_short_caption("Results");
+        g.set_caption("Results of the 3 runs");
+        assert_eq!(
+            g.to_latex().unwrap(),
+            "\\begin{figure}[htbp]\n\
+             \t\\includegraphics[width=0.8\\linewidth, height=5cm, angle=90, trim=1cm 0 1cm 0, clip]{plot.pdf}\n\
+             \t\\caption[Results]{Results of the 3 runs}\n\
+             \\end{figure}\n"
+        );
+        g.remove_caption();
+        g.set_width(Length::Textwidth(1.0));
+        assert!(g
+            .to_latex()
+            .unwrap()
+            .contains("[width=\\textwidth, height=5cm, angle=90, trim=1cm 0 1cm 0, clip]{plot.pdf}\n\\end{figure}"));
+        g.set_label("plot");
+        match g.to_latex() {
+            Err(Error::InvalidStructure(_)) => (),
+            _ => panic!("A labelled figure needs a caption"),
+        }
+    }
+
+    #[test]
+    fn plot_in_a_float() {
+        let mut plot = Plot::new();
+        plot.add(Series::line(vec![(0, 1), (1, 2)]));
+        let mut g = Graphic::from_plot(plot);
+        g.set_caption("Growth");
+        g.set_label("growth");
+        g.set_angle(90.0);
+        g.set_height(Length::fixed("4cm"));
+        assert_eq!(
+            g.to_latex().unwrap(),
+            "\\begin{figure}\n\t\\begin{tikzpicture}[rotate=90]\n\
+             \\begin{axis}[height=4cm]\n\
+             \\addplot+[mark=none] coordinates { (0,1) (1,2) };\n\
+             \\end{axis}\n\
+             \\end{tikzpicture}\n\
+             \t\\caption{Growth}\\label{fig:growth}\n\
+             \\end{figure}\n"
+        );
+        assert_eq!(g.get_filename(), None);
+        let mut packages = Packages::new();
+        g.add_packages(&mut packages);
+        assert!(packages.contains("pgfplots") && !packages.contains("graphicx"));
     }
 
-    fn write_to_buffer(&self, mut buf: &mut BufWriter<&mut LatexFile>) {
-        writeln!(&mut buf, "\\begin{{figure}}\n\t\\includegraphics[scale={}]{{{}}}\n\t\\caption{{{}}}\n\\end{{figure}}", self.scale, self.filename, self.description).unwrap();
+    #[test]
+    fn inline_graphic() {
+        let mut g = Graphic::inline("icon.png");
+        g.set_height(Length::fixed("1em"));
+        assert_eq!(
+            g.to_latex().unwrap(),
+            "\\includegraphics[height=1em]{icon.png}"
+        );
+        g.set_caption("Icon");
+        assert!(g.to_latex().is_err());
     }
 }
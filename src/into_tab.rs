@@ -1,23 +1,159 @@
 /// Implement the trait IntoTab
 ///
 use core::Core;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
 
 pub trait IntoTab {
+    #[allow(clippy::wrong_self_convention)]
     fn into_tab(&self) -> Vec<Vec<Core>>;
 }
 
+/// A row of a tabular: each value is a cell
+pub trait IntoRow {
+    fn to_row(&self) -> Vec<Core>;
+}
+
 impl IntoTab for Vec<Core> {
     fn into_tab(&self) -> Vec<Vec<Core>> {
-        let mut res = Vec::new();
-        res.push(self.to_vec());
-        res
+        vec![self.to_vec()]
+    }
+}
+
+impl<R: IntoRow> IntoTab for [R] {
+    fn into_tab(&self) -> Vec<Vec<Core>> {
+        self.iter().map(|row| row.to_row()).collect()
+    }
+}
+
+impl<R: IntoRow> IntoTab for Vec<R> {
+    fn into_tab(&self) -> Vec<Vec<Core>> {
+        self.as_slice().into_tab()
+    }
+}
+
+impl<R: IntoRow, const N: usize> IntoTab for [R; N] {
+    fn into_tab(&self) -> Vec<Vec<Core>> {
+        self[..].into_tab()
     }
 }
 
-impl IntoTab for Vec<Vec<Core>> {
+/// One row per key, sorted by key: key | value
+impl<K: Display, V: Display> IntoTab for HashMap<K, V> {
     fn into_tab(&self) -> Vec<Vec<Core>> {
+        let mut rows: Vec<(String, &V)> = self.iter().map(|(k, v)| (k.to_string(), v)).collect();
+        rows.sort_by(|a, b| a.0.cmp(&b.0));
+        rows.iter().map(|&(ref k, v)| (k, v).to_row()).collect()
+    }
+}
+
+/// One row per key: key | value
+impl<K: Display, V: Display> IntoTab for BTreeMap<K, V> {
+    fn into_tab(&self) -> Vec<Vec<Core>> {
+        self.iter().map(|(k, v)| (k, v).to_row()).collect()
+    }
+}
+
+impl IntoRow for Vec<Core> {
+    fn to_row(&self) -> Vec<Core> {
         self.to_vec()
     }
 }
 
+impl<T: Display> IntoRow for [T] {
+    fn to_row(&self) -> Vec<Core> {
+        self.iter().map(|v| Core::text(v.to_string())).collect()
+    }
+}
+
+impl<T: Display> IntoRow for Vec<T> {
+    fn to_row(&self) -> Vec<Core> {
+        self.as_slice().to_row()
+    }
+}
+
+impl<T: Display, const N: usize> IntoRow for [T; N] {
+    fn to_row(&self) -> Vec<Core> {
+        self[..].to_row()
+    }
+}
+
+impl<R: IntoRow + ?Sized> IntoRow for &R {
+    fn to_row(&self) -> Vec<Core> {
+        (**self).to_row()
+    }
+}
+
+/// Implements IntoRow for the tuples of values which can be displayed
+macro_rules! tuple_into_row {
+    ($($name:ident),+) => {
+        impl<$($name: Display),+> IntoRow for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn to_row(&self) -> Vec<Core> {
+                let ($(ref $name,)+) = *self;
+                vec![$(Core::text($name.to_string())),+]
+            }
+        }
+    };
+}
+
+tuple_into_row!(A);
+tuple_into_row!(A, B);
+tuple_into_row!(A, B, C);
+tuple_into_row!(A, B, C, D);
+tuple_into_row!(A, B, C, D, E);
+tuple_into_row!(A, B, C, D, E, F);
+tuple_into_row!(A, B, C, D, E, F, G);
+tuple_into_row!(A, B, C, D, E, F, G, H);
+
+#[cfg(test)]
+mod tests_into_tab {
+    use super::*;
+    use writable::*;
 
+    fn latex(rows: Vec<Vec<Core>>) -> Vec<Vec<String>> {
+        rows.iter()
+            .map(|row| row.iter().map(|c| c.to_latex().unwrap()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn numeric_matrix() {
+        let matrix = vec![vec![1, 2], vec![3, 4]];
+        assert_eq!(
+            latex(matrix.into_tab()),
+            vec![vec!["1", "2"], vec!["3", "4"]]
+        );
+        let array = [[1.5, 2.0], [3.25, 4.0]];
+        assert_eq!(
+            latex(array.into_tab()),
+            vec![vec!["1.5", "2"], vec!["3.25", "4"]]
+        );
+        let slice: &[Vec<&str>] = &[vec!["a_b"]];
+        assert_eq!(latex(slice.into_tab()), vec![vec!["a\\_b"]]);
+    }
+
+    #[test]
+    fn tuples() {
+        let rows = vec![("Rust", 2015, 1.0), ("C", 1972, 0.5)];
+        assert_eq!(
+            latex(rows.into_tab()),
+            vec![vec!["Rust", "2015", "1"], vec!["C", "1972", "0.5"]]
+        );
+    }
+
+    #[test]
+    fn maps() {
+        let mut map = HashMap::new();
+        map.insert("b", 2);
+        map.insert("a", 1);
+        assert_eq!(latex(map.into_tab()), vec![vec!["a", "1"], vec!["b", "2"]]);
+        let mut map = BTreeMap::new();
+        map.insert(2, "two");
+        map.insert(1, "one");
+        assert_eq!(
+            latex(map.into_tab()),
+            vec![vec!["1", "one"], vec!["2", "two"]]
+        );
+    }
+}
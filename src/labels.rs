@@ -0,0 +1,289 @@
+/// File defining the labels of the elements (\label)
+/// and the references to them (\ref, \eqref, \pageref, \autoref)
+///
+use core::*;
+use error::*;
+use packages::Packages;
+use std::io::Write;
+use writable::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LabelKind {
+    Section,
+    Equation,
+    Figure,
+    Table,
+}
+
+impl LabelKind {
+    /// Returns the prefix of the keys of this kind of label
+    pub fn prefix(&self) -> &str {
+        match *self {
+            LabelKind::Section => "sec",
+            LabelKind::Equation => "eq",
+            LabelKind::Figure => "fig",
+            LabelKind::Table => "tab",
+        }
+    }
+}
+
+/// The handle of a labelled element, used to create references to it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Label {
+    /// The kind of the labelled element
+    kind: LabelKind,
+    /// The name of the label, given by the user
+    name: String,
+}
+
+impl Label {
+    /// Returns a new Label
+    pub fn new<T: AsRef<str>>(kind: LabelKind, name: T) -> Self {
+        Label {
+            kind,
+            name: name.as_ref().to_string(),
+        }
+    }
+
+    /// Returns the kind of the labelled element
+    pub fn get_kind(&self) -> LabelKind {
+        self.kind
+    }
+
+    /// Returns the name of the label
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the LaTeX key of the label (e.g. sec:intro)
+    pub fn key(&self) -> String {
+        format!("{}:{}", self.kind.prefix(), self.name)
+    }
+
+    /// Returns true if the key can be used in LaTeX
+    pub fn is_valid(&self) -> bool {
+        !self.name.is_empty()
+            && !self
+                .name
+                .chars()
+                .any(|c| "\\{}#%$&^~,".contains(c) || c.is_whitespace())
+    }
+}
+
+impl Writable for Label {
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        write!(buf, "\\label{{{}}}", self.key())?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RefStyle {
+    /// \ref
+    Ref,
+    /// \eqref (amsmath)
+    EqRef,
+    /// \pageref
+    PageRef,
+    /// \autoref (hyperref)
+    AutoRef,
+}
+
+impl RefStyle {
+    /// Returns the LaTeX command of the style
+    pub fn command(&self) -> &str {
+        match *self {
+            RefStyle::Ref => "\\ref",
+            RefStyle::EqRef => "\\eqref",
+            RefStyle::PageRef => "\\pageref",
+            RefStyle::AutoRef => "\\autoref",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reference {
+    /// The label of the referenced element
+    label: Label,
+    /// The command used to refer to it
+    style: RefStyle,
+}
+
+impl Reference {
+    /// Returns a new Reference, \eqref for the equations and \ref otherwise
+    pub fn new(label: &Label) -> Self {
+        let style = match label.get_kind() {
+            LabelKind::Equation => RefStyle::EqRef,
+            _ => RefStyle::Ref,
+        };
+        Reference::with_style(label, style)
+    }
+
+    /// Returns a new Reference with the given style
+    pub fn with_style(label: &Label, style: RefStyle) -> Self {
+        Reference {
+            label: label.clone(),
+            style,
+        }
+    }
+
+    /// Returns the label of the referenced element
+    pub fn get_label(&self) -> &Label {
+        &self.label
+    }
+
+    /// Returns the style of the reference
+    pub fn get_style(&self) -> RefStyle {
+        self.style
+    }
+}
+
+impl Writable for Reference {
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        write!(buf, "{}{{{}}}", self.style.command(), self.label.key())?;
+        Ok(())
+    }
+
+    fn add_packages(&self, packages: &mut Packages) {
+        match self.style {
+            RefStyle::EqRef => packages.add_package("amsmath"),
+            RefStyle::AutoRef => packages.add_package("hyperref"),
+            _ => (),
+        }
+    }
+}
+
+/// Checks that no label is defined twice and that every reference points to a label
+pub fn check_references(elements: &[Core]) -> Result<()> {
+    let mut labels: Vec<Label> = Vec::new();
+    let mut references: Vec<Label> = Vec::new();
+    for element in elements.iter() {
+        element.walk(&mut |e: &Core| {
+            if let Some(label) = e.get_label() {
+                labels.push(label.clone());
+            }
+            if let Core::Figure(ref figure) = *e {
+                labels.extend(figure.get_subfigure_labels().into_iter().cloned());
+            }
+            if let Core::MultiEqua(ref eq) = *e {
+                let lines = eq.get_lines().iter().filter_map(|l| l.get_label());
+                labels.extend(lines.cloned());
+            }
+            if let Core::Ref(ref reference) = *e {
+                references.push(reference.get_label().clone());
+            }
+        });
+    }
+    for (i, label) in labels.iter().enumerate() {
+        if !label.is_valid() {
+            return Err(Error::InvalidStructure(format!(
+                "The label '{}' is not a valid LaTeX key",
+                label.key()
+            )));
+        }
+        if labels[..i].iter().any(|l| l.key() == label.key()) {
+            return Err(Error::DuplicateLabel(label.key()));
+        }
+    }
+    for reference in references.iter() {
+        if !labels.iter().any(|l| l.key() == reference.key()) {
+            return Err(Error::DanglingReference(reference.key()));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests_labels {
+    use super::*;
+    use equations::*;
+
+    #[test]
+    fn label_key() {
+        let label = Label::new(LabelKind::Figure, "logo");
+        assert_eq!(label.key(), "fig:logo");
+        assert_eq!(label.to_latex().unwrap(), "\\label{fig:logo}");
+    }
+
+    #[test]
+    fn default_reference_style() {
+        let eq = Label::new(LabelKind::Equation, "euler");
+        let sec = Label::new(LabelKind::Section, "intro");
+        assert_eq!(Reference::new(&eq).to_latex().unwrap(), "\\eqref{eq:euler}");
+        assert_eq!(Reference::new(&sec).to_latex().unwrap(), "\\ref{sec:intro}");
+        assert_eq!(
+            Reference::with_style(&sec, RefStyle::PageRef)
+                .to_latex()
+                .unwrap(),
+            "\\pageref{sec:intro}"
+        );
+    }
+
+    #[test]
+    fn labelled_section() {
+        let mut sec = Core::section("Introduction");
+        let intro = sec.set_label("intro").unwrap();
+        assert_eq!(
+            sec.to_latex().unwrap(),
+            "\\section{Introduction}\\label{sec:intro}\n\n"
+        );
+        assert_eq!(intro, Label::new(LabelKind::Section, "intro"));
+    }
+
+    #[test]
+    fn labelled_equation() {
+        let mut eq = Core::equation(new_equation(&["a", "=", "b"]));
+        let label = eq.set_label("ab").unwrap();
+        assert_eq!(label.get_kind(), LabelKind::Equation);
+        assert_eq!(
+            eq.to_latex().unwrap(),
+            "\\begin{equation}\n\\label{eq:ab}\n\\displaystyle a  =  b \n\\end{equation}\n"
+        );
+    }
+
+    #[test]
+    fn no_label_on_text() {
+        let mut text = Core::text("Text");
+        assert!(text.set_label("text").is_err());
+    }
+
+    #[test]
+    fn valid_references() {
+        let mut sec = Core::section("Introduction");
+        let intro = sec.set_label("intro").unwrap();
+        let mut other = Core::section("Other");
+        other.add(Core::reference(&intro)).unwrap();
+        assert!(check_references(&[sec, other]).is_ok());
+    }
+
+    #[test]
+    fn dangling_reference() {
+        let mut sec = Core::section("Introduction");
+        sec.add(Core::reference(&Label::new(LabelKind::Figure, "missing")))
+            .unwrap();
+        match check_references(&[sec]) {
+            Err(Error::DanglingReference(key)) => assert_eq!(key, "fig:missing"),
+            _ => panic!("The dangling reference should be reported"),
+        }
+    }
+
+    #[test]
+    fn duplicate_label() {
+        let mut s1 = Core::section("Introduction");
+        s1.set_label("intro").unwrap();
+        let mut s2 = Core::subsection("Introduction again");
+        s2.set_label("intro").unwrap();
+        s1.add(s2).unwrap();
+        match check_references(&[s1]) {
+            Err(Error::DuplicateLabel(key)) => assert_eq!(key, "sec:intro"),
+            _ => panic!("The duplicate label should be reported"),
+        }
+    }
+
+    #[test]
+    fn invalid_label() {
+        let mut sec = Core::section("Introduction");
+        sec.set_label("my intro").unwrap();
+        assert!(check_references(&[sec]).is_err());
+    }
+}
@@ -1,25 +1,30 @@
 /// File implementing the LatexFile type
+use document_class::DocumentClass;
+use error::*;
+use packages::Packages;
+use preamble::Preamble;
 use std::fs::File;
 use std::io::BufWriter;
 use std::io::{self, Write};
+use writable::Writable;
 
 // pub type LatexFile = File;
 
-pub struct LatexFile {
-    /// The file where everything will be written
-    file: File,
-    /// The title of the document
-    title: Option<String>,
-    /// The name of the authors
-    author: Option<String>,
-    /// The names of the packages
-    packages: Vec<String>,
-    // The style of the document (article, book, ...)
-    // style: String,
+pub struct LatexFile<'a> {
+    /// The sink where everything will be written
+    file: Box<dyn Write + 'a>,
+    /// The class, title, authors and packages of the document
+    preamble: Preamble,
+    /// True once the \\documentclass line is written (before anything else)
+    class_written: bool,
 }
 
-impl Write for LatexFile {
+impl<'a> Write for LatexFile<'a> {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_class().map_err(|e| match e {
+            Error::Io(e) => e,
+            e => io::Error::other(e.to_string()),
+        })?;
         self.file.write(buf)
     }
 
@@ -28,106 +33,193 @@ impl Write for LatexFile {
     }
 }
 
-impl LatexFile {
-    /// Writes raw text in the file
-    fn write_in_file(&mut self, line: &str) {
-        let mut buf = BufWriter::new(&mut self.file);
-        write!(&mut buf, "{}", line.to_string()).unwrap();
+impl<'a> LatexFile<'a> {
+    /// Writes the \\documentclass line, if it is not written yet
+    /// The class can not be changed once something is written in the file
+    fn write_class(&mut self) -> Result<()> {
+        if !self.class_written {
+            self.class_written = true;
+            let mut buf = BufWriter::new(&mut self.file);
+            self.preamble
+                .get_document_class()
+                .write_to_buffer(&mut buf)?;
+            buf.flush()?;
+        }
+        Ok(())
     }
 
-    /// Writes the document class if ir is an article
-    pub fn write_header_article(&mut self) {
-        self.write_in_file("\\documentclass[a4paper,11pt]{article}\n");
+    /// Writes the document class of an article (a4paper, 11pt)
+    #[deprecated(note = "the class is written before the first line, see document_class")]
+    pub fn write_header_article(&mut self) -> Result<()> {
+        if !self.class_written {
+            self.document_class(DocumentClass::default());
+        }
+        self.write_class()
+    }
+
+    /// Writes raw text in the file
+    fn write_in_file(&mut self, line: &str) -> Result<()> {
+        self.write_class()?;
+        let mut buf = BufWriter::new(&mut self.file);
+        write!(buf, "{}", line)?;
+        buf.flush()?;
+        Ok(())
     }
 
     /// Writes the footer of the class
-    pub fn write_footer(&mut self) {
-        self.write_in_file("\\end{document}\n");
+    pub fn write_footer(&mut self) -> Result<()> {
+        self.write_in_file("\\end{document}\n")
     }
 
     /// Writes an package in the file
-    pub fn write_package<T: AsRef<str>>(&mut self, package: T) {
+    pub fn write_package<T: AsRef<str>>(&mut self, package: T) -> Result<()> {
+        self.write_class()?;
         let mut buf = BufWriter::new(&mut self.file);
-        writeln!(&mut buf, "\\usepackage{{{}}}", package.as_ref().to_string()).unwrap();
+        writeln!(buf, "\\usepackage{{{}}}", package.as_ref())?;
+        buf.flush()?;
+        Ok(())
+    }
+
+    /// Change the class of the document (article, report, book, ...)
+    pub fn document_class(&mut self, class: DocumentClass) {
+        self.preamble.document_class(class);
+    }
+
+    /// Adds an option to the class of the document (a4paper, twocolumn, draft, ...)
+    pub fn add_class_option<T: AsRef<str>>(&mut self, option: T) {
+        self.preamble.document_class_mut().add_option(option);
     }
 
     /// Change the title of the document
     pub fn title<T: AsRef<str>>(&mut self, new_title: T) {
-        self.title = Some(new_title.as_ref().to_string());
+        self.preamble.title(new_title);
+    }
+
+    /// Change the title of the document, written verbatim (trusted LaTeX code)
+    pub fn title_raw<T: AsRef<str>>(&mut self, new_title: T) {
+        self.preamble.title_raw(new_title);
     }
 
     /// Change the author of the document
     pub fn author<T: AsRef<str>>(&mut self, new_author: T) {
-        self.author = Some(new_author.as_ref().to_string());
+        self.preamble.author(new_author);
+    }
+
+    /// Change the author of the document, written verbatim (e.g. with \and or \thanks)
+    pub fn author_raw<T: AsRef<str>>(&mut self, new_author: T) {
+        self.preamble.author_raw(new_author);
     }
 
     /// Removes the title of the document
     pub fn remove_title(&mut self) {
-        self.title = None;
+        self.preamble.remove_title();
     }
 
     /// Removes the author of the document
     pub fn remove_author(&mut self) {
-        self.author = None;
+        self.preamble.remove_author();
     }
 
     /// Adds a package
     pub fn add_package<T: AsRef<str>>(&mut self, package: T) {
-        self.packages.push(package.as_ref().to_string());
+        self.preamble.add_package(package);
+    }
+
+    /// Adds a package with options (e.g. \usepackage[utf8]{inputenc})
+    pub fn add_package_with_options<T1: AsRef<str>, T2: AsRef<str>>(
+        &mut self,
+        package: T1,
+        options: Vec<T2>,
+    ) {
+        self.preamble.add_package_with_options(package, options);
+    }
+
+    /// Returns the packages, to change their load order
+    pub fn packages_mut(&mut self) -> &mut Packages {
+        self.preamble.packages_mut()
     }
 
     /// Adds a list of packages
     pub fn add_package_list<T: AsRef<str>>(&mut self, packages: Vec<T>) {
-        for package in packages.iter() {
-            // self.add_include(*include);
-            self.packages.push(package.as_ref().to_string());
-        }
+        self.preamble.add_package_list(packages);
     }
 
-    /// Writes the title, authors, packages, ... and begin the document
-    pub fn begin_document(&mut self) {
+    /// Writes a table of contents after the title
+    pub fn set_table_of_contents(&mut self, enabled: bool) {
+        self.preamble.set_table_of_contents(enabled);
+    }
+
+    /// Writes a list of figures after the title
+    pub fn set_list_of_figures(&mut self, enabled: bool) {
+        self.preamble.set_list_of_figures(enabled);
+    }
+
+    /// Writes a list of tables after the title
+    pub fn set_list_of_tables(&mut self, enabled: bool) {
+        self.preamble.set_list_of_tables(enabled);
+    }
+
+    /// Adds the packages needed by the element (to call before begin_document)
+    pub fn add_packages_of<T: Writable>(&mut self, element: &T) {
+        element.add_packages(self.preamble.packages_mut());
+    }
+
+    /// Replaces the whole preamble of the document
+    pub fn set_preamble(&mut self, preamble: Preamble) {
+        self.preamble = preamble;
+    }
+
+    /// Writes the class, title, authors, packages, ... and begin the document
+    pub fn begin_document(&mut self) -> Result<()> {
+        self.write_class()?;
         {
             let mut buf = BufWriter::new(&mut self.file);
-            /* ----- INCLUDES ----- */
-            for package in self.packages.iter() {
-                writeln!(&mut buf, "\\usepackage{{{}}}", package.to_string()).unwrap();
-            }
-
-            /* ----- TITLE ----- */
-            match self.title {
-                None => {}
-                Some(ref t) => {
-                    writeln!(&mut buf, "\\title{{{}}}", t.to_string()).unwrap();
-                }
-            };
-
-            /* ----- AUTHOR ----- */
-            match self.author {
-                None => {}
-                Some(ref auth) => {
-                    writeln!(&mut buf, "\\author{{{}}}", auth.to_string()).unwrap();
-                }
-            };
-
-            writeln!(&mut buf, "\\date{{}}").unwrap();
+            self.preamble.write_without_class(&mut buf)?;
+            buf.flush()?;
         }
 
-        self.write_in_file("\\begin{document}\n");
-        if self.title != None || self.author != None {
-            self.write_in_file("\\maketitle\n");
-        }
+        self.write_in_file("\\begin{document}\n")?;
+        let mut buf = BufWriter::new(&mut self.file);
+        self.preamble.write_front_matter(&mut buf)?;
+        buf.flush()?;
+        Ok(())
     }
 }
+
 /// Returns a new LatexFile
-pub fn new_latex_file<T: AsRef<str>>(filename: T) -> LatexFile {
-    let f = File::create(filename.as_ref().to_string()).unwrap();
-    let mut ltx_file = LatexFile {
-        file: f,
-        title: None,
-        author: None,
-        packages: Vec::new(),
-        // style: "article".to_string(),
-    };
-    ltx_file.write_header_article();
-    ltx_file
+pub fn new_latex_file<T: AsRef<str>>(filename: T) -> Result<LatexFile<'static>> {
+    let f = File::create(filename.as_ref())?;
+    latex_file_from_writer(f)
+}
+
+/// Returns a new LatexFile writing into any sink (a Vec<u8>, stdout, ...)
+/// The class is written before the first line (an article if it is not changed)
+pub fn latex_file_from_writer<'a, W: Write + 'a>(writer: W) -> Result<LatexFile<'a>> {
+    Ok(LatexFile {
+        file: Box::new(writer),
+        preamble: Preamble::new(),
+        class_written: false,
+    })
+}
+
+#[cfg(test)]
+mod tests_latex_file {
+    use super::*;
+
+    #[test]
+    #[allow(deprecated)]
+    fn header_article() {
+        let mut out = Vec::new();
+        {
+            let mut f = latex_file_from_writer(&mut out).unwrap();
+            f.document_class(DocumentClass::report());
+            f.write_header_article().unwrap();
+            f.write_header_article().unwrap();
+            f.begin_document().unwrap();
+        }
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\\documentclass[a4paper,11pt]{article}\n\\date{}\n\\begin{document}\n"
+        );
+    }
 }
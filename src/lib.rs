@@ -1,3 +1,8 @@
+#[cfg(feature = "serde")]
+extern crate serde;
+
+#[macro_use]
+pub mod expr;
 pub mod sections;
 pub mod equations;
 pub mod core;
@@ -9,10 +14,31 @@ pub mod writable;
 pub mod symbols;
 pub mod tag;
 pub mod tabular;
+pub mod table;
 pub mod into_tab;
+pub mod csv;
+pub mod number;
+#[cfg(feature = "serde")]
+pub mod records;
 pub mod math_mode;
+pub mod matrix;
 pub mod graphics;
+pub mod figure;
+pub mod plot;
+pub mod assets;
 pub mod content_from_file;
+pub mod preamble;
+pub mod document;
+pub mod document_class;
+pub mod packages;
+pub mod labels;
+pub mod outline;
+pub mod bibliography;
+pub mod bibtex;
+pub mod error;
+pub mod escape;
+
+pub use error::{Error, Result};
 
 #[cfg(test)]
 mod tests {
@@ -1,27 +1,44 @@
-use latex_file::LatexFile;
 /// Math mode
-use std::io::BufWriter;
+use error::*;
+use expr::Expr;
+use packages::Packages;
 use std::io::Write;
 use writable::*;
 
 #[derive(Clone)]
 pub struct MathContent {
     content: String,
+    /// The expression written, for its packages
+    expr: Option<Expr>,
 }
 
 impl MathContent {
     pub fn new(content: String) -> Self {
-        MathContent { content }
+        MathContent {
+            content,
+            expr: None,
+        }
+    }
+}
+
+impl From<Expr> for MathContent {
+    fn from(expr: Expr) -> Self {
+        MathContent {
+            content: expr.to_string(),
+            expr: Some(expr),
+        }
     }
 }
 
 impl Writable for MathContent {
-    fn write_latex(&self, file: &mut LatexFile) {
-        let mut writer = BufWriter::new(file);
-        self.write_to_buffer(&mut writer);
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        write!(buf, "${}$", self.content)?;
+        Ok(())
     }
 
-    fn write_to_buffer(&self, mut buf: &mut BufWriter<&mut LatexFile>) {
-        write!(&mut buf, "${}$", self.content).unwrap();
+    fn add_packages(&self, packages: &mut Packages) {
+        if let Some(ref expr) = self.expr {
+            expr.add_packages(packages);
+        }
     }
 }
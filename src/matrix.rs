@@ -0,0 +1,324 @@
+/// File defining the matrices and the vectors of the math expressions,
+/// built from Rust arrays, with the large ones elided around their corners
+///
+use expr::Expr;
+use number::NumberFormat;
+use std::fmt;
+
+/// The delimiters of a matrix
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatrixStyle {
+    /// No delimiters (matrix)
+    Plain,
+    /// ( ) (pmatrix)
+    Paren,
+    /// [ ] (bmatrix)
+    Bracket,
+    /// | | for the determinants (vmatrix)
+    Bar,
+    /// \| \| for the norms (Vmatrix)
+    DoubleBar,
+}
+
+impl MatrixStyle {
+    /// Returns the environment of amsmath
+    pub fn environment(&self) -> &'static str {
+        match *self {
+            MatrixStyle::Plain => "matrix",
+            MatrixStyle::Paren => "pmatrix",
+            MatrixStyle::Bracket => "bmatrix",
+            MatrixStyle::Bar => "vmatrix",
+            MatrixStyle::DoubleBar => "Vmatrix",
+        }
+    }
+
+    /// Returns the left and the right delimiters, for the matrices written in an array
+    fn delimiters(&self) -> (&'static str, &'static str) {
+        match *self {
+            MatrixStyle::Plain => ("", ""),
+            MatrixStyle::Paren => ("\\left( ", " \\right)"),
+            MatrixStyle::Bracket => ("\\left[ ", " \\right]"),
+            MatrixStyle::Bar => ("\\left| ", " \\right|"),
+            MatrixStyle::DoubleBar => ("\\left\\| ", " \\right\\|"),
+        }
+    }
+}
+
+/// A matrix of expressions
+#[derive(Clone, Debug, PartialEq)]
+pub struct Matrix {
+    rows: Vec<Vec<Expr>>,
+    style: MatrixStyle,
+    /// The vertical rules, after the given number of columns (augmented matrices)
+    separators: Vec<usize>,
+    /// The number of rows and columns written before the large matrices are elided
+    max_size: Option<(usize, usize)>,
+    /// The format of the numbers
+    format: Option<NumberFormat>,
+}
+
+impl Matrix {
+    /// Returns a new Matrix, between parentheses
+    pub fn new(rows: Vec<Vec<Expr>>) -> Self {
+        Matrix {
+            rows,
+            style: MatrixStyle::Paren,
+            separators: Vec::new(),
+            max_size: None,
+            format: None,
+        }
+    }
+
+    /// Returns the matrix of the numbers, from a Vec<Vec<T>> or an array [[T; N]; M]
+    pub fn from_values<R, C, T>(rows: R) -> Self
+    where
+        R: IntoIterator<Item = C>,
+        C: IntoIterator<Item = T>,
+        T: fmt::Display,
+    {
+        Matrix::new(
+            rows.into_iter()
+                .map(|row| row.into_iter().map(Expr::num).collect())
+                .collect(),
+        )
+    }
+
+    /// Returns the column vector of the numbers
+    pub fn column<I, T>(values: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: fmt::Display,
+    {
+        Matrix::from_values(values.into_iter().map(|v| vec![v]))
+    }
+
+    /// Returns the rows of the matrix
+    pub fn get_rows(&self) -> &[Vec<Expr>] {
+        &self.rows
+    }
+
+    /// Returns the rows of the matrix, to change their entries
+    pub fn get_rows_mut(&mut self) -> &mut [Vec<Expr>] {
+        &mut self.rows
+    }
+
+    /// Changes the delimiters of the matrix
+    pub fn set_style(&mut self, style: MatrixStyle) {
+        self.style = style;
+    }
+
+    /// Returns the delimiters of the matrix
+    pub fn get_style(&self) -> MatrixStyle {
+        self.style
+    }
+
+    /// Adds a vertical rule after the column (1 for a rule after the first column)
+    pub fn add_separator(&mut self, column: usize) {
+        self.separators.push(column);
+    }
+
+    /// Elides the rows and the columns after the given numbers: the first ones and the
+    /// last one are written, with \cdots, \vdots and \ddots in between
+    pub fn set_max_size(&mut self, rows: usize, columns: usize) {
+        self.max_size = Some((rows.max(2), columns.max(2)));
+    }
+
+    /// Changes the format of the numbers of the matrix
+    pub fn set_number_format(&mut self, format: NumberFormat) {
+        self.format = Some(format);
+    }
+
+    /// Returns the number of columns (the longest row)
+    fn columns(&self) -> usize {
+        self.rows.iter().map(|r| r.len()).max().unwrap_or(0)
+    }
+
+    /// Returns the entry, formatted
+    fn entry(&self, row: usize, column: usize) -> String {
+        match self.rows[row].get(column) {
+            Some(Expr::Num(n)) => self
+                .format
+                .as_ref()
+                .and_then(|f| f.format(n))
+                .unwrap_or_else(|| n.to_string()),
+            Some(e) => e.to_string(),
+            None => String::new(),
+        }
+    }
+
+    /// Returns the indices written, None for the ellipsis
+    fn kept(count: usize, max: Option<usize>) -> Vec<Option<usize>> {
+        match max {
+            Some(max) if count > max => (0..max - 1)
+                .map(Some)
+                .chain(vec![None, Some(count - 1)])
+                .collect(),
+            _ => (0..count).map(Some).collect(),
+        }
+    }
+
+    /// Returns the entries written, with the ellipses
+    fn grid(&self) -> Vec<Vec<String>> {
+        let rows = Matrix::kept(self.rows.len(), self.max_size.map(|m| m.0));
+        let columns = Matrix::kept(self.columns(), self.max_size.map(|m| m.1));
+        rows.iter()
+            .map(|row| {
+                columns
+                    .iter()
+                    .map(|column| match (*row, *column) {
+                        (Some(r), Some(c)) => self.entry(r, c),
+                        (Some(_), None) => "\\cdots".to_string(),
+                        (None, Some(_)) => "\\vdots".to_string(),
+                        (None, None) => "\\ddots".to_string(),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns the position of the rule in the written columns
+    fn separator_position(&self, column: usize) -> usize {
+        let columns = self.columns();
+        match self.max_size {
+            Some((_, max)) if columns > max => {
+                if column < max {
+                    column
+                } else if column >= columns {
+                    max + 1
+                } else {
+                    max
+                }
+            }
+            _ => column,
+        }
+    }
+}
+
+impl fmt::Display for Matrix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let grid = self.grid();
+        let rows: Vec<String> = grid.iter().map(|r| r.join(" & ")).collect();
+        let body = rows.join(" \\\\ ");
+        if self.separators.is_empty() {
+            let environment = self.style.environment();
+            return write!(f, "\\begin{{{0}}} {1} \\end{{{0}}}", environment, body);
+        }
+        // The environments of amsmath have no rules: an array between delimiters
+        let written = grid.first().map(|r| r.len()).unwrap_or(0);
+        let separators: Vec<usize> = self
+            .separators
+            .iter()
+            .map(|s| self.separator_position(*s))
+            .collect();
+        let mut spec = String::new();
+        for column in 0..=written {
+            for _ in separators.iter().filter(|s| **s == column) {
+                spec.push('|');
+            }
+            if column < written {
+                spec.push('c');
+            }
+        }
+        let (left, right) = self.style.delimiters();
+        write!(
+            f,
+            "{}\\begin{{array}}{{{}}} {} \\end{{array}}{}",
+            left, spec, body, right
+        )
+    }
+}
+
+impl From<Matrix> for Expr {
+    fn from(matrix: Matrix) -> Self {
+        Expr::Matrix(Box::new(matrix))
+    }
+}
+
+#[cfg(test)]
+mod tests_matrix {
+    use super::*;
+    use equations::Equation;
+    use math_mode::MathContent;
+    use packages::Packages;
+    use symbols::Symbols;
+    use writable::*;
+
+    #[test]
+    fn styles() {
+        let mut m = Matrix::from_values([[1, 0], [0, 1]]);
+        assert_eq!(
+            m.to_string(),
+            "\\begin{pmatrix} 1 & 0 \\\\ 0 & 1 \\end{pmatrix}"
+        );
+        m.set_style(MatrixStyle::DoubleBar);
+        assert!(m.to_string().starts_with("\\begin{Vmatrix}"));
+        let v = Matrix::column(vec![1.5, -2.0]);
+        assert_eq!(v.to_string(), "\\begin{pmatrix} 1.5 \\\\ -2 \\end{pmatrix}");
+        let v = Matrix::column((0..2usize).map(|i| u64::MAX - i as u64));
+        assert_eq!(
+            v.to_string(),
+            "\\begin{pmatrix} 18446744073709551615 \\\\ 18446744073709551614 \\end{pmatrix}"
+        );
+        let m = Matrix::from_values(vec![vec![-3i64]]);
+        assert_eq!(m.to_string(), "\\begin{pmatrix} -3 \\end{pmatrix}");
+        let symbolic = Matrix::new(vec![
+            vec![Expr::var("a"), Expr::var("b")],
+            vec![Expr::var("c"), Expr::var("d")],
+        ]);
+        let mut det = symbolic.clone();
+        det.set_style(MatrixStyle::Bar);
+        assert_eq!(
+            det.to_string(),
+            "\\begin{vmatrix} a & b \\\\ c & d \\end{vmatrix}"
+        );
+    }
+
+    #[test]
+    fn augmented() {
+        let mut m = Matrix::from_values(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        m.set_style(MatrixStyle::Bracket);
+        m.add_separator(2);
+        assert_eq!(
+            m.to_string(),
+            "\\left[ \\begin{array}{cc|c} 1 & 2 & 3 \\\\ 4 & 5 & 6 \\end{array} \\right]"
+        );
+    }
+
+    #[test]
+    fn elision_and_format() {
+        let rows: Vec<Vec<f64>> = (0..10)
+            .map(|i| (0..10).map(|j| (i * 10 + j) as f64 / 3.0).collect())
+            .collect();
+        let mut m = Matrix::from_values(rows);
+        m.set_max_size(3, 3);
+        m.set_number_format(NumberFormat::fixed(1));
+        assert_eq!(
+            m.to_string(),
+            "\\begin{pmatrix} 0.0 & 0.3 & \\cdots & 3.0 \\\\ \
+             3.3 & 3.7 & \\cdots & 6.3 \\\\ \
+             \\vdots & \\vdots & \\ddots & \\vdots \\\\ \
+             30.0 & 30.3 & \\cdots & 33.0 \\end{pmatrix}"
+        );
+        m.add_separator(9);
+        assert!(m.to_string().contains("\\begin{array}{ccc|c}"));
+    }
+
+    #[test]
+    fn in_equations() {
+        let m = Matrix::from_values([[1, 2], [3, 4]]);
+        let eq = Equation::from(Expr::relation(
+            Expr::var("A"),
+            vec![(Symbols::Equals, m.clone().into())],
+        ));
+        let latex = eq.to_latex().unwrap();
+        assert!(latex.contains("A = \\begin{pmatrix} 1 & 2 \\\\ 3 & 4 \\end{pmatrix}"));
+        let inline = MathContent::from(Expr::from(m));
+        assert_eq!(
+            inline.to_latex().unwrap(),
+            "$\\begin{pmatrix} 1 & 2 \\\\ 3 & 4 \\end{pmatrix}$"
+        );
+        let mut packages = Packages::new();
+        inline.add_packages(&mut packages);
+        assert!(packages.contains("amsmath"));
+    }
+}
@@ -0,0 +1,314 @@
+/// File defining the numbers of the tabulars: rounding (decimals or significant figures),
+/// uncertainty (1.23 ± 0.04) and bold, their alignment in the siunitx S columns
+/// and the formatting of the numbers of the CSV files and the matrices
+///
+use error::*;
+use std::io::Write;
+use writable::*;
+
+/// The rounding of a number
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Precision {
+    /// The number is written as displayed by Rust
+    Exact,
+    /// A fixed number of decimals
+    Decimals(usize),
+    /// A number of significant figures
+    Significant(usize),
+}
+
+/// A numeric value, with an optional uncertainty
+#[derive(Clone, Debug, PartialEq)]
+pub struct Number {
+    /// The value
+    value: f64,
+    /// The uncertainty, written as value ± uncertainty
+    uncertainty: Option<f64>,
+    /// The rounding of the value (and of the uncertainty, to the same decimals)
+    precision: Precision,
+    /// If true, the number is written in bold
+    bold: bool,
+}
+
+impl Number {
+    /// Returns a new Number, written as displayed by Rust
+    pub fn new(value: f64) -> Self {
+        Number {
+            value,
+            uncertainty: None,
+            precision: Precision::Exact,
+            bold: false,
+        }
+    }
+
+    /// Returns a new Number with an uncertainty
+    pub fn with_uncertainty(value: f64, uncertainty: f64) -> Self {
+        let mut number = Number::new(value);
+        number.set_uncertainty(uncertainty);
+        number
+    }
+
+    /// Returns the value
+    pub fn get_value(&self) -> f64 {
+        self.value
+    }
+
+    /// Changes the uncertainty
+    pub fn set_uncertainty(&mut self, uncertainty: f64) {
+        self.uncertainty = Some(uncertainty);
+    }
+
+    /// Returns the uncertainty
+    pub fn get_uncertainty(&self) -> Option<f64> {
+        self.uncertainty
+    }
+
+    /// Changes the rounding of the number
+    pub fn set_precision(&mut self, precision: Precision) {
+        self.precision = precision;
+    }
+
+    /// Rounds the number to a fixed number of decimals
+    pub fn set_decimals(&mut self, decimals: usize) {
+        self.precision = Precision::Decimals(decimals);
+    }
+
+    /// Rounds the number to a number of significant figures
+    pub fn set_significant(&mut self, figures: usize) {
+        self.precision = Precision::Significant(figures);
+    }
+
+    /// Returns the rounding of the number
+    pub fn get_precision(&self) -> Precision {
+        self.precision
+    }
+
+    /// Writes the number in bold, or not
+    pub fn set_bold(&mut self, bold: bool) {
+        self.bold = bold;
+    }
+
+    /// Returns true if the number is written in bold
+    pub fn is_bold(&self) -> bool {
+        self.bold
+    }
+
+    /// Returns the number of decimals of the rounding, and the power of ten
+    /// to round to when the significant figures stop before the decimal point
+    fn rounding(&self) -> Option<(usize, i32)> {
+        match self.precision {
+            Precision::Exact => None,
+            Precision::Decimals(decimals) => Some((decimals, 0)),
+            Precision::Significant(figures) => {
+                let figures = figures.max(1) as i32;
+                let mut magnitude = if self.value == 0.0 || !self.value.is_finite() {
+                    0
+                } else {
+                    self.value.abs().log10().floor() as i32
+                };
+                // 9.99 rounded to 2 figures is 10: a figure more before the decimal point
+                let scaled = self.value.abs() * 10f64.powi(figures - 1 - magnitude);
+                if scaled.is_finite() && scaled.round() >= 10f64.powi(figures) {
+                    magnitude += 1;
+                }
+                let decimals = figures - 1 - magnitude;
+                if decimals >= 0 {
+                    Some((decimals as usize, 0))
+                } else {
+                    Some((0, -decimals))
+                }
+            }
+        }
+    }
+
+    /// Returns a value written with the rounding of the number
+    fn format(&self, value: f64) -> String {
+        match self.rounding() {
+            None => value.to_string(),
+            Some((decimals, 0)) => format!("{:.*}", decimals, value),
+            Some((_, power)) => {
+                let factor = 10f64.powi(power);
+                format!("{:.0}", (value / factor).round() * factor)
+            }
+        }
+    }
+
+    /// Returns the value as written (rounded), to compare the numbers of a column
+    pub fn rounded(&self) -> f64 {
+        self.format(self.value).parse().unwrap_or(self.value)
+    }
+
+    /// Returns the number as read by siunitx, e.g. in a S column: 1.23 \pm 0.04
+    pub fn siunitx(&self) -> String {
+        let mut out = if self.bold {
+            "\\bfseries ".to_string()
+        } else {
+            String::new()
+        };
+        out.push_str(&self.format(self.value));
+        if let Some(uncertainty) = self.uncertainty {
+            out.push_str(&format!(" \\pm {}", self.format(uncertainty)));
+        }
+        out
+    }
+}
+
+impl Writable for Number {
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        let value = self.format(self.value);
+        match self.uncertainty {
+            Some(uncertainty) => {
+                let number = format!("{} \\pm {}", value, self.format(uncertainty));
+                if self.bold {
+                    write!(buf, "$\\mathbf{{{}}}$", number)?
+                } else {
+                    write!(buf, "${}$", number)?
+                }
+            }
+            // The minus sign is only written as such in math mode
+            None if self.value < 0.0 && self.bold => write!(buf, "$\\mathbf{{{}}}$", value)?,
+            None if self.value < 0.0 => write!(buf, "${}$", value)?,
+            None if self.bold => write!(buf, "\\textbf{{{}}}", value)?,
+            None => write!(buf, "{}", value)?,
+        }
+        Ok(())
+    }
+}
+
+/// The formatting of the numbers of a CSV column or a matrix
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NumberFormat {
+    /// The number of decimals, None to keep the number as written
+    decimals: Option<usize>,
+    /// The separator of the thousands (LaTeX code, e.g. "{,}" or "\\,")
+    thousands_separator: Option<String>,
+}
+
+impl NumberFormat {
+    /// Returns a new NumberFormat, keeping the numbers as written
+    pub fn new() -> Self {
+        NumberFormat::default()
+    }
+
+    /// Returns a new NumberFormat with a fixed number of decimals
+    pub fn fixed(decimals: usize) -> Self {
+        NumberFormat {
+            decimals: Some(decimals),
+            thousands_separator: None,
+        }
+    }
+
+    /// Changes the number of decimals
+    pub fn set_decimals(&mut self, decimals: usize) {
+        self.decimals = Some(decimals);
+    }
+
+    /// Changes the separator of the thousands, written verbatim (e.g. "{,}" or "\\,")
+    pub fn set_thousands_separator<T: AsRef<str>>(&mut self, separator: T) {
+        self.thousands_separator = Some(separator.as_ref().to_string());
+    }
+
+    /// Returns the formatted number, or None if the value is not a number
+    pub fn format<T: AsRef<str>>(&self, value: T) -> Option<String> {
+        let value = value.as_ref().trim();
+        let number: f64 = value.parse().ok()?;
+        let mut out = match self.decimals {
+            Some(decimals) => format!("{:.*}", decimals, number),
+            None => value.to_string(),
+        };
+        if let Some(ref separator) = self.thousands_separator {
+            out = group_thousands(&out, separator);
+        }
+        Some(out)
+    }
+}
+
+/// Inserts the separator between the groups of three digits of the integer part
+fn group_thousands(number: &str, separator: &str) -> String {
+    let (sign, unsigned) = match number.chars().next() {
+        Some(c) if c == '-' || c == '+' => number.split_at(1),
+        _ => ("", number),
+    };
+    let (integer, rest) = match unsigned.find('.') {
+        Some(i) => unsigned.split_at(i),
+        None => (unsigned, ""),
+    };
+    if !integer.chars().all(|c| c.is_ascii_digit()) {
+        // 1e10, inf, ...
+        return number.to_string();
+    }
+    let mut out = sign.to_string();
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            out.push_str(separator);
+        }
+        out.push(digit);
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests_number {
+    use super::*;
+
+    #[test]
+    fn rounding() {
+        let mut number = Number::new(5.46789);
+        assert_eq!(number.to_latex().unwrap(), "5.46789");
+        number.set_decimals(2);
+        assert_eq!(number.to_latex().unwrap(), "5.47");
+        number.set_significant(4);
+        assert_eq!(number.to_latex().unwrap(), "5.468");
+        let mut number = Number::new(0.0012345);
+        number.set_significant(2);
+        assert_eq!(number.to_latex().unwrap(), "0.0012");
+        let mut number = Number::new(123456.0);
+        number.set_significant(2);
+        assert_eq!(number.to_latex().unwrap(), "120000");
+        assert_eq!(number.rounded(), 120000.0);
+        let mut number = Number::new(9.99);
+        number.set_significant(2);
+        assert_eq!(number.to_latex().unwrap(), "10");
+        number.set_significant(3);
+        assert_eq!(number.to_latex().unwrap(), "9.99");
+        let mut number = Number::with_uncertainty(0.0996, 0.0123);
+        number.set_significant(2);
+        assert_eq!(number.to_latex().unwrap(), "$0.10 \\pm 0.01$");
+        let mut number = Number::new(-99960.0);
+        number.set_significant(3);
+        assert_eq!(number.to_latex().unwrap(), "$-100000$");
+    }
+
+    #[test]
+    fn uncertainty_and_bold() {
+        let mut number = Number::with_uncertainty(1.2345, 0.0412);
+        number.set_decimals(2);
+        assert_eq!(number.to_latex().unwrap(), "$1.23 \\pm 0.04$");
+        assert_eq!(number.siunitx(), "1.23 \\pm 0.04");
+        number.set_bold(true);
+        assert_eq!(number.to_latex().unwrap(), "$\\mathbf{1.23 \\pm 0.04}$");
+        assert_eq!(number.siunitx(), "\\bfseries 1.23 \\pm 0.04");
+        let mut number = Number::new(-2.5);
+        assert_eq!(number.to_latex().unwrap(), "$-2.5$");
+        number.set_bold(true);
+        assert_eq!(number.to_latex().unwrap(), "$\\mathbf{-2.5}$");
+    }
+
+    #[test]
+    fn number_format() {
+        let mut format = NumberFormat::fixed(2);
+        assert_eq!(format.format("3.14159"), Some("3.14".to_string()));
+        format.set_thousands_separator("{,}");
+        assert_eq!(
+            format.format("-1234567.5"),
+            Some("-1{,}234{,}567.50".to_string())
+        );
+        assert_eq!(format.format("12"), Some("12.00".to_string()));
+        assert_eq!(format.format("n/a"), None);
+        let mut format = NumberFormat::new();
+        format.set_thousands_separator("\\,");
+        assert_eq!(format.format(" 1000 "), Some("1\\,000".to_string()));
+        assert_eq!(format.format("1e10"), Some("1e10".to_string()));
+    }
+}
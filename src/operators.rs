@@ -1,7 +1,6 @@
-use latex_file::*;
 /// File defining the structure of an operator
 ///
-use std::io::BufWriter;
+use error::*;
 use std::io::Write;
 use writable::*;
 
@@ -15,7 +14,7 @@ pub trait VarOrImmTrait {
     fn to_var_or_imm(&self) -> VarOrImm;
 }
 
-impl<'a> VarOrImmTrait for &'a str {
+impl VarOrImmTrait for &str {
     fn to_var_or_imm(&self) -> VarOrImm {
         VarOrImm::Var(String::from(*self))
     }
@@ -40,6 +39,8 @@ impl VarOrImm {
     }
 }
 
+/// The bounds are a variable or an integer, and the body is written after the operator
+/// (see expr::BigOp for the big operators with a body and any expression as bounds)
 #[derive(Clone)]
 pub enum Operators {
     /// Sum(variable, begin, end) body
@@ -94,13 +95,9 @@ impl Operators {
 }
 
 impl Writable for Operators {
-    fn write_latex(&self, file: &mut LatexFile) {
-        let mut writer = BufWriter::new(file);
-        self.write_to_buffer(&mut writer);
-    }
-
-    fn write_to_buffer(&self, mut buf: &mut BufWriter<&mut LatexFile>) {
-        write!(&mut buf, "{}", self.latex_code(),).unwrap()
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        write!(buf, "{}", self.latex_code(),)?;
+        Ok(())
     }
 }
 
@@ -108,34 +105,35 @@ impl Writable for Operators {
 mod tests_operators {
     use super::*;
     use equations::*;
+    use latex_file::*;
 
     #[test]
     fn test_sum() {
-        let mut f = new_latex_file("./tests_results/operators/sum.tex");
-        f.begin_document();
+        let mut f = new_latex_file("./tests_results/operators/sum.tex").unwrap();
+        f.begin_document().unwrap();
         let s = Operators::Sum(VarOrImm::new("i"), VarOrImm::new(0), VarOrImm::new("n"));
-        s.write_latex(&mut f);
-        f.write_footer();
+        s.write_latex(&mut f).unwrap();
+        f.write_footer().unwrap();
     }
 
     #[test]
     fn test_prod() {
-        let mut f = new_latex_file("./tests_results/operators/prod.tex");
-        f.begin_document();
+        let mut f = new_latex_file("./tests_results/operators/prod.tex").unwrap();
+        f.begin_document().unwrap();
         let s = Operators::Product(VarOrImm::new("i"), VarOrImm::new(0), VarOrImm::new("n"));
-        s.write_latex(&mut f);
-        f.write_footer();
+        s.write_latex(&mut f).unwrap();
+        f.write_footer().unwrap();
     }
 
     #[test]
     fn test_sum_with_equation() {
-        let mut f = new_latex_file("./tests_results/operators/sum_with_equation.tex");
-        f.begin_document();
+        let mut f = new_latex_file("./tests_results/operators/sum_with_equation.tex").unwrap();
+        f.begin_document().unwrap();
         let s = Operators::Sum(VarOrImm::new("i"), VarOrImm::new(0), VarOrImm::new("n"));
-        let mut eq = new_equation(&vec!["x", "="]);
+        let mut eq = new_equation(&["x", "="]);
         eq.push(EquationElements::Operator(s));
-        eq.write_latex(&mut f);
-        f.write_footer();
+        eq.write_latex(&mut f).unwrap();
+        f.write_footer().unwrap();
     }
 
 }
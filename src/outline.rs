@@ -0,0 +1,210 @@
+/// File defining the outline of a document
+/// (titles, levels and numbering of the sections, computed without LaTeX)
+///
+use core::*;
+use document_class::DocumentClass;
+use sections::*;
+use std::fmt;
+
+/// A section of the outline
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutlineEntry {
+    /// The title of the section (the short title if any, as in the table of contents)
+    pub title: String,
+    /// The level of the section
+    pub level: SectionLevel,
+    /// The number of the section (e.g. 2.1), None if the section is not numbered
+    pub number: Option<String>,
+    /// The key of the label of the section, if any
+    pub label: Option<String>,
+}
+
+/// The list of the sections of a document, in reading order
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Outline {
+    entries: Vec<OutlineEntry>,
+}
+
+impl Outline {
+    /// Computes the outline of the elements, numbered as the class would number them
+    /// (chapters are only numbered in classes with chapters,
+    /// paragraphs and subparagraphs are not numbered)
+    pub fn new(elements: &[Core], class: &DocumentClass) -> Self {
+        let has_chapters = class.has_chapters();
+        // Deepest numbered level (secnumdepth)
+        let max_depth = if has_chapters { 2 } else { 3 };
+        // First level in the numbers (1.2 is the chapter 1 or the section 1)
+        let first_depth = if has_chapters { 0 } else { 1 };
+        let mut part = 0;
+        // Counters of the chapters, sections, ..., subparagraphs
+        let mut counters = [0; 6];
+        let mut entries = Vec::new();
+        for element in elements.iter() {
+            element.walk(&mut |e: &Core| {
+                if let Core::Sec(ref section) = *e {
+                    let level = section.get_level();
+                    let depth = level.depth();
+                    let number = if section.is_starred() || depth > max_depth {
+                        None
+                    } else if level == SectionLevel::Part {
+                        part += 1;
+                        Some(roman(part))
+                    } else if depth < first_depth {
+                        None
+                    } else {
+                        let d = depth as usize;
+                        counters[d] += 1;
+                        for c in counters.iter_mut().skip(d + 1) {
+                            *c = 0;
+                        }
+                        let numbers: Vec<String> = counters[first_depth as usize..=d]
+                            .iter()
+                            .map(|c| c.to_string())
+                            .collect();
+                        Some(numbers.join("."))
+                    };
+                    entries.push(OutlineEntry {
+                        title: section
+                            .get_short_title()
+                            .unwrap_or_else(|| section.get_title())
+                            .to_string(),
+                        level,
+                        number,
+                        label: section.get_label().map(|l| l.key()),
+                    });
+                }
+            });
+        }
+        Outline { entries }
+    }
+
+    /// Returns the sections of the outline
+    pub fn entries(&self) -> &[OutlineEntry] {
+        &self.entries
+    }
+
+    /// Returns the number of sections
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if there is no section
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl fmt::Display for Outline {
+    /// One line per section, indented by level
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let top = self.entries.iter().map(|e| e.level).min();
+        for entry in self.entries.iter() {
+            let indent = entry.level as usize - top.map_or(0, |l| l as usize);
+            write!(f, "{}", "  ".repeat(indent))?;
+            if let Some(ref number) = entry.number {
+                write!(f, "{} ", number)?;
+            }
+            writeln!(f, "{}", entry.title)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the number in upper case roman numerals (the numbering of the parts)
+fn roman(mut n: u32) -> String {
+    let numerals = [
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+    let mut out = String::new();
+    for &(value, numeral) in numerals.iter() {
+        while n >= value {
+            out.push_str(numeral);
+            n -= value;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests_outline {
+    use super::*;
+
+    #[test]
+    fn article_outline() {
+        let mut intro = Core::section("Introduction");
+        intro.set_label("intro").unwrap();
+        intro.add(Core::subsection("Context")).unwrap();
+        intro.add(Core::subsection("Goals")).unwrap();
+        let mut starred = Core::section("Acknowledgements");
+        starred.set_starred(true);
+        let mut method = Core::section("Method");
+        let mut sub = Core::subsection("Data");
+        let mut subsub = Core::subsubsection("Sources");
+        subsub.add(Core::paragraph("Remark")).unwrap();
+        sub.add(subsub).unwrap();
+        method.add(sub).unwrap();
+        let body = vec![intro, starred, method];
+        let outline = Outline::new(&body, &DocumentClass::article());
+        let numbers: Vec<Option<&str>> = outline
+            .entries()
+            .iter()
+            .map(|e| e.number.as_deref())
+            .collect();
+        assert_eq!(
+            numbers,
+            vec![
+                Some("1"),
+                Some("1.1"),
+                Some("1.2"),
+                None,
+                Some("2"),
+                Some("2.1"),
+                Some("2.1.1"),
+                None
+            ]
+        );
+        assert_eq!(outline.entries()[0].label, Some("sec:intro".to_string()));
+        assert_eq!(
+            outline.to_string(),
+            "1 Introduction\n  1.1 Context\n  1.2 Goals\nAcknowledgements\n2 Method\n  2.1 Data\n    2.1.1 Sources\n      Remark\n"
+        );
+    }
+
+    #[test]
+    fn book_outline() {
+        let mut part = Core::part("Theory");
+        let mut chapter = Core::chapter("A long title");
+        chapter.set_short_title("Basics");
+        let mut sec = Core::section("First");
+        sec.add(Core::subsection("Deep")).unwrap();
+        sec.add(Core::subsubsection("Too deep")).unwrap();
+        chapter.add(sec).unwrap();
+        part.add(chapter).unwrap();
+        let body = vec![part, Core::part("Practice"), Core::chapter("Second")];
+        let outline = Outline::new(&body, &DocumentClass::book());
+        assert_eq!(outline.len(), 7);
+        assert_eq!(
+            outline.to_string(),
+            "I Theory\n  1 Basics\n    1.1 First\n      1.1.1 Deep\n        Too deep\nII Practice\n  2 Second\n"
+        );
+    }
+
+    #[test]
+    fn roman_numerals() {
+        assert_eq!(roman(4), "IV");
+        assert_eq!(roman(14), "XIV");
+        assert_eq!(roman(1999), "MCMXCIX");
+    }
+}
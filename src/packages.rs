@@ -0,0 +1,334 @@
+/// File defining the packages loaded in the preamble
+/// with their options and their load order
+///
+use error::*;
+use std::io::Write;
+use writable::*;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Package {
+    /// The name of the package
+    name: String,
+    /// The options of the package (utf8, margin=2cm, ...)
+    options: Vec<String>,
+}
+
+impl Package {
+    /// Returns a new Package without options
+    pub fn new<T: AsRef<str>>(name: T) -> Self {
+        Package {
+            name: name.as_ref().to_string(),
+            options: Vec::new(),
+        }
+    }
+
+    /// Returns a new Package with a list of options
+    pub fn with_options<T1: AsRef<str>, T2: AsRef<str>>(name: T1, options: Vec<T2>) -> Self {
+        let mut package = Package::new(name);
+        for option in options.iter() {
+            package.add_option(option);
+        }
+        package
+    }
+
+    /// Returns the name of the package
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the options of the package
+    pub fn get_options(&self) -> &[String] {
+        &self.options
+    }
+
+    /// Adds an option to the package
+    /// A `key=value` option replaces the previous value of the key
+    pub fn add_option<T: AsRef<str>>(&mut self, option: T) {
+        let option = option.as_ref().trim().to_string();
+        match option.find('=') {
+            Some(i) => {
+                let key = option[..i].trim().to_string();
+                let position = self
+                    .options
+                    .iter()
+                    .position(|o| o.split('=').next().map(|k| k.trim()) == Some(&key));
+                match position {
+                    Some(j) => self.options[j] = option,
+                    None => self.options.push(option),
+                }
+            }
+            None => {
+                if !self.options.contains(&option) {
+                    self.options.push(option);
+                }
+            }
+        }
+    }
+
+    /// Merges the options of an other package into this one
+    fn merge(&mut self, other: &Package) {
+        for option in other.options.iter() {
+            self.add_option(option);
+        }
+    }
+}
+
+impl Writable for Package {
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        if self.options.is_empty() {
+            writeln!(buf, "\\usepackage{{{}}}", self.name)?;
+        } else {
+            writeln!(
+                buf,
+                "\\usepackage[{}]{{{}}}",
+                self.options.join(","),
+                self.name
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Packages {
+    /// The packages, in the order they were added
+    packages: Vec<Package>,
+    /// (package, dependency): package is loaded after dependency
+    after: Vec<(String, String)>,
+    /// The packages loaded after all the others (hyperref, ...)
+    last: Vec<String>,
+}
+
+impl Default for Packages {
+    fn default() -> Self {
+        let mut packages = Packages {
+            packages: Vec::new(),
+            after: Vec::new(),
+            last: Vec::new(),
+        };
+        // hyperref has to be loaded after (almost) everything else
+        packages.load_last("hyperref");
+        packages.load_after("cleveref", "hyperref");
+        packages
+    }
+}
+
+impl Packages {
+    /// Returns a new registry, with the usual ordering rules (hyperref last, ...)
+    pub fn new() -> Self {
+        Packages::default()
+    }
+
+    /// Adds a package, its options are merged if it is already there
+    pub fn add(&mut self, package: Package) {
+        match self
+            .packages
+            .iter_mut()
+            .find(|p| p.get_name() == package.get_name())
+        {
+            Some(p) => p.merge(&package),
+            None => self.packages.push(package),
+        }
+    }
+
+    /// Adds a package without options
+    pub fn add_package<T: AsRef<str>>(&mut self, name: T) {
+        self.add(Package::new(name));
+    }
+
+    /// Adds a package with a list of options
+    pub fn add_package_with_options<T1: AsRef<str>, T2: AsRef<str>>(
+        &mut self,
+        name: T1,
+        options: Vec<T2>,
+    ) {
+        self.add(Package::with_options(name, options));
+    }
+
+    /// Returns true if the package is in the registry
+    pub fn contains<T: AsRef<str>>(&self, name: T) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Returns the package with this name
+    pub fn get<T: AsRef<str>>(&self, name: T) -> Option<&Package> {
+        self.packages.iter().find(|p| p.get_name() == name.as_ref())
+    }
+
+    /// Returns the number of packages
+    pub fn len(&self) -> usize {
+        self.packages.len()
+    }
+
+    /// Returns true if there is no package
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty()
+    }
+
+    /// Forces `package` to be loaded after `dependency` (if both are used)
+    pub fn load_after<T1: AsRef<str>, T2: AsRef<str>>(&mut self, package: T1, dependency: T2) {
+        let constraint = (
+            package.as_ref().to_string(),
+            dependency.as_ref().to_string(),
+        );
+        if !self.after.contains(&constraint) {
+            self.after.push(constraint);
+        }
+    }
+
+    /// Forces the package to be loaded after all the others
+    pub fn load_last<T: AsRef<str>>(&mut self, package: T) {
+        let package = package.as_ref().to_string();
+        if !self.last.contains(&package) {
+            self.last.push(package);
+        }
+    }
+
+    /// Returns the packages in their load order:
+    /// the order they were added, except when a constraint says otherwise
+    pub fn ordered(&self) -> Result<Vec<&Package>> {
+        let mut ordered = Vec::with_capacity(self.packages.len());
+        let mut done = vec![false; self.packages.len()];
+        while ordered.len() < self.packages.len() {
+            let mut next = None;
+            for (i, package) in self.packages.iter().enumerate() {
+                if done[i] || !self.dependencies_loaded(package, &done) {
+                    continue;
+                }
+                let is_last = self.last.iter().any(|l| l == package.get_name());
+                match next {
+                    None => next = Some((i, is_last)),
+                    Some((_, true)) if !is_last => next = Some((i, is_last)),
+                    _ => (),
+                }
+            }
+            match next {
+                Some((i, _)) => {
+                    done[i] = true;
+                    ordered.push(&self.packages[i]);
+                }
+                None => {
+                    return Err(Error::InvalidStructure(
+                        "The load order constraints of the packages are cyclic".to_string(),
+                    ))
+                }
+            }
+        }
+        Ok(ordered)
+    }
+
+    /// Returns true if every package that has to be loaded before this one is loaded
+    fn dependencies_loaded(&self, package: &Package, done: &[bool]) -> bool {
+        self.after
+            .iter()
+            .filter(|&(p, _)| p == package.get_name())
+            .all(|(_, dependency)| {
+                match self
+                    .packages
+                    .iter()
+                    .position(|p| p.get_name() == dependency)
+                {
+                    Some(j) => done[j],
+                    None => true,
+                }
+            })
+    }
+}
+
+impl Writable for Packages {
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        for package in self.ordered()? {
+            package.write_to_buffer(buf)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests_packages {
+    use super::*;
+
+    #[test]
+    fn package_without_options() {
+        assert_eq!(
+            Package::new("graphicx").to_latex().unwrap(),
+            "\\usepackage{graphicx}\n"
+        );
+    }
+
+    #[test]
+    fn package_with_options() {
+        let p = Package::with_options("geometry", vec!["margin=2cm", "a4paper"]);
+        assert_eq!(
+            p.to_latex().unwrap(),
+            "\\usepackage[margin=2cm,a4paper]{geometry}\n"
+        );
+    }
+
+    #[test]
+    fn key_value_option_is_replaced() {
+        let mut p = Package::with_options("geometry", vec!["margin=2cm"]);
+        p.add_option("margin = 3cm");
+        p.add_option("a4paper");
+        p.add_option("a4paper");
+        assert_eq!(p.get_options(), &["margin = 3cm", "a4paper"]);
+    }
+
+    #[test]
+    fn duplicates_are_merged() {
+        let mut packages = Packages::new();
+        packages.add_package("inputenc");
+        packages.add_package("graphicx");
+        packages.add_package_with_options("inputenc", vec!["utf8"]);
+        assert_eq!(packages.len(), 2);
+        assert_eq!(
+            packages.to_latex().unwrap(),
+            "\\usepackage[utf8]{inputenc}\n\\usepackage{graphicx}\n"
+        );
+    }
+
+    #[test]
+    fn hyperref_is_last() {
+        let mut packages = Packages::new();
+        packages.add_package("cleveref");
+        packages.add_package("hyperref");
+        packages.add_package("graphicx");
+        packages.add_package("amsmath");
+        let names: Vec<&str> = packages
+            .ordered()
+            .unwrap()
+            .iter()
+            .map(|p| p.get_name())
+            .collect();
+        assert_eq!(names, vec!["graphicx", "amsmath", "hyperref", "cleveref"]);
+    }
+
+    #[test]
+    fn load_after() {
+        let mut packages = Packages::new();
+        packages.add_package("b");
+        packages.add_package("a");
+        packages.add_package("c");
+        packages.load_after("b", "c");
+        let names: Vec<&str> = packages
+            .ordered()
+            .unwrap()
+            .iter()
+            .map(|p| p.get_name())
+            .collect();
+        assert_eq!(names, vec!["a", "c", "b"]);
+    }
+
+    #[test]
+    fn cyclic_constraints() {
+        let mut packages = Packages::new();
+        packages.add_package("a");
+        packages.add_package("b");
+        packages.load_after("a", "b");
+        packages.load_after("b", "a");
+        match packages.to_latex() {
+            Err(Error::InvalidStructure(_)) => (),
+            _ => panic!("Cyclic constraints should be reported"),
+        }
+    }
+}
@@ -0,0 +1,393 @@
+/// File defining the plots drawn by pgfplots from Rust data
+/// (lines, scatter plots, bars and error bars, in an axis environment)
+///
+use error::*;
+use escape::Text;
+use graphics::Length;
+use packages::Packages;
+use std::io::Write;
+use writable::*;
+
+/// The version of pgfplots the plots are written for (\pgfplotsset{compat=...})
+pub const PGFPLOTS_COMPAT: &str = "1.18";
+
+/// The numbers which can be plotted, converted to f64
+/// (the large integers are rounded to the nearest f64)
+pub trait PlotValue {
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_plot_value {
+    ($($t:ty),*) => {
+        $(
+            impl PlotValue for $t {
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_plot_value!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+/// How a series is drawn
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlotKind {
+    /// The points are joined by a line
+    Line,
+    /// The points are drawn as marks
+    Scatter,
+    /// A bar for each point
+    Bar,
+}
+
+/// The scale of an axis
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scale {
+    Linear,
+    Log,
+}
+
+/// The position of the legend, in the axis or outside of it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LegendPosition {
+    NorthWest,
+    NorthEast,
+    SouthWest,
+    SouthEast,
+    /// On the right of the axis
+    OuterNorthEast,
+}
+
+impl LegendPosition {
+    /// Returns the value of the legend pos key of pgfplots
+    pub fn code(&self) -> &'static str {
+        match *self {
+            LegendPosition::NorthWest => "north west",
+            LegendPosition::NorthEast => "north east",
+            LegendPosition::SouthWest => "south west",
+            LegendPosition::SouthEast => "south east",
+            LegendPosition::OuterNorthEast => "outer north east",
+        }
+    }
+}
+
+/// A series of points of a plot
+#[derive(Clone, Debug)]
+pub struct Series {
+    kind: PlotKind,
+    points: Vec<(f64, f64)>,
+    /// The errors on y, one for each point (written as error bars)
+    errors: Option<Vec<f64>>,
+    /// The entry of the series in the legend
+    legend: Option<Text>,
+    /// The color (a color of xcolor: red, blue!50, ...)
+    color: Option<String>,
+}
+
+impl Series {
+    /// Returns a new series of the points (x, y)
+    pub fn new<I, X, Y>(kind: PlotKind, points: I) -> Self
+    where
+        I: IntoIterator<Item = (X, Y)>,
+        X: PlotValue,
+        Y: PlotValue,
+    {
+        Series {
+            kind,
+            points: points
+                .into_iter()
+                .map(|(x, y)| (x.to_f64(), y.to_f64()))
+                .collect(),
+            errors: None,
+            legend: None,
+            color: None,
+        }
+    }
+
+    /// Returns a new series of points joined by a line
+    pub fn line<I, X, Y>(points: I) -> Self
+    where
+        I: IntoIterator<Item = (X, Y)>,
+        X: PlotValue,
+        Y: PlotValue,
+    {
+        Series::new(PlotKind::Line, points)
+    }
+
+    /// Returns a new series of points drawn as marks
+    pub fn scatter<I, X, Y>(points: I) -> Self
+    where
+        I: IntoIterator<Item = (X, Y)>,
+        X: PlotValue,
+        Y: PlotValue,
+    {
+        Series::new(PlotKind::Scatter, points)
+    }
+
+    /// Returns a new series of bars
+    pub fn bar<I, X, Y>(points: I) -> Self
+    where
+        I: IntoIterator<Item = (X, Y)>,
+        X: PlotValue,
+        Y: PlotValue,
+    {
+        Series::new(PlotKind::Bar, points)
+    }
+
+    /// Adds error bars, the error on y of each point
+    pub fn set_errors(&mut self, errors: Vec<f64>) {
+        self.errors = Some(errors);
+    }
+
+    /// Changes the entry of the series in the legend
+    pub fn set_legend<T: AsRef<str>>(&mut self, legend: T) {
+        self.legend = Some(Text::new(legend));
+    }
+
+    /// Changes the color of the series (a color of xcolor: red, blue!50, ...)
+    pub fn set_color<T: AsRef<str>>(&mut self, color: T) {
+        self.color = Some(color.as_ref().to_string());
+    }
+
+    /// Returns the points of the series
+    pub fn get_points(&self) -> &[(f64, f64)] {
+        &self.points
+    }
+}
+
+impl Writable for Series {
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        let mut options = Vec::new();
+        if let Some(ref color) = self.color {
+            options.push(format!("color={}", color));
+        }
+        match self.kind {
+            PlotKind::Line => options.push("mark=none".to_string()),
+            PlotKind::Scatter => options.push("only marks".to_string()),
+            PlotKind::Bar => options.push("ybar".to_string()),
+        }
+        if let Some(ref errors) = self.errors {
+            if errors.len() != self.points.len() {
+                return Err(Error::InvalidStructure(format!(
+                    "A series of {} points has {} errors",
+                    self.points.len(),
+                    errors.len()
+                )));
+            }
+            options.push("error bars/.cd, y dir=both, y explicit".to_string());
+        }
+        write!(buf, "\\addplot+[{}] coordinates {{", options.join(", "))?;
+        for (i, &(x, y)) in self.points.iter().enumerate() {
+            write!(buf, " ({},{})", x, y)?;
+            if let Some(ref errors) = self.errors {
+                write!(buf, " +- (0,{})", errors[i])?;
+            }
+        }
+        writeln!(buf, " }};")?;
+        if let Some(ref legend) = self.legend {
+            writeln!(buf, "\\addlegendentry{{{}}}", legend)?;
+        }
+        Ok(())
+    }
+}
+
+/// A plot: an axis with the series drawn in it
+#[derive(Clone, Debug)]
+pub struct Plot {
+    series: Vec<Series>,
+    title: Option<Text>,
+    xlabel: Option<Text>,
+    ylabel: Option<Text>,
+    xscale: Scale,
+    yscale: Scale,
+    legend_position: Option<LegendPosition>,
+    width: Option<Length>,
+    height: Option<Length>,
+}
+
+impl Plot {
+    /// Returns a new empty plot
+    pub fn new() -> Self {
+        Plot {
+            series: Vec::new(),
+            title: None,
+            xlabel: None,
+            ylabel: None,
+            xscale: Scale::Linear,
+            yscale: Scale::Linear,
+            legend_position: None,
+            width: None,
+            height: None,
+        }
+    }
+
+    /// Adds a series to the plot
+    pub fn add(&mut self, series: Series) {
+        self.series.push(series);
+    }
+
+    /// Returns the series of the plot
+    pub fn get_series(&self) -> &[Series] {
+        &self.series
+    }
+
+    /// Changes the title of the plot
+    pub fn set_title<T: AsRef<str>>(&mut self, title: T) {
+        self.title = Some(Text::new(title));
+    }
+
+    /// Changes the label of the x axis
+    pub fn set_xlabel<T: AsRef<str>>(&mut self, label: T) {
+        self.xlabel = Some(Text::new(label));
+    }
+
+    /// Changes the label of the y axis
+    pub fn set_ylabel<T: AsRef<str>>(&mut self, label: T) {
+        self.ylabel = Some(Text::new(label));
+    }
+
+    /// Changes the scale of the x axis
+    pub fn set_xscale(&mut self, scale: Scale) {
+        self.xscale = scale;
+    }
+
+    /// Changes the scale of the y axis
+    pub fn set_yscale(&mut self, scale: Scale) {
+        self.yscale = scale;
+    }
+
+    /// Changes the position of the legend
+    pub fn set_legend_position(&mut self, position: LegendPosition) {
+        self.legend_position = Some(position);
+    }
+
+    /// Changes the width of the axis
+    pub fn set_width(&mut self, width: Length) {
+        self.width = Some(width);
+    }
+
+    /// Changes the height of the axis
+    pub fn set_height(&mut self, height: Length) {
+        self.height = Some(height);
+    }
+
+    /// Writes the plot in a tikzpicture, with the options of the picture (scale, rotate, ...)
+    /// The width and the height of the picture replace the ones of the plot, if given
+    pub fn write_picture<W: Write + ?Sized>(
+        &self,
+        buf: &mut W,
+        picture: &[String],
+        width: Option<&Length>,
+        height: Option<&Length>,
+    ) -> Result<()> {
+        if picture.is_empty() {
+            writeln!(buf, "\\begin{{tikzpicture}}")?;
+        } else {
+            writeln!(buf, "\\begin{{tikzpicture}}[{}]", picture.join(", "))?;
+        }
+        let mut options = Vec::new();
+        if let Some(width) = width.or(self.width.as_ref()) {
+            options.push(format!("width={}", width));
+        }
+        if let Some(height) = height.or(self.height.as_ref()) {
+            options.push(format!("height={}", height));
+        }
+        if let Some(ref title) = self.title {
+            options.push(format!("title={{{}}}", title));
+        }
+        if let Some(ref label) = self.xlabel {
+            options.push(format!("xlabel={{{}}}", label));
+        }
+        if let Some(ref label) = self.ylabel {
+            options.push(format!("ylabel={{{}}}", label));
+        }
+        if self.xscale == Scale::Log {
+            options.push("xmode=log".to_string());
+        }
+        if self.yscale == Scale::Log {
+            options.push("ymode=log".to_string());
+        }
+        if let Some(position) = self.legend_position {
+            options.push(format!("legend pos={}", position.code()));
+        }
+        if options.is_empty() {
+            writeln!(buf, "\\begin{{axis}}")?;
+        } else {
+            writeln!(buf, "\\begin{{axis}}[{}]", options.join(", "))?;
+        }
+        for series in self.series.iter() {
+            series.write_to_buffer(buf)?;
+        }
+        writeln!(buf, "\\end{{axis}}")?;
+        write!(buf, "\\end{{tikzpicture}}")?;
+        Ok(())
+    }
+}
+
+impl Default for Plot {
+    fn default() -> Self {
+        Plot::new()
+    }
+}
+
+impl Writable for Plot {
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        self.write_picture(buf, &[], None, None)?;
+        writeln!(buf)?;
+        Ok(())
+    }
+
+    fn add_packages(&self, packages: &mut Packages) {
+        packages.add_package("pgfplots");
+    }
+}
+
+#[cfg(test)]
+mod tests_plot {
+    use super::*;
+
+    #[test]
+    fn line_and_scatter() {
+        let mut plot = Plot::new();
+        plot.set_xlabel("Threads");
+        plot.set_ylabel("Time (s)");
+        plot.set_yscale(Scale::Log);
+        plot.set_legend_position(LegendPosition::NorthEast);
+        let mut measures = Series::line(vec![(1, 8.0), (2, 4.5)]);
+        measures.set_legend("measured_time");
+        measures.set_color("red");
+        plot.add(measures);
+        plot.add(Series::scatter(vec![(1.5, 6.0)]));
+        assert_eq!(
+            plot.to_latex().unwrap(),
+            "\\begin{tikzpicture}\n\
+             \\begin{axis}[xlabel={Threads}, ylabel={Time (s)}, ymode=log, legend pos=north east]\n\
+             \\addplot+[color=red, mark=none] coordinates { (1,8) (2,4.5) };\n\
+             \\addlegendentry{measured\\_time}\n\
+             \\addplot+[only marks] coordinates { (1.5,6) };\n\
+             \\end{axis}\n\
+             \\end{tikzpicture}\n"
+        );
+    }
+
+    #[test]
+    fn bars_with_errors() {
+        let mut bars = Series::bar(vec![(0, 2.0), (1, 3.0)]);
+        bars.set_errors(vec![0.5, 0.25]);
+        assert_eq!(
+            bars.to_latex().unwrap(),
+            "\\addplot+[ybar, error bars/.cd, y dir=both, y explicit] coordinates { (0,2) +- (0,0.5) (1,3) +- (0,0.25) };\n"
+        );
+        bars.set_errors(vec![0.5]);
+        assert!(bars.to_latex().is_err());
+        let counts = Series::bar((0..3usize).map(|i| (i, 10u64 * i as u64)));
+        assert_eq!(counts.get_points(), &[(0.0, 0.0), (1.0, 10.0), (2.0, 20.0)]);
+        let mut plot = Plot::new();
+        plot.add(bars);
+        let mut packages = Packages::new();
+        plot.add_packages(&mut packages);
+        assert!(packages.contains("pgfplots"));
+    }
+}
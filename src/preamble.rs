@@ -0,0 +1,357 @@
+/// File defining the preamble of a LaTeX document
+/// (class, title, authors, packages, ...)
+///
+use document_class::DocumentClass;
+use error::*;
+use escape::Text;
+use packages::*;
+use plot::PGFPLOTS_COMPAT;
+use std::io::Write;
+use writable::*;
+
+#[derive(Clone, Default)]
+pub struct Preamble {
+    /// The class of the document (article, book, ...)
+    class: DocumentClass,
+    /// The title of the document
+    title: Option<Text>,
+    /// The name of the authors
+    author: Option<Text>,
+    /// The packages, with their options and load order
+    packages: Packages,
+    /// If true, a \\tableofcontents is written after the title
+    table_of_contents: bool,
+    /// If true, a \\listoffigures is written after the title
+    list_of_figures: bool,
+    /// If true, a \\listoftables is written after the title
+    list_of_tables: bool,
+    /// Raw commands written after the packages (\\addbibresource, \\newcommand, ...)
+    commands: Vec<String>,
+    /// The directories where the images are searched (\\graphicspath)
+    graphics_path: Vec<String>,
+}
+
+impl Preamble {
+    /// Returns a new empty Preamble
+    pub fn new() -> Self {
+        Preamble::default()
+    }
+
+    /// Change the class of the document
+    pub fn document_class(&mut self, class: DocumentClass) {
+        self.class = class;
+    }
+
+    /// Returns the class of the document
+    pub fn get_document_class(&self) -> &DocumentClass {
+        &self.class
+    }
+
+    /// Returns the class of the document, to change its options
+    pub fn document_class_mut(&mut self) -> &mut DocumentClass {
+        &mut self.class
+    }
+
+    /// Change the title of the document
+    pub fn title<T: AsRef<str>>(&mut self, new_title: T) {
+        self.title = Some(Text::new(new_title));
+    }
+
+    /// Change the title of the document, written verbatim (trusted LaTeX code)
+    pub fn title_raw<T: AsRef<str>>(&mut self, new_title: T) {
+        self.title = Some(Text::raw(new_title));
+    }
+
+    /// Change the author of the document
+    pub fn author<T: AsRef<str>>(&mut self, new_author: T) {
+        self.author = Some(Text::new(new_author));
+    }
+
+    /// Change the author of the document, written verbatim (e.g. with \and or \thanks)
+    pub fn author_raw<T: AsRef<str>>(&mut self, new_author: T) {
+        self.author = Some(Text::raw(new_author));
+    }
+
+    /// Removes the title of the document
+    pub fn remove_title(&mut self) {
+        self.title = None;
+    }
+
+    /// Removes the author of the document
+    pub fn remove_author(&mut self) {
+        self.author = None;
+    }
+
+    /// Returns the title of the document
+    pub fn get_title(&self) -> Option<&str> {
+        self.title.as_ref().map(|t| t.content())
+    }
+
+    /// Returns the author of the document
+    pub fn get_author(&self) -> Option<&str> {
+        self.author.as_ref().map(|a| a.content())
+    }
+
+    /// Returns the packages
+    pub fn get_packages(&self) -> &Packages {
+        &self.packages
+    }
+
+    /// Returns the packages, to add some or change their load order
+    pub fn packages_mut(&mut self) -> &mut Packages {
+        &mut self.packages
+    }
+
+    /// Adds a package
+    pub fn add_package<T: AsRef<str>>(&mut self, package: T) {
+        self.packages.add_package(package);
+    }
+
+    /// Adds a package with options (e.g. \usepackage[utf8]{inputenc})
+    pub fn add_package_with_options<T1: AsRef<str>, T2: AsRef<str>>(
+        &mut self,
+        package: T1,
+        options: Vec<T2>,
+    ) {
+        self.packages.add_package_with_options(package, options);
+    }
+
+    /// Adds a list of packages
+    pub fn add_package_list<T: AsRef<str>>(&mut self, packages: Vec<T>) {
+        for package in packages.iter() {
+            self.add_package(package);
+        }
+    }
+
+    /// Adds a command written verbatim after the packages
+    pub fn add_command<T: AsRef<str>>(&mut self, command: T) {
+        self.commands.push(command.as_ref().to_string());
+    }
+
+    /// Adds a directory where the images are searched (\\graphicspath)
+    pub fn add_graphics_path<T: AsRef<str>>(&mut self, dir: T) {
+        let mut dir = dir.as_ref().to_string();
+        if !dir.ends_with('/') {
+            dir.push('/');
+        }
+        self.graphics_path.push(dir);
+        self.packages.add_package("graphicx");
+    }
+
+    /// Returns the directories where the images are searched
+    pub fn get_graphics_path(&self) -> &[String] {
+        &self.graphics_path
+    }
+
+    /// Returns the commands written after the packages
+    pub fn get_commands(&self) -> &[String] {
+        &self.commands
+    }
+
+    /// Returns true if the document needs a \maketitle
+    pub fn has_title_page(&self) -> bool {
+        self.title.is_some() || self.author.is_some()
+    }
+
+    /// Enables or disables the table of contents
+    pub fn set_table_of_contents(&mut self, enabled: bool) {
+        self.table_of_contents = enabled;
+    }
+
+    /// Enables or disables the list of figures
+    pub fn set_list_of_figures(&mut self, enabled: bool) {
+        self.list_of_figures = enabled;
+    }
+
+    /// Enables or disables the list of tables
+    pub fn set_list_of_tables(&mut self, enabled: bool) {
+        self.list_of_tables = enabled;
+    }
+
+    /// Returns true if the document has a table of contents
+    pub fn has_table_of_contents(&self) -> bool {
+        self.table_of_contents
+    }
+
+    /// Returns true if the document has a list of figures
+    pub fn has_list_of_figures(&self) -> bool {
+        self.list_of_figures
+    }
+
+    /// Returns true if the document has a list of tables
+    pub fn has_list_of_tables(&self) -> bool {
+        self.list_of_tables
+    }
+
+    /// Writes what comes right after \\begin{document}:
+    /// the title, the table of contents, the list of figures and the list of tables
+    pub fn write_front_matter<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        if self.has_title_page() {
+            writeln!(buf, "\\maketitle")?;
+        }
+        if self.table_of_contents {
+            writeln!(buf, "\\tableofcontents")?;
+        }
+        if self.list_of_figures {
+            writeln!(buf, "\\listoffigures")?;
+        }
+        if self.list_of_tables {
+            writeln!(buf, "\\listoftables")?;
+        }
+        Ok(())
+    }
+
+    /// Writes the class, the packages, the title and the authors
+    pub fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        /* ----- CLASS ----- */
+        self.class.write_to_buffer(buf)?;
+        self.write_without_class(buf)
+    }
+
+    /// Writes the packages, the title and the authors (the class is already written)
+    pub fn write_without_class<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        /* ----- INCLUDES ----- */
+        self.packages.write_to_buffer(buf)?;
+        if !self.graphics_path.is_empty() {
+            let dirs: Vec<String> = self.graphics_path.iter().map(|d| format!("{{{}}}", d)).collect();
+            writeln!(buf, "\\graphicspath{{{}}}", dirs.concat())?;
+        }
+        // Without compat, pgfplots uses the behaviour of its first versions
+        let compat = self
+            .commands
+            .iter()
+            .any(|c| c.starts_with("\\pgfplotsset{compat"));
+        if self.packages.contains("pgfplots") && !compat {
+            writeln!(buf, "\\pgfplotsset{{compat={}}}", PGFPLOTS_COMPAT)?;
+        }
+        for command in self.commands.iter() {
+            writeln!(buf, "{}", command)?;
+        }
+
+        /* ----- TITLE ----- */
+        if let Some(ref t) = self.title {
+            writeln!(buf, "\\title{{{}}}", t)?;
+        }
+
+        /* ----- AUTHOR ----- */
+        if let Some(ref auth) = self.author {
+            writeln!(buf, "\\author{{{}}}", auth)?;
+        }
+
+        writeln!(buf, "\\date{{}}")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests_preamble {
+    use super::*;
+
+    #[test]
+    fn empty_preamble() {
+        let p = Preamble::new();
+        let mut out = Vec::new();
+        p.write_to_buffer(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\\documentclass[a4paper,11pt]{article}\n\\date{}\n"
+        );
+        assert!(!p.has_title_page());
+    }
+
+    #[test]
+    fn full_preamble() {
+        let mut p = Preamble::new();
+        p.title("Title");
+        p.author("Quentin");
+        p.add_package_list(vec!["graphicx", "listings"]);
+        let mut out = Vec::new();
+        p.write_to_buffer(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\\documentclass[a4paper,11pt]{article}\n\\usepackage{graphicx}\n\\usepackage{listings}\n\\title{Title}\n\\author{Quentin}\n\\date{}\n"
+        );
+        assert!(p.has_title_page());
+    }
+
+    #[test]
+    fn escaped_title_and_author() {
+        let mut p = Preamble::new();
+        p.title("50% off");
+        p.author_raw("A \\and B");
+        let mut out = Vec::new();
+        p.write_to_buffer(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\\documentclass[a4paper,11pt]{article}\n\\title{50\\% off}\n\\author{A \\and B}\n\\date{}\n"
+        );
+        assert_eq!(p.get_title(), Some("50% off"));
+    }
+
+    #[test]
+    fn packages_with_options() {
+        let mut p = Preamble::new();
+        p.add_package("hyperref");
+        p.add_package_with_options("inputenc", vec!["utf8"]);
+        p.add_package_with_options("geometry", vec!["margin=2cm"]);
+        p.add_package("inputenc");
+        let mut out = Vec::new();
+        p.write_to_buffer(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\\documentclass[a4paper,11pt]{article}\n\
+             \\usepackage[utf8]{inputenc}\n\
+             \\usepackage[margin=2cm]{geometry}\n\
+             \\usepackage{hyperref}\n\
+             \\date{}\n"
+        );
+    }
+
+    #[test]
+    fn pgfplots_compat() {
+        let mut p = Preamble::new();
+        p.add_package("pgfplots");
+        let mut out = Vec::new();
+        p.write_to_buffer(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\\documentclass[a4paper,11pt]{article}\n\
+             \\usepackage{pgfplots}\n\
+             \\pgfplotsset{compat=1.18}\n\
+             \\date{}\n"
+        );
+        p.add_command("\\pgfplotsset{compat=1.16}");
+        let mut out = Vec::new();
+        p.write_to_buffer(&mut out).unwrap();
+        assert!(!String::from_utf8(out).unwrap().contains("1.18"));
+    }
+
+    #[test]
+    fn front_matter() {
+        let mut p = Preamble::new();
+        let mut out = Vec::new();
+        p.write_front_matter(&mut out).unwrap();
+        assert!(out.is_empty());
+        p.title("Title");
+        p.set_table_of_contents(true);
+        p.set_list_of_tables(true);
+        p.write_front_matter(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\\maketitle\n\\tableofcontents\n\\listoftables\n"
+        );
+    }
+
+    #[test]
+    fn report_class() {
+        let mut p = Preamble::new();
+        p.document_class(DocumentClass::report());
+        p.document_class_mut().add_option("twocolumn");
+        let mut out = Vec::new();
+        p.write_to_buffer(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\\documentclass[twocolumn]{report}\n\\date{}\n"
+        );
+    }
+}
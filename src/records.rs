@@ -0,0 +1,429 @@
+/// File defining the tabulars built from serde records (feature "serde")
+/// Each record is a row, and the names of the fields of the first one are the header
+///
+use core::Core;
+use error::*;
+use serde::ser::{self, Impossible, Serialize, SerializeStruct};
+use std::fmt::Display;
+use tabular::Tabular;
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::InvalidStructure(msg.to_string())
+    }
+}
+
+impl Tabular {
+    /// Returns a new Tabular with a row per record
+    /// The records must be structs (or maps) of plain values: numbers, strings, options, enums
+    pub fn from_records<T: Serialize>(records: &[T]) -> Result<Self> {
+        let mut header: Option<Vec<String>> = None;
+        let mut rows = Vec::new();
+        for record in records.iter() {
+            let mut fields = RecordSerializer::default();
+            record.serialize(&mut fields)?;
+            match header {
+                Some(ref names) if *names != fields.names => {
+                    return Err(Error::InvalidStructure(
+                        "All the records must have the same fields".to_string(),
+                    ))
+                }
+                Some(_) => (),
+                None => header = Some(fields.names),
+            }
+            rows.push(fields.values);
+        }
+        let mut tab = Tabular::new(&rows);
+        if let Some(names) = header {
+            tab.set_header(names.into_iter().map(Core::text).collect());
+        }
+        Ok(tab)
+    }
+}
+
+/// Collects the names and the values of the fields of a record
+#[derive(Default)]
+struct RecordSerializer {
+    names: Vec<String>,
+    values: Vec<Core>,
+    /// The key of a map entry, waiting for its value
+    key: Option<String>,
+}
+
+impl RecordSerializer {
+    fn push<T: ?Sized + Serialize>(&mut self, name: String, value: &T) -> Result<()> {
+        self.names.push(name);
+        self.values
+            .push(Core::text(value.serialize(ValueSerializer)?));
+        Ok(())
+    }
+}
+
+/// Returns the error for a record which is not a struct
+fn not_a_record<T>() -> Result<T> {
+    Err(Error::InvalidStructure(
+        "A record must be a struct or a map".to_string(),
+    ))
+}
+
+impl ser::Serializer for &mut RecordSerializer {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Impossible<(), Error>;
+    type SerializeTuple = Impossible<(), Error>;
+    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_bool(self, _v: bool) -> Result<()> {
+        not_a_record()
+    }
+    fn serialize_i8(self, _v: i8) -> Result<()> {
+        not_a_record()
+    }
+    fn serialize_i16(self, _v: i16) -> Result<()> {
+        not_a_record()
+    }
+    fn serialize_i32(self, _v: i32) -> Result<()> {
+        not_a_record()
+    }
+    fn serialize_i64(self, _v: i64) -> Result<()> {
+        not_a_record()
+    }
+    fn serialize_u8(self, _v: u8) -> Result<()> {
+        not_a_record()
+    }
+    fn serialize_u16(self, _v: u16) -> Result<()> {
+        not_a_record()
+    }
+    fn serialize_u32(self, _v: u32) -> Result<()> {
+        not_a_record()
+    }
+    fn serialize_u64(self, _v: u64) -> Result<()> {
+        not_a_record()
+    }
+    fn serialize_f32(self, _v: f32) -> Result<()> {
+        not_a_record()
+    }
+    fn serialize_f64(self, _v: f64) -> Result<()> {
+        not_a_record()
+    }
+    fn serialize_char(self, _v: char) -> Result<()> {
+        not_a_record()
+    }
+    fn serialize_str(self, _v: &str) -> Result<()> {
+        not_a_record()
+    }
+    fn serialize_bytes(self, _v: &[u8]) -> Result<()> {
+        not_a_record()
+    }
+    fn serialize_none(self) -> Result<()> {
+        not_a_record()
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> Result<()> {
+        not_a_record()
+    }
+    fn serialize_unit(self) -> Result<()> {
+        not_a_record()
+    }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<()> {
+        not_a_record()
+    }
+    fn serialize_unit_variant(self, _: &'static str, _: u32, _: &'static str) -> Result<()> {
+        not_a_record()
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _value: &T,
+    ) -> Result<()> {
+        not_a_record()
+    }
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        not_a_record()
+    }
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        not_a_record()
+    }
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        not_a_record()
+    }
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        not_a_record()
+    }
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        not_a_record()
+    }
+}
+
+impl SerializeStruct for &mut RecordSerializer {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.push(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for &mut RecordSerializer {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<()> {
+        self.key = Some(key.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        match self.key.take() {
+            Some(key) => self.push(key, value),
+            None => Err(Error::InvalidStructure(
+                "A value of a map has no key".to_string(),
+            )),
+        }
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes a plain value (number, string, option, enum) as a String
+struct ValueSerializer;
+
+/// Returns the error for a value which is not plain
+fn not_a_value<T>() -> Result<T> {
+    Err(Error::InvalidStructure(
+        "A field of a record must be a number, a string, an option or an enum".to_string(),
+    ))
+}
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = String;
+    type Error = Error;
+    type SerializeSeq = Impossible<String, Error>;
+    type SerializeTuple = Impossible<String, Error>;
+    type SerializeTupleStruct = Impossible<String, Error>;
+    type SerializeTupleVariant = Impossible<String, Error>;
+    type SerializeMap = Impossible<String, Error>;
+    type SerializeStruct = Impossible<String, Error>;
+    type SerializeStructVariant = Impossible<String, Error>;
+
+    fn serialize_bool(self, v: bool) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_i8(self, v: i8) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_i16(self, v: i16) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_i32(self, v: i32) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_i64(self, v: i64) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_u8(self, v: u8) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_u16(self, v: u16) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_u32(self, v: u32) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_u64(self, v: u64) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_f32(self, v: f32) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_f64(self, v: f64) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_char(self, v: char) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_str(self, v: &str) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String> {
+        not_a_value()
+    }
+    fn serialize_none(self) -> Result<String> {
+        Ok(String::new())
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<String> {
+        value.serialize(self)
+    }
+    fn serialize_unit(self) -> Result<String> {
+        Ok(String::new())
+    }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String> {
+        Ok(String::new())
+    }
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<String> {
+        Ok(variant.to_string())
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        value: &T,
+    ) -> Result<String> {
+        value.serialize(self)
+    }
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        not_a_value()
+    }
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        not_a_value()
+    }
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        not_a_value()
+    }
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        not_a_value()
+    }
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        not_a_value()
+    }
+    fn serialize_struct(self, _: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        not_a_value()
+    }
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        not_a_value()
+    }
+}
+
+#[cfg(test)]
+mod tests_records {
+    use super::*;
+    use serde::Serializer;
+    use writable::*;
+
+    struct Run {
+        name: &'static str,
+        time: f64,
+        threads: Option<u32>,
+    }
+
+    // What #[derive(Serialize)] generates
+    impl Serialize for Run {
+        fn serialize<S: Serializer>(
+            &self,
+            serializer: S,
+        ) -> ::std::result::Result<S::Ok, S::Error> {
+            let mut s = serializer.serialize_struct("Run", 3)?;
+            s.serialize_field("name", &self.name)?;
+            s.serialize_field("time", &self.time)?;
+            s.serialize_field("threads", &self.threads)?;
+            s.end()
+        }
+    }
+
+    #[test]
+    fn records() {
+        let runs = vec![
+            Run {
+                name: "seq_bench",
+                time: 1.5,
+                threads: Some(1),
+            },
+            Run {
+                name: "par",
+                time: 0.25,
+                threads: None,
+            },
+        ];
+        let tab = Tabular::from_records(&runs).unwrap();
+        assert_eq!(
+            tab.to_latex().unwrap(),
+            "\\begin{tabular}{| c | c | c |}\n \\hline\n\
+             name & time & threads \\\\\n \\hline\n\
+             seq\\_bench & 1.5 & 1 \\\\\n \\hline\n\
+             par & 0.25 &  \\\\\n \\hline\n\
+             \\end{tabular}\n"
+        );
+    }
+
+    #[test]
+    fn not_records() {
+        assert!(Tabular::from_records(&[1, 2]).is_err());
+        assert!(Tabular::from_records(&[vec![1]]).is_err());
+    }
+}
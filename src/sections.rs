@@ -1,8 +1,10 @@
-/// File defining the section / subsection / subsubsection of the file
+/// File defining the part / chapter / section / subsection / ... of the file
 ///
 use core::*;
-use latex_file::LatexFile;
-use std::io::BufWriter;
+use error::*;
+use escape::*;
+use labels::*;
+use packages::Packages;
 use std::io::Write;
 use writable::*;
 
@@ -10,45 +12,99 @@ use writable::*;
 // pub type Core = usize; // Should be an Enum
 // pub type LatexFile = File;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SectionLevel {
+    Part,
+    Chapter,
+    Section,
+    SubSection,
+    SubSubSection,
+    Paragraph,
+    SubParagraph,
+}
+
+impl SectionLevel {
+    /// Returns the LaTeX command of the level
+    pub fn command(&self) -> &str {
+        match *self {
+            SectionLevel::Part => "\\part",
+            SectionLevel::Chapter => "\\chapter",
+            SectionLevel::Section => "\\section",
+            SectionLevel::SubSection => "\\subsection",
+            SectionLevel::SubSubSection => "\\subsubsection",
+            SectionLevel::Paragraph => "\\paragraph",
+            SectionLevel::SubParagraph => "\\subparagraph",
+        }
+    }
+
+    /// Returns the LaTeX depth of the level (-1 for a part, 0 for a chapter, ...)
+    pub fn depth(&self) -> i32 {
+        *self as i32 - 1
+    }
+}
+
 #[derive(Clone)]
 pub struct Section {
     /// The title of the section
-    pub title: String,
-    /// 0 -> Section, 1 -> SubSection, 2 -> SubSubSection, 3 -> paragraph
-    rank: usize,
+    pub title: Text,
+    /// The short title, for the table of contents and the headers
+    short_title: Option<Text>,
+    /// Part, Chapter, Section, SubSection, ...
+    level: SectionLevel,
+    /// If true, the section is not numbered (\\section*)
+    starred: bool,
+    /// The label of the section
+    label: Option<Label>,
     /// The content of the section
     content: Vec<Core>,
 }
 
 impl Section {
-    /// Returns a new (sub, subsub)Section depending on the rank
-    fn new<T: AsRef<str>>(title: T, rank: usize) -> Self {
-        assert!(rank <= 3);
+    /// Returns a new Part, Chapter, Section, ... depending on the level
+    pub fn new<T: AsRef<str>>(title: T, level: SectionLevel) -> Self {
         Section {
-            title: title.as_ref().to_string(),
-            rank,
+            title: Text::new(title),
+            short_title: None,
+            level,
+            starred: false,
+            label: None,
             content: Vec::new(),
         }
     }
 
+    /// Returns a new Part
+    pub fn new_part<T: AsRef<str>>(title: T) -> Self {
+        Section::new(title, SectionLevel::Part)
+    }
+
+    /// Returns a new Chapter
+    pub fn new_chapter<T: AsRef<str>>(title: T) -> Self {
+        Section::new(title, SectionLevel::Chapter)
+    }
+
     /// Returns a new Section
     pub fn new_section<T: AsRef<str>>(title: T) -> Self {
-        Section::new(title.as_ref().to_string(), 0)
+        Section::new(title, SectionLevel::Section)
     }
 
     /// Returns a new SubSection
     pub fn new_subsection<T: AsRef<str>>(title: T) -> Self {
-        Section::new(title.as_ref().to_string(), 1)
+        Section::new(title, SectionLevel::SubSection)
     }
 
     /// Returns a new SubSubSection
     pub fn new_subsubsection<T: AsRef<str>>(title: T) -> Self {
-        Section::new(title.as_ref().to_string(), 2)
+        Section::new(title, SectionLevel::SubSubSection)
     }
 
     /// Returns a new Paragraph
     pub fn new_paragraph<T: AsRef<str>>(title: T) -> Self {
-        Section::new(title.as_ref().to_string(), 3)
+        Section::new(title, SectionLevel::Paragraph)
+    }
+
+    /// Returns a new SubParagraph
+    pub fn new_subparagraph<T: AsRef<str>>(title: T) -> Self {
+        Section::new(title, SectionLevel::SubParagraph)
     }
 
     /// Push some content in the section
@@ -58,38 +114,114 @@ impl Section {
 
     /// Changes the title of the Section
     pub fn change_title<T: AsRef<str>>(&mut self, new_title: T) {
-        self.title = new_title.as_ref().to_string();
+        // The new title is escaped like the previous one
+        let raw = self.title.is_raw();
+        self.title = Text::new(new_title);
+        self.title.set_raw(raw);
     }
 
-    /// Returns the title of the section
-    pub fn get_title(&self) -> &String {
-        &self.title
+    /// Returns the title of the section, as given
+    pub fn get_title(&self) -> &str {
+        self.title.content()
     }
 
-    /// Return the string corresponding on the rank
-    fn type_of_section(&self) -> &str {
-        match self.rank {
-            0 => "\\section",
-            1 => "\\subsection",
-            2 => "\\subsubsection",
-            3 => "\\paragraph",
-            _ => panic!("The rank of this section is not valid!"),
+    /// Returns the content of the section
+    pub fn get_content(&self) -> &[Core] {
+        &self.content
+    }
+
+    /// Returns the level of the section
+    pub fn get_level(&self) -> SectionLevel {
+        self.level
+    }
+
+    /// Returns true if the section is not numbered
+    pub fn is_starred(&self) -> bool {
+        self.starred
+    }
+
+    /// Enables or disables the numbering of the section (\\section* is not numbered)
+    pub fn set_starred(&mut self, starred: bool) {
+        self.starred = starred;
+    }
+
+    /// Changes the short title, used in the table of contents (\\section[short]{long}),
+    /// escaped like the title
+    pub fn set_short_title<T: AsRef<str>>(&mut self, short_title: T) {
+        let mut short_title = Text::new(short_title);
+        short_title.set_raw(self.title.is_raw());
+        self.short_title = Some(short_title);
+    }
+
+    /// Removes the short title
+    pub fn remove_short_title(&mut self) {
+        self.short_title = None;
+    }
+
+    /// Returns the short title of the section, as given
+    pub fn get_short_title(&self) -> Option<&str> {
+        self.short_title.as_ref().map(|t| t.content())
+    }
+
+    /// Labels the section, and returns the handle to refer to it
+    pub fn set_label<T: AsRef<str>>(&mut self, name: T) -> Label {
+        let label = Label::new(LabelKind::Section, name);
+        self.label = Some(label.clone());
+        label
+    }
+
+    /// Returns the label of the section
+    pub fn get_label(&self) -> Option<&Label> {
+        self.label.as_ref()
+    }
+
+    /// Enables or disables the escaping of the title and the short title
+    /// (to put LaTeX code in them)
+    pub fn set_raw_title(&mut self, raw: bool) {
+        self.title.set_raw(raw);
+        if let Some(ref mut short_title) = self.short_title {
+            short_title.set_raw(raw);
+        }
+    }
+
+    /// Return the string corresponding on the level
+    fn type_of_section(&self) -> String {
+        if self.starred {
+            format!("{}*", self.level.command())
+        } else {
+            self.level.command().to_string()
         }
     }
 }
 
 impl Writable for Section {
-    fn write_latex(&self, file: &mut LatexFile) {
-        let mut writer = BufWriter::new(file);
-        self.write_to_buffer(&mut writer);
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        write!(buf, "{}", self.type_of_section())?;
+        if let Some(ref short_title) = self.short_title {
+            if self.starred {
+                return Err(Error::InvalidStructure(format!(
+                    "The unnumbered section '{}' can not have a short title",
+                    self.title.content()
+                )));
+            }
+            write!(buf, "[{}]", short_title)?;
+        }
+        write!(buf, "{{{}}}", self.title)?;
+        if let Some(ref label) = self.label {
+            label.write_to_buffer(buf)?;
+        }
+        writeln!(buf)?;
+        for item in self.content.iter() {
+            item.write_to_buffer(buf)?;
+        }
+        writeln!(buf)?;
+        Ok(())
     }
 
-    fn write_to_buffer(&self, mut buf: &mut BufWriter<&mut LatexFile>) {
-        writeln!(&mut buf, "{}{{{}}}", self.type_of_section(), self.title).unwrap();
+    fn add_packages(&self, packages: &mut Packages) {
         for item in self.content.iter() {
-            item.write_to_buffer(&mut buf);
+            item.add_packages(packages);
         }
-        writeln!(&mut buf).unwrap();
     }
 }
 
@@ -109,14 +241,14 @@ mod tests_section {
     #[test]
     fn getting_name() {
         let sec = Section::new_section("Section");
-        assert_eq!(sec.title, "Section");
+        assert_eq!(sec.title.content(), "Section");
     }
 
     #[test]
     fn changing_name() {
         let mut sec = Section::new_section("Section");
         sec.change_title("NewSection");
-        assert_eq!(sec.title, "NewSection");
+        assert_eq!(sec.title.content(), "NewSection");
     }
 
     #[test]
@@ -127,35 +259,72 @@ mod tests_section {
         assert_eq!(s1.type_of_section(), r"\section");
         assert_eq!(s2.type_of_section(), r"\subsection");
         assert_eq!(s3.type_of_section(), r"\subsubsection");
+        assert_eq!(Section::new_part("Part").type_of_section(), r"\part");
+        assert_eq!(Section::new_chapter("Chapter").type_of_section(), r"\chapter");
+        assert_eq!(
+            Section::new_subparagraph("SubParagraph").type_of_section(),
+            r"\subparagraph"
+        );
+    }
+
+    #[test]
+    fn levels_depth() {
+        assert_eq!(SectionLevel::Part.depth(), -1);
+        assert_eq!(SectionLevel::Chapter.depth(), 0);
+        assert_eq!(SectionLevel::Section.depth(), 1);
+        assert_eq!(SectionLevel::SubParagraph.depth(), 5);
+        assert!(SectionLevel::Chapter < SectionLevel::Section);
+    }
+
+    #[test]
+    fn starred_section() {
+        let mut sec = Section::new_chapter("Preface");
+        sec.set_starred(true);
+        assert_eq!(sec.to_latex().unwrap(), "\\chapter*{Preface}\n\n");
+    }
+
+    #[test]
+    fn short_title() {
+        let mut sec = Section::new_section("A very long title & more");
+        sec.set_short_title("Short & title");
+        assert_eq!(
+            sec.to_latex().unwrap(),
+            "\\section[Short \\& title]{A very long title \\& more}\n\n"
+        );
+        sec.set_starred(true);
+        match sec.to_latex() {
+            Err(Error::InvalidStructure(_)) => (),
+            _ => panic!("A starred section can not have a short title"),
+        }
     }
 
     #[test]
     fn simple_write_in_file() {
-        let mut f = new_latex_file("./tests_results/sections/section_simple_test.tex");
-        f.begin_document();
+        let mut f = new_latex_file("./tests_results/sections/section_simple_test.tex").unwrap();
+        f.begin_document().unwrap();
         let s1 = Section::new_section("Section1");
-        s1.write_latex(&mut f);
-        f.write_footer();
+        s1.write_latex(&mut f).unwrap();
+        f.write_footer().unwrap();
     }
 
     #[test]
     fn nested_write_in_file() {
-        let mut f = new_latex_file("./tests_results/sections/section_nested_test.tex");
-        f.begin_document();
+        let mut f = new_latex_file("./tests_results/sections/section_nested_test.tex").unwrap();
+        f.begin_document().unwrap();
         let mut s1 = Section::new_section("Section1");
         let s2 = Section::new_subsection("subsect");
         s1.add_content(Core::Sec(s2));
-        s1.write_latex(&mut f);
-        f.write_footer();
+        s1.write_latex(&mut f).unwrap();
+        f.write_footer().unwrap();
     }
 
     #[test]
     fn complex_nested_write_in_file() {
-        let mut f = new_latex_file("./tests_results/sections/section_complex_nested_test.tex");
+        let mut f = new_latex_file("./tests_results/sections/section_complex_nested_test.tex").unwrap();
         f.author("Quentin");
         f.title("Test Nested Write in File");
         f.add_package("graphics");
-        f.begin_document();
+        f.begin_document().unwrap();
         let mut s1 = Section::new_section("Section1");
         let mut s2 = Section::new_subsection("subsect");
         let s3 = Section::new_subsubsection("subsubsubsub");
@@ -165,8 +334,25 @@ mod tests_section {
         s1.add_content(Core::Sec(s2));
         s1.add_content(Core::Sec(s4));
         assert_eq!(s1.content.len(), 2);
-        s1.write_latex(&mut f);
-        f.write_footer();
+        s1.write_latex(&mut f).unwrap();
+        f.write_footer().unwrap();
+    }
+
+    #[test]
+    fn escaped_title() {
+        let mut sec = Section::new_section("R&D");
+        assert_eq!(sec.to_latex().unwrap(), "\\section{R\\&D}\n\n");
+        sec.set_raw_title(true);
+        sec.change_title("\\LaTeX");
+        assert_eq!(sec.to_latex().unwrap(), "\\section{\\LaTeX}\n\n");
     }
 
+    #[test]
+    fn nested_write_in_string() {
+        let mut s1 = Section::new_section("Section1");
+        let mut s2 = Section::new_subsection("subsect");
+        s2.add_content(Core::text("Hello"));
+        s1.add_content(Core::Sec(s2));
+        assert_eq!(s1.to_latex().unwrap(), "\\section{Section1}\n\\subsection{subsect}\nHello\n\n");
+    }
 }
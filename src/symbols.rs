@@ -1,5 +1,457 @@
 /// File to define symbols in LaTeX
 ///
+use error::*;
+use symbols::SymbolKind::*;
+
+/// The families of math symbols
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SymbolKind {
+    Greek,
+    /// =, \leq, \approx, \equiv, ...
+    Relation,
+    Arrow,
+    /// \land, \forall, \implies, ...
+    Logic,
+    /// \in, \subseteq, \cup, ...
+    Set,
+    /// \times, \oplus, \sum, ...
+    Operator,
+    /// The symbols written like letters: \infty, \ell, \mathbb{R}, \mathcal{A}, ...
+    Letter,
+    /// Dots, delimiters, ...
+    Misc,
+}
+
+/// A math symbol: its LaTeX command, its Unicode character, its ASCII names
+/// and the package defining it
+#[derive(Debug, PartialEq, Eq)]
+pub struct MathSymbol {
+    pub kind: SymbolKind,
+    pub latex: &'static str,
+    pub unicode: char,
+    /// The ASCII names (<=, alpha, RR, ...)
+    pub aliases: &'static [&'static str],
+    pub package: Option<&'static str>,
+}
+
+const fn symbol(
+    kind: SymbolKind,
+    latex: &'static str,
+    unicode: char,
+    aliases: &'static [&'static str],
+    package: Option<&'static str>,
+) -> MathSymbol {
+    MathSymbol {
+        kind,
+        latex,
+        unicode,
+        aliases,
+        package,
+    }
+}
+
+/// The known symbols
+/// When several symbols have the same character, the first one is used for the Unicode
+pub static SYMBOL_TABLE: &[MathSymbol] = &[
+    symbol(Greek, "\\alpha", 'α', &["alpha"], None),
+    symbol(Greek, "\\beta", 'β', &["beta"], None),
+    symbol(Greek, "\\gamma", 'γ', &["gamma"], None),
+    symbol(Greek, "\\delta", 'δ', &["delta"], None),
+    symbol(Greek, "\\epsilon", 'ϵ', &["epsilon"], None),
+    symbol(Greek, "\\varepsilon", 'ε', &["varepsilon"], None),
+    symbol(Greek, "\\zeta", 'ζ', &["zeta"], None),
+    symbol(Greek, "\\eta", 'η', &["eta"], None),
+    symbol(Greek, "\\theta", 'θ', &["theta"], None),
+    symbol(Greek, "\\vartheta", 'ϑ', &["vartheta"], None),
+    symbol(Greek, "\\iota", 'ι', &["iota"], None),
+    symbol(Greek, "\\kappa", 'κ', &["kappa"], None),
+    symbol(Greek, "\\varkappa", 'ϰ', &["varkappa"], Some("amssymb")),
+    symbol(Greek, "\\lambda", 'λ', &["lambda"], None),
+    symbol(Greek, "\\mu", 'μ', &["mu"], None),
+    symbol(Greek, "\\nu", 'ν', &["nu"], None),
+    symbol(Greek, "\\xi", 'ξ', &["xi"], None),
+    symbol(Greek, "\\pi", 'π', &["pi"], None),
+    symbol(Greek, "\\varpi", 'ϖ', &["varpi"], None),
+    symbol(Greek, "\\rho", 'ρ', &["rho"], None),
+    symbol(Greek, "\\varrho", 'ϱ', &["varrho"], None),
+    symbol(Greek, "\\sigma", 'σ', &["sigma"], None),
+    symbol(Greek, "\\varsigma", 'ς', &["varsigma"], None),
+    symbol(Greek, "\\tau", 'τ', &["tau"], None),
+    symbol(Greek, "\\upsilon", 'υ', &["upsilon"], None),
+    symbol(Greek, "\\phi", 'ϕ', &["phi"], None),
+    symbol(Greek, "\\varphi", 'φ', &["varphi"], None),
+    symbol(Greek, "\\chi", 'χ', &["chi"], None),
+    symbol(Greek, "\\psi", 'ψ', &["psi"], None),
+    symbol(Greek, "\\omega", 'ω', &["omega"], None),
+    symbol(Greek, "\\digamma", 'ϝ', &["digamma"], Some("amssymb")),
+    symbol(Greek, "\\Gamma", 'Γ', &["Gamma"], None),
+    symbol(Greek, "\\Delta", 'Δ', &["Delta"], None),
+    symbol(Greek, "\\Theta", 'Θ', &["Theta"], None),
+    symbol(Greek, "\\Lambda", 'Λ', &["Lambda"], None),
+    symbol(Greek, "\\Xi", 'Ξ', &["Xi"], None),
+    symbol(Greek, "\\Pi", 'Π', &["Pi"], None),
+    symbol(Greek, "\\Sigma", 'Σ', &["Sigma"], None),
+    symbol(Greek, "\\Upsilon", 'Υ', &["Upsilon"], None),
+    symbol(Greek, "\\Phi", 'Φ', &["Phi"], None),
+    symbol(Greek, "\\Psi", 'Ψ', &["Psi"], None),
+    symbol(Greek, "\\Omega", 'Ω', &["Omega"], None),
+    symbol(Relation, "=", '=', &["=", "=="], None),
+    symbol(Relation, "\\neq", '≠', &["!=", "<>", "neq"], None),
+    symbol(Relation, "<", '<', &["<"], None),
+    symbol(Relation, ">", '>', &[">"], None),
+    symbol(Relation, "\\leq", '≤', &["<=", "leq"], None),
+    symbol(Relation, "\\geq", '≥', &[">=", "geq"], None),
+    symbol(Relation, "\\leqslant", '⩽', &["leqslant"], Some("amssymb")),
+    symbol(Relation, "\\geqslant", '⩾', &["geqslant"], Some("amssymb")),
+    symbol(Relation, "\\ll", '≪', &["<<", "ll"], None),
+    symbol(Relation, "\\gg", '≫', &[">>", "gg"], None),
+    symbol(Relation, "\\approx", '≈', &["~~", "approx"], None),
+    symbol(Relation, "\\sim", '∼', &["~", "sim"], None),
+    symbol(Relation, "\\simeq", '≃', &["~=", "simeq"], None),
+    symbol(Relation, "\\cong", '≅', &["cong"], None),
+    symbol(Relation, "\\equiv", '≡', &["===", "equiv"], None),
+    symbol(Relation, "\\propto", '∝', &["propto"], None),
+    symbol(Relation, "\\doteq", '≐', &["doteq"], None),
+    symbol(
+        Relation,
+        "\\coloneqq",
+        '≔',
+        &[":=", "coloneqq"],
+        Some("mathtools"),
+    ),
+    symbol(Relation, "\\prec", '≺', &["prec"], None),
+    symbol(Relation, "\\succ", '≻', &["succ"], None),
+    symbol(Relation, "\\preceq", '⪯', &["preceq"], None),
+    symbol(Relation, "\\succeq", '⪰', &["succeq"], None),
+    symbol(Relation, "\\perp", '⊥', &["perp"], None),
+    symbol(Relation, "\\parallel", '∥', &["parallel"], None),
+    symbol(Relation, "\\mid", '∣', &["mid", "divides"], None),
+    symbol(Relation, "\\nmid", '∤', &["nmid"], Some("amssymb")),
+    symbol(Relation, "\\lesssim", '≲', &["lesssim"], Some("amssymb")),
+    symbol(Relation, "\\gtrsim", '≳', &["gtrsim"], Some("amssymb")),
+    symbol(Relation, "\\asymp", '≍', &["asymp"], None),
+    symbol(Relation, "\\vdash", '⊢', &["|-", "vdash"], None),
+    symbol(Relation, "\\models", '⊨', &["|=", "models"], None),
+    symbol(Arrow, "\\to", '→', &["->", "to"], None),
+    symbol(Arrow, "\\rightarrow", '→', &["rightarrow"], None),
+    symbol(
+        Arrow,
+        "\\leftarrow",
+        '←',
+        &["<-", "leftarrow", "gets"],
+        None,
+    ),
+    symbol(
+        Arrow,
+        "\\leftrightarrow",
+        '↔',
+        &["<->", "leftrightarrow"],
+        None,
+    ),
+    symbol(Arrow, "\\Rightarrow", '⇒', &["Rightarrow"], None),
+    symbol(Arrow, "\\Leftarrow", '⇐', &["Leftarrow"], None),
+    symbol(Arrow, "\\Leftrightarrow", '⇔', &["Leftrightarrow"], None),
+    symbol(Arrow, "\\mapsto", '↦', &["|->", "mapsto"], None),
+    symbol(
+        Arrow,
+        "\\longrightarrow",
+        '⟶',
+        &["-->", "longrightarrow"],
+        None,
+    ),
+    symbol(
+        Arrow,
+        "\\longleftarrow",
+        '⟵',
+        &["<--", "longleftarrow"],
+        None,
+    ),
+    symbol(
+        Arrow,
+        "\\longleftrightarrow",
+        '⟷',
+        &["<-->", "longleftrightarrow"],
+        None,
+    ),
+    symbol(Arrow, "\\Longrightarrow", '⟹', &["Longrightarrow"], None),
+    symbol(Arrow, "\\Longleftarrow", '⟸', &["Longleftarrow"], None),
+    symbol(
+        Arrow,
+        "\\Longleftrightarrow",
+        '⟺',
+        &["Longleftrightarrow"],
+        None,
+    ),
+    symbol(Arrow, "\\longmapsto", '⟼', &["|-->", "longmapsto"], None),
+    symbol(Arrow, "\\uparrow", '↑', &["uparrow"], None),
+    symbol(Arrow, "\\downarrow", '↓', &["downarrow"], None),
+    symbol(Arrow, "\\updownarrow", '↕', &["updownarrow"], None),
+    symbol(Arrow, "\\Uparrow", '⇑', &["Uparrow"], None),
+    symbol(Arrow, "\\Downarrow", '⇓', &["Downarrow"], None),
+    symbol(Arrow, "\\nearrow", '↗', &["nearrow"], None),
+    symbol(Arrow, "\\searrow", '↘', &["searrow"], None),
+    symbol(Arrow, "\\swarrow", '↙', &["swarrow"], None),
+    symbol(Arrow, "\\nwarrow", '↖', &["nwarrow"], None),
+    symbol(Arrow, "\\hookrightarrow", '↪', &["hookrightarrow"], None),
+    symbol(Arrow, "\\hookleftarrow", '↩', &["hookleftarrow"], None),
+    symbol(
+        Arrow,
+        "\\rightleftharpoons",
+        '⇌',
+        &["rightleftharpoons"],
+        None,
+    ),
+    symbol(Arrow, "\\rightharpoonup", '⇀', &["rightharpoonup"], None),
+    symbol(Arrow, "\\leftharpoonup", '↼', &["leftharpoonup"], None),
+    symbol(
+        Arrow,
+        "\\twoheadrightarrow",
+        '↠',
+        &["twoheadrightarrow"],
+        Some("amssymb"),
+    ),
+    symbol(
+        Arrow,
+        "\\circlearrowleft",
+        '↺',
+        &["circlearrowleft"],
+        Some("amssymb"),
+    ),
+    symbol(
+        Arrow,
+        "\\circlearrowright",
+        '↻',
+        &["circlearrowright"],
+        Some("amssymb"),
+    ),
+    symbol(Arrow, "\\leadsto", '⇝', &["~>", "leadsto"], Some("amssymb")),
+    symbol(
+        Logic,
+        "\\implies",
+        '⟹',
+        &["==>", "implies"],
+        Some("amsmath"),
+    ),
+    symbol(
+        Logic,
+        "\\impliedby",
+        '⟸',
+        &["<==", "impliedby"],
+        Some("amsmath"),
+    ),
+    symbol(Logic, "\\iff", '⟺', &["<==>", "iff"], None),
+    symbol(Logic, "\\land", '∧', &["/\\", "&&", "and", "land"], None),
+    symbol(Logic, "\\lor", '∨', &["\\/", "||", "or", "lor"], None),
+    symbol(Logic, "\\wedge", '∧', &["wedge"], None),
+    symbol(Logic, "\\vee", '∨', &["vee"], None),
+    symbol(Logic, "\\neg", '¬', &["!", "not", "neg"], None),
+    symbol(Logic, "\\veebar", '⊻', &["xor", "veebar"], Some("amssymb")),
+    symbol(Logic, "\\forall", '∀', &["forall"], None),
+    symbol(Logic, "\\exists", '∃', &["exists"], None),
+    symbol(Logic, "\\nexists", '∄', &["nexists"], Some("amssymb")),
+    symbol(Logic, "\\top", '⊤', &["top", "true"], None),
+    symbol(Logic, "\\bot", '⊥', &["bot", "false"], None),
+    symbol(Logic, "\\therefore", '∴', &["therefore"], Some("amssymb")),
+    symbol(Logic, "\\because", '∵', &["because"], Some("amssymb")),
+    symbol(Set, "\\in", '∈', &["in"], None),
+    symbol(Set, "\\notin", '∉', &["notin"], None),
+    symbol(Set, "\\ni", '∋', &["ni"], None),
+    symbol(Set, "\\subset", '⊂', &["subset"], None),
+    symbol(Set, "\\subseteq", '⊆', &["subseteq"], None),
+    symbol(Set, "\\subsetneq", '⊊', &["subsetneq"], Some("amssymb")),
+    symbol(Set, "\\nsubseteq", '⊈', &["nsubseteq"], Some("amssymb")),
+    symbol(Set, "\\supset", '⊃', &["supset"], None),
+    symbol(Set, "\\supseteq", '⊇', &["supseteq"], None),
+    symbol(Set, "\\supsetneq", '⊋', &["supsetneq"], Some("amssymb")),
+    symbol(Set, "\\cup", '∪', &["cup"], None),
+    symbol(Set, "\\cap", '∩', &["cap"], None),
+    symbol(Set, "\\setminus", '∖', &["setminus"], None),
+    symbol(Set, "\\emptyset", '∅', &["emptyset"], None),
+    symbol(Set, "\\varnothing", '∅', &["varnothing"], Some("amssymb")),
+    symbol(Set, "\\complement", '∁', &["complement"], Some("amssymb")),
+    symbol(Set, "\\sqcup", '⊔', &["sqcup"], None),
+    symbol(Set, "\\sqcap", '⊓', &["sqcap"], None),
+    symbol(Set, "\\uplus", '⊎', &["uplus"], None),
+    symbol(Operator, "\\times", '×', &["times"], None),
+    symbol(Operator, "\\cdot", '⋅', &["cdot"], None),
+    symbol(Operator, "\\div", '÷', &["div"], None),
+    symbol(Operator, "\\pm", '±', &["+-", "pm"], None),
+    symbol(Operator, "\\mp", '∓', &["-+", "mp"], None),
+    symbol(Operator, "\\ast", '∗', &["ast"], None),
+    symbol(Operator, "\\star", '⋆', &["star"], None),
+    symbol(Operator, "\\circ", '∘', &["circ", "compose"], None),
+    symbol(Operator, "\\bullet", '∙', &["bullet"], None),
+    symbol(Operator, "\\oplus", '⊕', &["oplus"], None),
+    symbol(Operator, "\\ominus", '⊖', &["ominus"], None),
+    symbol(Operator, "\\otimes", '⊗', &["otimes"], None),
+    symbol(Operator, "\\odot", '⊙', &["odot"], None),
+    symbol(Operator, "\\oslash", '⊘', &["oslash"], None),
+    symbol(Operator, "\\wr", '≀', &["wr"], None),
+    symbol(Operator, "\\dagger", '†', &["dagger"], None),
+    symbol(Operator, "\\ddagger", '‡', &["ddagger"], None),
+    symbol(Operator, "\\amalg", '⨿', &["amalg"], None),
+    symbol(Operator, "\\nabla", '∇', &["nabla", "grad"], None),
+    symbol(Operator, "\\sum", '∑', &["sum"], None),
+    symbol(Operator, "\\prod", '∏', &["prod"], None),
+    symbol(Operator, "\\coprod", '∐', &["coprod"], None),
+    symbol(Operator, "\\bigcup", '⋃', &["bigcup"], None),
+    symbol(Operator, "\\bigcap", '⋂', &["bigcap"], None),
+    symbol(Operator, "\\int", '∫', &["int"], None),
+    symbol(Operator, "\\iint", '∬', &["iint"], Some("amsmath")),
+    symbol(Operator, "\\iiint", '∭', &["iiint"], Some("amsmath")),
+    symbol(Operator, "\\oint", '∮', &["oint"], None),
+    symbol(Letter, "\\infty", '∞', &["inf", "infty", "infinity"], None),
+    symbol(Letter, "\\partial", '∂', &["partial"], None),
+    symbol(Letter, "\\aleph", 'ℵ', &["aleph"], None),
+    symbol(Letter, "\\beth", 'ℶ', &["beth"], Some("amssymb")),
+    symbol(Letter, "\\gimel", 'ℷ', &["gimel"], Some("amssymb")),
+    symbol(Letter, "\\hbar", 'ℏ', &["hbar"], None),
+    symbol(Letter, "\\ell", 'ℓ', &["ell"], None),
+    symbol(Letter, "\\wp", '℘', &["wp"], None),
+    symbol(Letter, "\\Re", 'ℜ', &["Re"], None),
+    symbol(Letter, "\\Im", 'ℑ', &["Im"], None),
+    symbol(Letter, "\\imath", 'ı', &["imath"], None),
+    symbol(Letter, "\\jmath", 'ȷ', &["jmath"], None),
+    symbol(Letter, "\\mathbb{A}", '𝔸', &["AA"], Some("amssymb")),
+    symbol(Letter, "\\mathbb{B}", '𝔹', &["BB"], Some("amssymb")),
+    symbol(Letter, "\\mathbb{C}", 'ℂ', &["CC"], Some("amssymb")),
+    symbol(Letter, "\\mathbb{D}", '𝔻', &["DD"], Some("amssymb")),
+    symbol(Letter, "\\mathbb{E}", '𝔼', &["EE"], Some("amssymb")),
+    symbol(Letter, "\\mathbb{F}", '𝔽', &["FF"], Some("amssymb")),
+    symbol(Letter, "\\mathbb{G}", '𝔾', &["GG"], Some("amssymb")),
+    symbol(Letter, "\\mathbb{H}", 'ℍ', &["HH"], Some("amssymb")),
+    symbol(Letter, "\\mathbb{I}", '𝕀', &["II"], Some("amssymb")),
+    symbol(Letter, "\\mathbb{J}", '𝕁', &["JJ"], Some("amssymb")),
+    symbol(Letter, "\\mathbb{K}", '𝕂', &["KK"], Some("amssymb")),
+    symbol(Letter, "\\mathbb{L}", '𝕃', &["LL"], Some("amssymb")),
+    symbol(Letter, "\\mathbb{M}", '𝕄', &["MM"], Some("amssymb")),
+    symbol(Letter, "\\mathbb{N}", 'ℕ', &["NN"], Some("amssymb")),
+    symbol(Letter, "\\mathbb{O}", '𝕆', &["OO"], Some("amssymb")),
+    symbol(Letter, "\\mathbb{P}", 'ℙ', &["PP"], Some("amssymb")),
+    symbol(Letter, "\\mathbb{Q}", 'ℚ', &["QQ"], Some("amssymb")),
+    symbol(Letter, "\\mathbb{R}", 'ℝ', &["RR"], Some("amssymb")),
+    symbol(Letter, "\\mathbb{S}", '𝕊', &["SS"], Some("amssymb")),
+    symbol(Letter, "\\mathbb{T}", '𝕋', &["TT"], Some("amssymb")),
+    symbol(Letter, "\\mathbb{U}", '𝕌', &["UU"], Some("amssymb")),
+    symbol(Letter, "\\mathbb{V}", '𝕍', &["VV"], Some("amssymb")),
+    symbol(Letter, "\\mathbb{W}", '𝕎', &["WW"], Some("amssymb")),
+    symbol(Letter, "\\mathbb{X}", '𝕏', &["XX"], Some("amssymb")),
+    symbol(Letter, "\\mathbb{Y}", '𝕐', &["YY"], Some("amssymb")),
+    symbol(Letter, "\\mathbb{Z}", 'ℤ', &["ZZ"], Some("amssymb")),
+    symbol(Letter, "\\mathcal{A}", '𝒜', &["calA"], None),
+    symbol(Letter, "\\mathcal{B}", 'ℬ', &["calB"], None),
+    symbol(Letter, "\\mathcal{C}", '𝒞', &["calC"], None),
+    symbol(Letter, "\\mathcal{D}", '𝒟', &["calD"], None),
+    symbol(Letter, "\\mathcal{E}", 'ℰ', &["calE"], None),
+    symbol(Letter, "\\mathcal{F}", 'ℱ', &["calF"], None),
+    symbol(Letter, "\\mathcal{G}", '𝒢', &["calG"], None),
+    symbol(Letter, "\\mathcal{H}", 'ℋ', &["calH"], None),
+    symbol(Letter, "\\mathcal{I}", 'ℐ', &["calI"], None),
+    symbol(Letter, "\\mathcal{J}", '𝒥', &["calJ"], None),
+    symbol(Letter, "\\mathcal{K}", '𝒦', &["calK"], None),
+    symbol(Letter, "\\mathcal{L}", 'ℒ', &["calL"], None),
+    symbol(Letter, "\\mathcal{M}", 'ℳ', &["calM"], None),
+    symbol(Letter, "\\mathcal{N}", '𝒩', &["calN"], None),
+    symbol(Letter, "\\mathcal{O}", '𝒪', &["calO"], None),
+    symbol(Letter, "\\mathcal{P}", '𝒫', &["calP"], None),
+    symbol(Letter, "\\mathcal{Q}", '𝒬', &["calQ"], None),
+    symbol(Letter, "\\mathcal{R}", 'ℛ', &["calR"], None),
+    symbol(Letter, "\\mathcal{S}", '𝒮', &["calS"], None),
+    symbol(Letter, "\\mathcal{T}", '𝒯', &["calT"], None),
+    symbol(Letter, "\\mathcal{U}", '𝒰', &["calU"], None),
+    symbol(Letter, "\\mathcal{V}", '𝒱', &["calV"], None),
+    symbol(Letter, "\\mathcal{W}", '𝒲', &["calW"], None),
+    symbol(Letter, "\\mathcal{X}", '𝒳', &["calX"], None),
+    symbol(Letter, "\\mathcal{Y}", '𝒴', &["calY"], None),
+    symbol(Letter, "\\mathcal{Z}", '𝒵', &["calZ"], None),
+    symbol(Misc, "\\ldots", '…', &["...", "ldots"], None),
+    symbol(Misc, "\\cdots", '⋯', &["cdots"], None),
+    symbol(Misc, "\\vdots", '⋮', &["vdots"], None),
+    symbol(Misc, "\\ddots", '⋱', &["ddots"], None),
+    symbol(Misc, "\\prime", '′', &["prime"], None),
+    symbol(Misc, "\\angle", '∠', &["angle"], None),
+    symbol(Misc, "\\triangle", '△', &["triangle"], None),
+    symbol(Misc, "\\square", '□', &["square"], Some("amssymb")),
+    symbol(
+        Misc,
+        "\\blacksquare",
+        '■',
+        &["blacksquare", "qed"],
+        Some("amssymb"),
+    ),
+    symbol(Misc, "\\checkmark", '✓', &["checkmark"], Some("amssymb")),
+    symbol(Misc, "\\langle", '⟨', &["langle"], None),
+    symbol(Misc, "\\rangle", '⟩', &["rangle"], None),
+    symbol(Misc, "\\lceil", '⌈', &["lceil"], None),
+    symbol(Misc, "\\rceil", '⌉', &["rceil"], None),
+    symbol(Misc, "\\lfloor", '⌊', &["lfloor"], None),
+    symbol(Misc, "\\rfloor", '⌋', &["rfloor"], None),
+    symbol(Misc, "\\sharp", '♯', &["sharp"], None),
+    symbol(Misc, "\\flat", '♭', &["flat"], None),
+    symbol(Misc, "\\natural", '♮', &["natural"], None),
+    symbol(Misc, "\\clubsuit", '♣', &["clubsuit"], None),
+    symbol(Misc, "\\diamondsuit", '♢', &["diamondsuit"], None),
+    symbol(Misc, "\\heartsuit", '♡', &["heartsuit"], None),
+    symbol(Misc, "\\spadesuit", '♠', &["spadesuit"], None),
+];
+
+impl MathSymbol {
+    /// Returns the symbol of an ASCII name (<=, alpha, RR, ...)
+    pub fn from_alias(alias: &str) -> Option<&'static MathSymbol> {
+        SYMBOL_TABLE.iter().find(|s| s.aliases.contains(&alias))
+    }
+
+    /// Returns the symbol of a Unicode character
+    pub fn from_unicode(c: char) -> Option<&'static MathSymbol> {
+        SYMBOL_TABLE.iter().find(|s| s.unicode == c)
+    }
+
+    /// Returns the symbol of a LaTeX command, with or without its backslash
+    pub fn from_latex(command: &str) -> Option<&'static MathSymbol> {
+        SYMBOL_TABLE
+            .iter()
+            .find(|s| s.latex == command || s.latex.trim_start_matches('\\') == command)
+    }
+
+    /// Returns the symbol of an ASCII name, a LaTeX command or a Unicode character
+    pub fn find(s: &str) -> Option<&'static MathSymbol> {
+        let mut chars = s.chars();
+        let unicode = match (chars.next(), chars.next()) {
+            (Some(c), None) => MathSymbol::from_unicode(c),
+            _ => None,
+        };
+        MathSymbol::from_alias(s)
+            .or_else(|| {
+                if s.starts_with('\\') {
+                    MathSymbol::from_latex(s)
+                } else {
+                    None
+                }
+            })
+            .or(unicode)
+    }
+}
+
+/// Returns the text with the known Unicode symbols replaced by their LaTeX commands
+pub fn unicode_to_latex(text: &str) -> String {
+    let mut latex = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match MathSymbol::from_unicode(c) {
+            Some(s) if !c.is_ascii() => {
+                latex.push_str(s.latex);
+                // \in x, not \inx
+                if s.latex.ends_with(|c: char| c.is_ascii_alphabetic())
+                    && chars.peek().is_some_and(|c| c.is_ascii_alphabetic())
+                {
+                    latex.push(' ');
+                }
+            }
+            _ => latex.push(c),
+        }
+    }
+    latex
+}
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum Symbols {
@@ -15,24 +467,46 @@ pub enum Symbols {
     More,
     /// !=
     Diff,
+    /// Any other symbol of the table
+    Other(&'static MathSymbol),
 }
 
 impl Symbols {
     /// Returns the enum corresponding to the String
-    pub fn get_symbol(symb: String) -> Self {
-        match symb.as_ref() {
+    /// (an ASCII name, a LaTeX command or a Unicode character of the table)
+    pub fn get_symbol(symb: String) -> Result<Self> {
+        match MathSymbol::find(&symb) {
+            Some(s) => Ok(Symbols::from_table(s)),
+            None => Err(Error::UnknownSymbol(symb)),
+        }
+    }
+
+    /// Returns the enum corresponding to a symbol of the table
+    pub fn from_table(symbol: &'static MathSymbol) -> Self {
+        match symbol.latex {
             "=" => Symbols::Equals,
-            "==" => Symbols::Equals,
-            "<=" => Symbols::LessOrEquals,
+            "\\leq" => Symbols::LessOrEquals,
             "<" => Symbols::Less,
-            ">=" => Symbols::MoreOrEquals,
+            "\\geq" => Symbols::MoreOrEquals,
             ">" => Symbols::More,
-            "!=" => Symbols::Diff,
-            "<>" => Symbols::Diff,
-            _ => panic!("get_symbol: The symbol {} is not a valid symbol !", symb),
+            "\\neq" => Symbols::Diff,
+            _ => Symbols::Other(symbol),
         }
     }
 
+    /// Returns the symbol of the table
+    pub fn get_entry(&self) -> &'static MathSymbol {
+        match *self {
+            Symbols::Other(s) => s,
+            _ => MathSymbol::from_alias(self.get_string()).expect("The relations are in the table"),
+        }
+    }
+
+    /// Returns the package defining the symbol, if any
+    pub fn get_package(&self) -> Option<&'static str> {
+        self.get_entry().package
+    }
+
     /// Returns the string corresponding to the enum
     pub fn get_string(&self) -> &str {
         match *self {
@@ -42,6 +516,7 @@ impl Symbols {
             Symbols::MoreOrEquals => ">=",
             Symbols::More => ">",
             Symbols::Diff => "!=",
+            Symbols::Other(s) => s.aliases.first().unwrap_or(&s.latex),
         }
     }
 
@@ -54,20 +529,15 @@ impl Symbols {
             Symbols::MoreOrEquals => " \\geq ",
             Symbols::More => " > ",
             Symbols::Diff => " \\neq ",
+            Symbols::Other(s) => return format!(" {} ", s.latex),
         };
         String::from(x)
     }
 }
 
+/// Returns true if the string is a relation of the table (=, <=, ~~, ...)
 pub fn is_op(s: &str) -> bool {
-    s == "=="
-        || s == "="
-        || s == "<"
-        || s == "<="
-        || s == ">"
-        || s == ">="
-        || s == "!="
-        || s == "<>"
+    MathSymbol::find(s).is_some_and(|s| s.kind == SymbolKind::Relation)
 }
 
 #[cfg(test)]
@@ -76,14 +546,40 @@ mod tests_symbols {
 
     #[test]
     fn getting_symbols() {
-        assert_eq!(Symbols::get_symbol("==".to_string()), Symbols::Equals);
-        assert_eq!(Symbols::get_symbol("=".to_string()), Symbols::Equals);
-        assert_eq!(Symbols::get_symbol(">=".to_string()), Symbols::MoreOrEquals);
-        assert_eq!(Symbols::get_symbol("<=".to_string()), Symbols::LessOrEquals);
-        assert_eq!(Symbols::get_symbol("<".to_string()), Symbols::Less);
-        assert_eq!(Symbols::get_symbol(">".to_string()), Symbols::More);
-        assert_eq!(Symbols::get_symbol("!=".to_string()), Symbols::Diff);
-        assert_eq!(Symbols::get_symbol("<>".to_string()), Symbols::Diff);
+        assert_eq!(
+            Symbols::get_symbol("==".to_string()).unwrap(),
+            Symbols::Equals
+        );
+        assert_eq!(
+            Symbols::get_symbol("=".to_string()).unwrap(),
+            Symbols::Equals
+        );
+        assert_eq!(
+            Symbols::get_symbol(">=".to_string()).unwrap(),
+            Symbols::MoreOrEquals
+        );
+        assert_eq!(
+            Symbols::get_symbol("<=".to_string()).unwrap(),
+            Symbols::LessOrEquals
+        );
+        assert_eq!(Symbols::get_symbol("<".to_string()).unwrap(), Symbols::Less);
+        assert_eq!(Symbols::get_symbol(">".to_string()).unwrap(), Symbols::More);
+        assert_eq!(
+            Symbols::get_symbol("!=".to_string()).unwrap(),
+            Symbols::Diff
+        );
+        assert_eq!(
+            Symbols::get_symbol("<>".to_string()).unwrap(),
+            Symbols::Diff
+        );
+    }
+
+    #[test]
+    fn unknown_symbol() {
+        match Symbols::get_symbol("=>".to_string()) {
+            Err(Error::UnknownSymbol(s)) => assert_eq!(s, "=>"),
+            _ => panic!("'=>' should not be a known symbol"),
+        }
     }
 
     #[test]
@@ -91,9 +587,47 @@ mod tests_symbols {
         assert_eq!(Symbols::get_string(&Symbols::Equals), "=");
         assert_eq!(Symbols::get_string(&Symbols::More), ">");
         assert_eq!(Symbols::get_string(&Symbols::MoreOrEquals), ">=");
-        assert_eq!(Symbols::get_string(&Symbols::LessOrEquals), "<=");;
+        assert_eq!(Symbols::get_string(&Symbols::LessOrEquals), "<=");
         assert_eq!(Symbols::get_string(&Symbols::Less), "<");
         assert_eq!(Symbols::get_string(&Symbols::Diff), "!=");
     }
 
+    #[test]
+    fn table_both_ways() {
+        let approx = MathSymbol::from_alias("~~").unwrap();
+        assert_eq!(approx.latex, "\\approx");
+        assert_eq!(approx.unicode, '≈');
+        assert_eq!(MathSymbol::from_unicode('≈'), Some(approx));
+        assert_eq!(MathSymbol::from_latex("\\approx"), Some(approx));
+        assert_eq!(MathSymbol::from_latex("approx"), Some(approx));
+        let reals = MathSymbol::find("ℝ").unwrap();
+        assert_eq!(reals.latex, "\\mathbb{R}");
+        assert_eq!(reals.aliases, &["RR"]);
+        assert_eq!(reals.package, Some("amssymb"));
+        assert_eq!(MathSymbol::find("calL").unwrap().unicode, 'ℒ');
+        assert_eq!(MathSymbol::find("alpha").unwrap().kind, SymbolKind::Greek);
+        assert_eq!(MathSymbol::from_unicode('→').unwrap().latex, "\\to");
+        assert!(SYMBOL_TABLE.len() > 200);
+    }
+
+    #[test]
+    fn table_symbols() {
+        assert_eq!(
+            Symbols::get_symbol("≤".to_string()).unwrap(),
+            Symbols::LessOrEquals
+        );
+        assert_eq!(
+            Symbols::get_symbol("\\neq".to_string()).unwrap(),
+            Symbols::Diff
+        );
+        let implies = Symbols::get_symbol("==>".to_string()).unwrap();
+        assert_eq!(implies.latex_code(), " \\implies ");
+        assert_eq!(implies.get_string(), "==>");
+        assert_eq!(implies.get_package(), Some("amsmath"));
+        assert_eq!(Symbols::Equals.get_entry().unicode, '=');
+        assert!(is_op("~~") && is_op("<>") && is_op("≡"));
+        assert!(!is_op("->") && !is_op("x"));
+        assert_eq!(unicode_to_latex("∀x ∈ ℕ"), "\\forall x \\in \\mathbb{N}");
+        assert_eq!(unicode_to_latex("α≤β"), "\\alpha\\leq\\beta");
+    }
 }
@@ -0,0 +1,274 @@
+/// File defining the tables: a tabular in a float (\begin{table})
+/// with a caption, a label and notes, or a longtable breaking across pages
+///
+use error::*;
+use escape::*;
+use labels::*;
+use packages::Packages;
+use std::io::Write;
+use tabular::*;
+use writable::*;
+
+#[derive(Clone)]
+pub struct Table {
+    /// The content of the table
+    tabular: Tabular,
+    /// The caption, written above the tabular
+    caption: Option<Text>,
+    /// The placement of the float (htbp by default)
+    placement: Option<String>,
+    /// If true, the tabular is centered
+    centering: bool,
+    /// The notes written below the tabular
+    notes: Vec<Text>,
+    /// If true, the table is a longtable, which can break across pages
+    long: bool,
+    /// The label of the table
+    label: Option<Label>,
+}
+
+impl Table {
+    /// Returns a new Table, centered and placed with [htbp]
+    /// The label of the tabular becomes the label of the table
+    pub fn new(mut tabular: Tabular) -> Self {
+        let label = tabular.take_label();
+        Table {
+            tabular,
+            caption: None,
+            placement: Some("htbp".to_string()),
+            centering: true,
+            notes: Vec::new(),
+            long: false,
+            label,
+        }
+    }
+
+    /// Returns a new Table with a caption
+    pub fn with_caption<T: AsRef<str>>(tabular: Tabular, caption: T) -> Self {
+        let mut table = Table::new(tabular);
+        table.set_caption(caption);
+        table
+    }
+
+    /// Returns the tabular of the table
+    pub fn get_tabular(&self) -> &Tabular {
+        &self.tabular
+    }
+
+    /// Returns the tabular of the table, to modify it
+    pub fn tabular_mut(&mut self) -> &mut Tabular {
+        &mut self.tabular
+    }
+
+    /// Changes the caption of the table
+    pub fn set_caption<T: AsRef<str>>(&mut self, caption: T) {
+        self.caption = Some(Text::new(caption));
+    }
+
+    /// Changes the caption of the table, written verbatim (trusted LaTeX code)
+    pub fn set_raw_caption<T: AsRef<str>>(&mut self, caption: T) {
+        self.caption = Some(Text::raw(caption));
+    }
+
+    /// Returns the caption of the table
+    pub fn get_caption(&self) -> Option<&str> {
+        self.caption.as_ref().map(|c| c.content())
+    }
+
+    /// Changes the placement of the float (h, t, b, p, H, ...)
+    pub fn set_placement<T: AsRef<str>>(&mut self, placement: T) {
+        self.placement = Some(placement.as_ref().to_string());
+    }
+
+    /// Removes the placement of the float (LaTeX then uses its default)
+    pub fn remove_placement(&mut self) {
+        self.placement = None;
+    }
+
+    /// Enables or disables the centering of the tabular
+    pub fn set_centering(&mut self, centering: bool) {
+        self.centering = centering;
+    }
+
+    /// Adds a note below the tabular
+    pub fn add_note<T: AsRef<str>>(&mut self, note: T) {
+        self.notes.push(Text::new(note));
+    }
+
+    /// Writes the table as a longtable, which can break across pages
+    /// (the header row is repeated on every page)
+    pub fn set_long(&mut self, long: bool) {
+        self.long = long;
+    }
+
+    /// Returns true if the table is a longtable
+    pub fn is_long(&self) -> bool {
+        self.long
+    }
+
+    /// Labels the table, and returns the handle to refer to it
+    pub fn set_label<T: AsRef<str>>(&mut self, name: T) -> Label {
+        let label = Label::new(LabelKind::Table, name);
+        self.label = Some(label.clone());
+        label
+    }
+
+    /// Returns the label of the table (or the one set on its tabular)
+    pub fn get_label(&self) -> Option<&Label> {
+        self.label.as_ref().or_else(|| self.tabular.get_label())
+    }
+
+    /// Returns the caption and the label, as written on one line
+    fn caption_line(&self) -> Result<Option<String>> {
+        match self.caption {
+            Some(ref caption) => {
+                let mut line = format!("\\caption{{{}}}", caption);
+                if let Some(label) = self.get_label() {
+                    line.push_str(&label.to_latex()?);
+                }
+                Ok(Some(line))
+            }
+            None => match self.get_label() {
+                Some(label) => Err(Error::InvalidStructure(format!(
+                    "The table {} needs a caption to be referenced",
+                    label.key()
+                ))),
+                None => Ok(None),
+            },
+        }
+    }
+
+    fn write_notes<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        if self.notes.is_empty() {
+            return Ok(());
+        }
+        writeln!(buf, "\\par\\smallskip")?;
+        write!(buf, "{{\\footnotesize")?;
+        for note in self.notes.iter() {
+            write!(buf, " {}\\par", note)?;
+        }
+        writeln!(buf, "}}")?;
+        Ok(())
+    }
+}
+
+impl Writable for Table {
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        let caption = self.caption_line()?;
+        if self.long {
+            // A longtable is not a float: it is centered and placed where it is written
+            self.tabular.write_longtable(buf, caption.as_deref())?;
+            return self.write_notes(buf);
+        }
+        match self.placement {
+            Some(ref placement) => writeln!(buf, "\\begin{{table}}[{}]", placement)?,
+            None => writeln!(buf, "\\begin{{table}}")?,
+        }
+        if self.centering {
+            writeln!(buf, "\\centering")?;
+        }
+        if let Some(caption) = caption {
+            writeln!(buf, "{}", caption)?;
+        }
+        self.tabular.write_tabular(buf)?;
+        self.write_notes(buf)?;
+        writeln!(buf, "\\end{{table}}")?;
+        Ok(())
+    }
+
+    fn add_packages(&self, packages: &mut Packages) {
+        if self.long {
+            packages.add_package("longtable");
+        }
+        if self.placement.as_ref().is_some_and(|p| p.contains('H')) {
+            packages.add_package("float");
+        }
+        self.tabular.add_packages(packages);
+    }
+}
+
+#[cfg(test)]
+mod tests_table {
+    use super::*;
+    use core::*;
+
+    fn tabular() -> Tabular {
+        let mut tab = Tabular::new(&vec![Core::text("1"), Core::text("2")]);
+        tab.set_header(vec![Core::text("A"), Core::text("B")]);
+        tab.set_rules(RuleStyle::Booktabs);
+        tab.set_vertical_rules(false);
+        tab
+    }
+
+    #[test]
+    fn float_table() {
+        let mut table = Table::with_caption(tabular(), "Results & more");
+        table.set_label("results");
+        table.add_note("Measured twice");
+        assert_eq!(
+            table.to_latex().unwrap(),
+            "\\begin{table}[htbp]\n\
+             \\centering\n\
+             \\caption{Results \\& more}\\label{tab:results}\n\
+             \\begin{tabular}{c c}\n \\toprule\nA & B \\\\\n \\midrule\n1 & 2 \\\\\n \\bottomrule\n\\end{tabular}\n\
+             \\par\\smallskip\n\
+             {\\footnotesize Measured twice\\par}\n\
+             \\end{table}\n"
+        );
+    }
+
+    #[test]
+    fn label_of_the_tabular() {
+        let mut tab = tabular();
+        tab.set_label("moved");
+        let mut table = Table::new(tab);
+        table.remove_placement();
+        table.set_centering(false);
+        match table.to_latex() {
+            Err(Error::InvalidStructure(_)) => (),
+            _ => panic!("A labelled table needs a caption"),
+        }
+        table.set_caption("Moved");
+        assert_eq!(table.get_label().unwrap().key(), "tab:moved");
+        assert!(table
+            .to_latex()
+            .unwrap()
+            .starts_with("\\begin{table}\n\\caption{Moved}\\label{tab:moved}\n\\begin{tabular}"));
+    }
+
+    #[test]
+    fn label_set_on_the_tabular() {
+        let mut tab = tabular();
+        tab.set_label("bare");
+        match tab.to_latex() {
+            Err(Error::InvalidStructure(_)) => (),
+            _ => panic!("A tabular can only be referenced in a table"),
+        }
+        let mut table = Table::with_caption(tabular(), "Later");
+        let later = table.tabular_mut().set_label("later");
+        assert!(table
+            .to_latex()
+            .unwrap()
+            .contains("\\caption{Later}\\label{tab:later}\n\\begin{tabular}"));
+        let elements = vec![Core::Table(table), Core::reference(&later)];
+        assert!(check_references(&elements).is_ok());
+    }
+
+    #[test]
+    fn longtable() {
+        let mut table = Table::with_caption(tabular(), "Benchmark");
+        table.set_long(true);
+        assert_eq!(
+            table.to_latex().unwrap(),
+            "\\begin{longtable}{c c}\n\
+             \\caption{Benchmark} \\\\\n \
+             \\toprule\nA & B \\\\\n \\midrule\n\\endfirsthead\n \
+             \\toprule\nA & B \\\\\n \\midrule\n\\endhead\n\
+             1 & 2 \\\\\n \\bottomrule\n\\end{longtable}\n"
+        );
+        let mut packages = Packages::new();
+        table.add_packages(&mut packages);
+        assert!(packages.contains("longtable"));
+        assert!(packages.contains("booktabs"));
+    }
+}
@@ -1,53 +1,805 @@
 /// Implementation of tabular
 ///
 use core::*;
+use error::*;
 use into_tab::*;
-use latex_file::LatexFile;
-use std::io::BufWriter;
+use labels::*;
+use packages::Packages;
 use std::io::Write;
 use writable::Writable;
 
+/// The alignment of a column
+#[derive(Clone, Debug, PartialEq)]
+pub enum ColumnSpec {
+    /// l
+    Left,
+    /// r
+    Right,
+    /// c
+    Center,
+    /// p{width}, a paragraph of the given width (e.g. 3cm)
+    Paragraph(String),
+    /// S, numbers aligned on the decimal point (siunitx)
+    Number,
+}
+
+impl ColumnSpec {
+    /// Returns the LaTeX code of the column
+    pub fn code(&self) -> String {
+        match *self {
+            ColumnSpec::Left => "l".to_string(),
+            ColumnSpec::Right => "r".to_string(),
+            ColumnSpec::Center => "c".to_string(),
+            ColumnSpec::Paragraph(ref width) => format!("p{{{}}}", width),
+            ColumnSpec::Number => "S".to_string(),
+        }
+    }
+}
+
+/// The horizontal rules of a tabular
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RuleStyle {
+    /// \hline above, between and below the rows
+    Lines,
+    /// \toprule, \midrule under the header and \bottomrule (booktabs)
+    Booktabs,
+    /// No horizontal rule
+    NoRule,
+}
+
+/// The numbers of a column written in bold
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Highlight {
+    /// The lowest numbers
+    Min,
+    /// The highest numbers
+    Max,
+}
+
+/// A cell of a tabular, which can span several columns (\multicolumn) or rows (\multirow)
+#[derive(Clone)]
+pub struct Cell {
+    /// The content of the cell
+    content: Core,
+    /// The number of columns of the cell
+    columns: usize,
+    /// The number of rows of the cell
+    rows: usize,
+    /// The alignment of the cell, instead of the one of its column
+    align: Option<ColumnSpec>,
+}
+
+impl Cell {
+    /// Returns a new Cell of one column and one row
+    pub fn new(content: Core) -> Self {
+        Cell {
+            content,
+            columns: 1,
+            rows: 1,
+            align: None,
+        }
+    }
+
+    /// Returns a new empty Cell
+    pub fn empty() -> Self {
+        Cell::new(Core::raw(""))
+    }
+
+    /// Returns a new Cell spanning several columns (\multicolumn)
+    pub fn multicolumn(content: Core, columns: usize, align: ColumnSpec) -> Self {
+        let mut cell = Cell::new(content);
+        cell.columns = columns;
+        cell.align = Some(align);
+        cell
+    }
+
+    /// Returns a new Cell spanning several rows (\multirow)
+    /// The cells it covers in the next rows are left out of these rows
+    pub fn multirow(content: Core, rows: usize) -> Self {
+        let mut cell = Cell::new(content);
+        cell.rows = rows;
+        cell
+    }
+
+    /// Changes the number of columns of the cell
+    pub fn set_columns(&mut self, columns: usize) {
+        self.columns = columns;
+    }
+
+    /// Changes the number of rows of the cell
+    pub fn set_rows(&mut self, rows: usize) {
+        self.rows = rows;
+    }
+
+    /// Changes the alignment of the cell
+    pub fn set_align(&mut self, align: ColumnSpec) {
+        self.align = Some(align);
+    }
+
+    /// Returns the content of the cell
+    pub fn get_content(&self) -> &Core {
+        &self.content
+    }
+
+    /// Returns the number of columns of the cell
+    pub fn get_columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Returns the number of rows of the cell
+    pub fn get_rows(&self) -> usize {
+        self.rows
+    }
+}
+
+impl From<Core> for Cell {
+    fn from(content: Core) -> Self {
+        Cell::new(content)
+    }
+}
+
+/// The place of a cell in the grid of the tabular
+enum Slot<'a> {
+    /// A cell starting at the column
+    Cell(&'a Cell, usize),
+    /// The part of a multirow cell of the previous rows (column, number of columns)
+    Covered(usize, usize),
+    /// A missing cell, added to complete a short row
+    Empty,
+}
+
 #[derive(Clone)]
 pub struct Tabular {
+    /// The header row
+    header: Option<Vec<Cell>>,
     /// Content
-    content: Vec<Vec<Core>>,
+    content: Vec<Vec<Cell>>,
+    /// The alignment of the columns (centered if not given)
+    columns: Option<Vec<ColumnSpec>>,
+    /// The horizontal rules
+    rules: RuleStyle,
+    /// If true, the columns are separated by vertical rules
+    vertical_rules: bool,
+    /// The numbers written in bold, by column
+    highlights: Vec<(usize, Highlight)>,
+    /// The label of the table containing the tabular
+    label: Option<Label>,
 }
 
 impl Tabular {
     pub fn new<T: IntoTab>(content: &T) -> Self {
         Tabular {
-            content: content.into_tab(),
+            header: None,
+            content: content
+                .into_tab()
+                .into_iter()
+                .map(|line| line.into_iter().map(Cell::new).collect())
+                .collect(),
+            columns: None,
+            rules: RuleStyle::Lines,
+            vertical_rules: true,
+            highlights: Vec::new(),
+            label: None,
         }
     }
 
-    fn align(&self) -> String {
-        let size = self.content[0].len();
-        let mut cols = String::from("|");
-        for _ in 0..size {
-            cols.push_str(" c |");
+    /// Returns a new Tabular with a row per item of the iterator (tuples, vectors, ...)
+    pub fn from_rows<I>(rows: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: IntoRow,
+    {
+        let rows: Vec<Vec<Core>> = rows.into_iter().map(|row| row.to_row()).collect();
+        Tabular::new(&rows)
+    }
+
+    /// Returns the cells of the tabular
+    pub fn get_content(&self) -> &[Vec<Cell>] {
+        &self.content
+    }
+
+    /// Returns the header row
+    pub fn get_header(&self) -> Option<&[Cell]> {
+        self.header.as_deref()
+    }
+
+    /// Changes the header row, written above the other rows and separated by a rule
+    pub fn set_header<T: Into<Cell>>(&mut self, header: Vec<T>) {
+        self.header = Some(header.into_iter().map(|c| c.into()).collect());
+    }
+
+    /// Removes the header row
+    pub fn remove_header(&mut self) {
+        self.header = None;
+    }
+
+    /// Adds a row at the end of the tabular
+    pub fn add_row<T: Into<Cell>>(&mut self, row: Vec<T>) {
+        self.content
+            .push(row.into_iter().map(|c| c.into()).collect());
+    }
+
+    /// Changes the alignment of the columns
+    /// A row with more columns than given is an error
+    pub fn set_columns(&mut self, columns: Vec<ColumnSpec>) {
+        self.columns = Some(columns);
+    }
+
+    /// Changes the horizontal rules
+    pub fn set_rules(&mut self, rules: RuleStyle) {
+        self.rules = rules;
+    }
+
+    /// Enables or disables the vertical rules between the columns
+    pub fn set_vertical_rules(&mut self, vertical_rules: bool) {
+        self.vertical_rules = vertical_rules;
+    }
+
+    /// Writes in bold the lowest or the highest numbers (Core::number) of a column
+    /// The numbers are compared as written, so the ties after rounding are all in bold
+    pub fn highlight_column(&mut self, column: usize, highlight: Highlight) {
+        self.highlights.retain(|h| h.0 != column);
+        self.highlights.push((column, highlight));
+    }
+
+    /// Labels the table containing the tabular, and returns the handle to refer to it
+    /// A labelled tabular can only be written in a Table
+    pub fn set_label<T: AsRef<str>>(&mut self, name: T) -> Label {
+        let label = Label::new(LabelKind::Table, name);
+        self.label = Some(label.clone());
+        label
+    }
+
+    /// Returns the label of the tabular
+    pub fn get_label(&self) -> Option<&Label> {
+        self.label.as_ref()
+    }
+
+    /// Removes the label of the tabular, and returns it
+    pub fn take_label(&mut self) -> Option<Label> {
+        self.label.take()
+    }
+
+    /// Returns the header and the rows
+    fn rows(&self) -> Vec<&[Cell]> {
+        self.header
+            .iter()
+            .chain(self.content.iter())
+            .map(|row| row.as_slice())
+            .collect()
+    }
+
+    /// Places the cells in the grid, skipping the places of the multirow cells
+    /// and completing the short rows up to width columns
+    /// Returns the rows and the number of columns of the widest one
+    fn layout(&self, width: Option<usize>) -> Result<(Vec<Vec<Slot<'_>>>, usize)> {
+        let rows = self.rows();
+        // For each column, the last row covered by a multirow cell and the number of columns
+        let mut covered: Vec<Option<(usize, usize)>> = Vec::new();
+        let mut slots = Vec::new();
+        let mut max_width = 0;
+        for (r, row) in rows.iter().enumerate() {
+            let mut line = Vec::new();
+            let mut cells = row.iter();
+            let mut col = 0;
+            loop {
+                if let Some(&Some((last, span))) = covered.get(col) {
+                    if r <= last {
+                        line.push(Slot::Covered(col, span));
+                        col += span;
+                        continue;
+                    }
+                }
+                match cells.next() {
+                    Some(cell) => {
+                        if cell.columns == 0 || cell.rows == 0 {
+                            return Err(Error::InvalidStructure(
+                                "A cell must span at least one row and one column".to_string(),
+                            ));
+                        }
+                        if cell.rows > 1 {
+                            if r + cell.rows > rows.len() {
+                                return Err(Error::InvalidStructure(
+                                    "A multirow cell spans more rows than the tabular".to_string(),
+                                ));
+                            }
+                            if covered.len() <= col {
+                                covered.resize(col + 1, None);
+                            }
+                            covered[col] = Some((r + cell.rows - 1, cell.columns));
+                        }
+                        line.push(Slot::Cell(cell, col));
+                        col += cell.columns;
+                    }
+                    None => {
+                        // Completes the row up to the width, and the multirow cells after its end
+                        let last_cover = covered
+                            .iter()
+                            .enumerate()
+                            .filter(|&(_, c)| c.is_some_and(|(last, _)| r <= last))
+                            .map(|(i, _)| i + 1)
+                            .max();
+                        let end = width.unwrap_or(0).max(last_cover.unwrap_or(0));
+                        if col >= end {
+                            break;
+                        }
+                        line.push(Slot::Empty);
+                        col += 1;
+                    }
+                }
+            }
+            if let Some(w) = width {
+                if col > w {
+                    return Err(Error::InvalidStructure(format!(
+                        "The row {} has {} columns, but the tabular has {}",
+                        r + 1,
+                        col,
+                        w
+                    )));
+                }
+            }
+            max_width = max_width.max(col);
+            slots.push(line);
+        }
+        if max_width == 0 {
+            return Err(Error::InvalidStructure(
+                "The first line of a tabular can not be empty".to_string(),
+            ));
         }
-        cols
+        Ok((slots, max_width))
+    }
+
+    /// Returns the cells to write in bold, from the highlighted columns
+    fn highlighted<'a>(&self, slots: &[Vec<Slot<'a>>]) -> Vec<&'a Cell> {
+        let body = if self.header.is_some() { 1 } else { 0 };
+        let mut cells = Vec::new();
+        for &(column, highlight) in self.highlights.iter() {
+            let numbers: Vec<(&Cell, f64)> = slots[body.min(slots.len())..]
+                .iter()
+                .flatten()
+                .filter_map(|slot| match *slot {
+                    Slot::Cell(cell, col) if col == column && cell.columns == 1 => {
+                        match cell.content {
+                            Core::Num(ref n) if !n.rounded().is_nan() => Some((cell, n.rounded())),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                })
+                .collect();
+            let values = numbers.iter().map(|n| n.1);
+            let best = match highlight {
+                Highlight::Min => values.fold(f64::INFINITY, f64::min),
+                Highlight::Max => values.fold(f64::NEG_INFINITY, f64::max),
+            };
+            cells.extend(numbers.iter().filter(|n| n.1 == best).map(|n| n.0));
+        }
+        cells
+    }
+
+    /// Returns the alignment of the columns
+    fn column_specs(&self, width: usize) -> Vec<ColumnSpec> {
+        match self.columns {
+            Some(ref columns) => columns.clone(),
+            None => vec![ColumnSpec::Center; width],
+        }
+    }
+
+    fn align(&self, columns: &[ColumnSpec]) -> String {
+        let codes: Vec<String> = columns.iter().map(|c| c.code()).collect();
+        if self.vertical_rules {
+            format!("| {} |", codes.join(" | "))
+        } else {
+            codes.join(" ")
+        }
+    }
+
+    /// Returns the column specification of a \multicolumn starting at the column
+    fn multicolumn_align(&self, align: &ColumnSpec, col: usize) -> String {
+        if !self.vertical_rules {
+            align.code()
+        } else if col == 0 {
+            format!("|{}|", align.code())
+        } else {
+            format!("{}|", align.code())
+        }
+    }
+
+    /// Writes a cell
+    fn write_cell<W: Write + ?Sized>(
+        &self,
+        buf: &mut W,
+        cell: &Cell,
+        col: usize,
+        columns: &[ColumnSpec],
+        header: bool,
+        bold: bool,
+    ) -> Result<()> {
+        let mut content = match cell.content {
+            Core::Num(ref number) => {
+                let mut number = number.clone();
+                if bold {
+                    number.set_bold(true);
+                }
+                // The S columns align the numbers read by siunitx
+                let spec = cell.align.as_ref().or_else(|| columns.get(col));
+                if spec == Some(&ColumnSpec::Number) && cell.rows == 1 {
+                    number.siunitx()
+                } else {
+                    number.to_latex()?
+                }
+            }
+            ref content => content.to_latex()?,
+        };
+        if cell.rows > 1 {
+            content = format!("\\multirow{{{}}}{{*}}{{{}}}", cell.rows, content);
+        }
+        match cell.align {
+            Some(ref align) => write!(
+                buf,
+                "\\multicolumn{{{}}}{{{}}}{{{}}}",
+                cell.columns,
+                self.multicolumn_align(align, col),
+                content
+            )?,
+            None if cell.columns > 1 => write!(
+                buf,
+                "\\multicolumn{{{}}}{{{}}}{{{}}}",
+                cell.columns,
+                self.multicolumn_align(&ColumnSpec::Center, col),
+                content
+            )?,
+            // The text of a header is not a number
+            None if header && columns.get(col) == Some(&ColumnSpec::Number) => {
+                write!(buf, "{{{}}}", content)?
+            }
+            None => write!(buf, "{}", content)?,
+        }
+        Ok(())
+    }
+
+    /// Writes the rule between the row and the next one
+    /// (\cline around the multirow cells going on in the next row)
+    fn write_rule_between<W: Write + ?Sized>(
+        &self,
+        buf: &mut W,
+        next: &[Slot],
+        width: usize,
+    ) -> Result<()> {
+        let mut free = vec![true; width];
+        for slot in next.iter() {
+            if let Slot::Covered(col, span) = *slot {
+                for f in free.iter_mut().skip(col).take(span) {
+                    *f = false;
+                }
+            }
+        }
+        if free.iter().all(|f| *f) {
+            writeln!(buf, " \\hline")?;
+            return Ok(());
+        }
+        let mut col = 0;
+        while col < width {
+            if free[col] {
+                let start = col;
+                while col < width && free[col] {
+                    col += 1;
+                }
+                write!(buf, " \\cline{{{}-{}}}", start + 1, col)?;
+            } else {
+                col += 1;
+            }
+        }
+        writeln!(buf)?;
+        Ok(())
+    }
+}
+
+impl Tabular {
+    /// Writes the rows in a longtable, breaking across pages
+    /// The head (e.g. the caption) is written on the first line,
+    /// and the header row is repeated at the top of every page
+    pub fn write_longtable<W: Write + ?Sized>(
+        &self,
+        buf: &mut W,
+        head: Option<&str>,
+    ) -> Result<()> {
+        self.write_environment(buf, "longtable", head)
+    }
+
+    /// Writes the rows in a tabular, the label is left to the Table containing it
+    pub fn write_tabular<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        self.write_environment(buf, "tabular", None)
+    }
+
+    /// Writes the rows in the environment (tabular or longtable)
+    fn write_environment<W: Write + ?Sized>(
+        &self,
+        buf: &mut W,
+        environment: &str,
+        head: Option<&str>,
+    ) -> Result<()> {
+        let width = match self.columns {
+            Some(ref columns) => columns.len(),
+            None => self.layout(None)?.1,
+        };
+        let (slots, _) = self.layout(Some(width))?;
+        let columns = self.column_specs(width);
+        let bold = self.highlighted(&slots);
+        let long = environment == "longtable";
+        writeln!(
+            buf,
+            "\\begin{{{}}}{{{}}}",
+            environment,
+            self.align(&columns)
+        )?;
+        if let Some(head) = head {
+            writeln!(buf, "{} \\\\", head)?;
+        }
+        self.write_top_rule(buf)?;
+        for (r, line) in slots.iter().enumerate() {
+            let header = r == 0 && self.header.is_some();
+            self.write_row(buf, line, &columns, header, &bold)?;
+            self.write_rule_after(buf, slots.get(r + 1), width, header)?;
+            if header && long {
+                // The header is repeated on the next pages
+                writeln!(buf, "\\endfirsthead")?;
+                self.write_top_rule(buf)?;
+                self.write_row(buf, line, &columns, header, &bold)?;
+                self.write_rule_after(buf, slots.get(r + 1), width, header)?;
+                writeln!(buf, "\\endhead")?;
+            }
+        }
+        match self.rules {
+            RuleStyle::Lines => writeln!(buf, " \\hline")?,
+            RuleStyle::Booktabs => writeln!(buf, " \\bottomrule")?,
+            RuleStyle::NoRule => (),
+        }
+        writeln!(buf, "\\end{{{}}}", environment)?;
+        Ok(())
+    }
+
+    fn write_top_rule<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        match self.rules {
+            RuleStyle::Lines => writeln!(buf, " \\hline")?,
+            RuleStyle::Booktabs => writeln!(buf, " \\toprule")?,
+            RuleStyle::NoRule => (),
+        }
+        Ok(())
+    }
+
+    /// Writes the rule after a row, if it is not the last one
+    fn write_rule_after<W: Write + ?Sized>(
+        &self,
+        buf: &mut W,
+        next: Option<&Vec<Slot>>,
+        width: usize,
+        header: bool,
+    ) -> Result<()> {
+        match (self.rules, next) {
+            (RuleStyle::Lines, Some(next)) => self.write_rule_between(buf, next, width)?,
+            (RuleStyle::Booktabs, Some(_)) if header => writeln!(buf, " \\midrule")?,
+            _ => (),
+        }
+        Ok(())
+    }
+
+    fn write_row<W: Write + ?Sized>(
+        &self,
+        buf: &mut W,
+        line: &[Slot],
+        columns: &[ColumnSpec],
+        header: bool,
+        bold: &[&Cell],
+    ) -> Result<()> {
+        for (i, slot) in line.iter().enumerate() {
+            if i > 0 {
+                write!(buf, " & ")?;
+            }
+            match *slot {
+                Slot::Cell(cell, col) => {
+                    let highlighted = bold.iter().any(|b| std::ptr::eq(*b, cell));
+                    self.write_cell(buf, cell, col, columns, header, highlighted)?
+                }
+                Slot::Covered(col, span) if span > 1 => write!(
+                    buf,
+                    "\\multicolumn{{{}}}{{{}}}{{}}",
+                    span,
+                    self.multicolumn_align(&ColumnSpec::Center, col)
+                )?,
+                Slot::Covered(..) | Slot::Empty => (),
+            }
+        }
+        writeln!(buf, " \\\\")?;
+        Ok(())
     }
 }
 
 impl Writable for Tabular {
-    fn write_latex(&self, file: &mut LatexFile) {
-        let mut writer = BufWriter::new(file);
-        self.write_to_buffer(&mut writer);
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        // Without a float and a caption, \ref would give the number of the section
+        if let Some(ref label) = self.label {
+            return Err(Error::InvalidStructure(format!(
+                "The tabular {} can only be referenced in a Table",
+                label.key()
+            )));
+        }
+        self.write_tabular(buf)
     }
 
-    fn write_to_buffer(&self, mut buf: &mut BufWriter<&mut LatexFile>) {
-        writeln!(&mut buf, "\\begin{{tabular}}{{{}}}", self.align()).unwrap();
-        for line in self.content.iter() {
-            writeln!(&mut buf, " \\hline").unwrap();
-            line[0].write_to_buffer(&mut buf);
-            for elem in line.iter().skip(1) {
-                write!(&mut buf, " & ").unwrap();
-                elem.write_to_buffer(&mut buf);
+    fn add_packages(&self, packages: &mut Packages) {
+        if self.rules == RuleStyle::Booktabs {
+            packages.add_package("booktabs");
+        }
+        let numbers = self
+            .columns
+            .iter()
+            .flatten()
+            .any(|c| *c == ColumnSpec::Number);
+        if numbers {
+            packages.add_package("siunitx");
+            let bold = !self.highlights.is_empty()
+                || self.rows().iter().any(|row| {
+                    row.iter()
+                        .any(|c| matches!(c.content, Core::Num(ref n) if n.is_bold()))
+                });
+            if bold {
+                // The bold numbers (\bfseries) of the S columns
+                packages.add_package_with_options("siunitx", vec!["detect-weight=true"]);
+            }
+        }
+        for line in self.rows().iter() {
+            for cell in line.iter() {
+                if cell.rows > 1 {
+                    packages.add_package("multirow");
+                }
+                if cell.align == Some(ColumnSpec::Number) && !numbers {
+                    packages.add_package("siunitx");
+                }
+                cell.content.add_packages(packages);
             }
-            writeln!(&mut buf, " \\\\").unwrap();
         }
-        writeln!(&mut buf, " \\hline").unwrap();
-        writeln!(&mut buf, "\\end{{tabular}}").unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests_tabular {
+    use super::*;
+    use number::Number;
+
+    fn texts(cells: &[&str]) -> Vec<Core> {
+        cells.iter().map(Core::text).collect()
+    }
+
+    #[test]
+    fn empty_tabular() {
+        let tab = Tabular::new(&Vec::<Core>::new());
+        match tab.to_latex() {
+            Err(Error::InvalidStructure(_)) => (),
+            _ => panic!("An empty tabular should not be written"),
+        }
+    }
+
+    #[test]
+    fn one_line_tabular() {
+        let tab = Tabular::new(&vec![Core::text("a"), Core::text("b")]);
+        assert_eq!(
+            tab.to_latex().unwrap(),
+            "\\begin{tabular}{| c | c |}\n \\hline\na & b \\\\\n \\hline\n\\end{tabular}\n"
+        );
+    }
+
+    #[test]
+    fn from_iterator() {
+        let tab = Tabular::from_rows((1..3).map(|i| (i, i * i)));
+        assert_eq!(
+            tab.to_latex().unwrap(),
+            "\\begin{tabular}{| c | c |}\n \\hline\n1 & 1 \\\\\n \\hline\n2 & 4 \\\\\n \\hline\n\\end{tabular}\n"
+        );
+    }
+
+    #[test]
+    fn ragged_rows() {
+        let tab = Tabular::new(&vec![texts(&["a"]), texts(&["b", "c", "d"])]);
+        assert_eq!(
+            tab.to_latex().unwrap(),
+            "\\begin{tabular}{| c | c | c |}\n \\hline\na &  &  \\\\\n \\hline\nb & c & d \\\\\n \\hline\n\\end{tabular}\n"
+        );
+        let mut tab = Tabular::new(&vec![texts(&["a", "b", "c"])]);
+        tab.set_columns(vec![ColumnSpec::Left, ColumnSpec::Right]);
+        match tab.to_latex() {
+            Err(Error::InvalidStructure(_)) => (),
+            _ => panic!("A row can not have more cells than columns"),
+        }
+    }
+
+    #[test]
+    fn booktabs_with_header() {
+        let mut tab = Tabular::new(&vec![texts(&["Rust", "2015"]), texts(&["C", "1972"])]);
+        tab.set_header(texts(&["Language", "Year"]));
+        tab.set_columns(vec![
+            ColumnSpec::Paragraph("3cm".to_string()),
+            ColumnSpec::Number,
+        ]);
+        tab.set_rules(RuleStyle::Booktabs);
+        tab.set_vertical_rules(false);
+        assert_eq!(
+            tab.to_latex().unwrap(),
+            "\\begin{tabular}{p{3cm} S}\n \\toprule\nLanguage & {Year} \\\\\n \\midrule\nRust & 2015 \\\\\nC & 1972 \\\\\n \\bottomrule\n\\end{tabular}\n"
+        );
+        let mut packages = Packages::new();
+        tab.add_packages(&mut packages);
+        assert!(packages.contains("booktabs"));
+        assert!(packages.contains("siunitx"));
+    }
+
+    #[test]
+    fn multicolumn_and_multirow() {
+        let mut tab = Tabular::new(&Vec::<Vec<Core>>::new());
+        tab.add_row(vec![Cell::multicolumn(
+            Core::text("Total"),
+            2,
+            ColumnSpec::Left,
+        )]);
+        tab.add_row(vec![
+            Cell::multirow(Core::text("A"), 2),
+            Cell::new(Core::text("1")),
+        ]);
+        tab.add_row(vec![Core::text("2")]);
+        assert_eq!(
+            tab.to_latex().unwrap(),
+            "\\begin{tabular}{| c | c |}\n \\hline\n\
+             \\multicolumn{2}{|l|}{Total} \\\\\n \\hline\n\
+             \\multirow{2}{*}{A} & 1 \\\\\n \\cline{2-2}\n & 2 \\\\\n \\hline\n\
+             \\end{tabular}\n"
+        );
+        let mut packages = Packages::new();
+        tab.add_packages(&mut packages);
+        assert!(packages.contains("multirow"));
+    }
+
+    #[test]
+    fn multirow_out_of_the_tabular() {
+        let mut tab = Tabular::new(&Vec::<Vec<Core>>::new());
+        tab.add_row(vec![Cell::multirow(Core::text("A"), 3)]);
+        tab.add_row(vec![Cell::empty()]);
+        assert!(tab.to_latex().is_err());
+    }
+
+    #[test]
+    fn highlighted_numbers() {
+        let row = |name: &str, time: f64, error: f64, speedup: f64| {
+            let mut time = Number::with_uncertainty(time, error);
+            time.set_decimals(2);
+            let mut speedup = Number::new(speedup);
+            speedup.set_significant(2);
+            vec![Core::text(name), Core::Num(time), Core::Num(speedup)]
+        };
+        let mut tab = Tabular::new(&vec![
+            row("seq", 1.234, 0.05, 1.0),
+            row("par", 0.4321, 0.012, 2.857),
+            row("gpu", 0.4349, 0.02, 2.84),
+        ]);
+        tab.set_header(texts(&["Version", "Time", "Speedup"]));
+        tab.set_columns(vec![ColumnSpec::Left, ColumnSpec::Number, ColumnSpec::Right]);
+        tab.set_rules(RuleStyle::Booktabs);
+        tab.set_vertical_rules(false);
+        tab.highlight_column(1, Highlight::Min);
+        tab.highlight_column(2, Highlight::Max);
+        assert_eq!(
+            tab.to_latex().unwrap(),
+            "\\begin{tabular}{l S r}\n \\toprule\n\
+             Version & {Time} & Speedup \\\\\n \\midrule\n\
+             seq & 1.23 \\pm 0.05 & 1.0 \\\\\n\
+             par & \\bfseries 0.43 \\pm 0.01 & \\textbf{2.9} \\\\\n\
+             gpu & \\bfseries 0.43 \\pm 0.02 & 2.8 \\\\\n \\bottomrule\n\
+             \\end{tabular}\n"
+        );
+        let mut packages = Packages::new();
+        tab.add_packages(&mut packages);
+        assert_eq!(
+            packages.get("siunitx").unwrap().get_options(),
+            ["detect-weight=true"]
+        );
     }
 }
@@ -1,6 +1,6 @@
 use core::*;
-use latex_file::LatexFile;
-use std::io::BufWriter;
+use error::*;
+use packages::Packages;
 use std::io::Write;
 use writable::*;
 
@@ -25,17 +25,22 @@ impl SingleTag {
     pub fn item(content: Core) -> Self {
         SingleTag::new("item".to_string(), content)
     }
+
+    /// Returns the content of the tag
+    pub fn get_content(&self) -> &Core {
+        &self.content
+    }
 }
 
 impl Writable for SingleTag {
-    fn write_latex(&self, file: &mut LatexFile) {
-        let mut writer = BufWriter::new(file);
-        self.write_to_buffer(&mut writer);
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        write!(buf, "\\{} ", self.name)?;
+        self.content.write_to_buffer(buf)?;
+        writeln!(buf)?;
+        Ok(())
     }
 
-    fn write_to_buffer(&self, mut buf: &mut BufWriter<&mut LatexFile>) {
-        write!(&mut buf, "\\{} ", self.name).unwrap();
-        self.content.write_to_buffer(&mut buf);
-        writeln!(&mut buf).unwrap();
+    fn add_packages(&self, packages: &mut Packages) {
+        self.content.add_packages(packages);
     }
 }
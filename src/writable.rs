@@ -1,23 +1,70 @@
 /// File to define the trait Writable to write in a LaTex File
+/// or in any other sink (io::Write or fmt::Write)
 ///
-
-use latex_file::LatexFile;
+use error::*;
+use packages::Packages;
+use std::fmt;
 use std::io::BufWriter;
-use std::io::Write;
-
+use std::io::{self, Write};
 
 pub trait Writable {
-    fn write_latex(&self, file: &mut LatexFile);
-    fn write_to_buffer(&self, buf: &mut BufWriter<&mut LatexFile>);
+    /// Writes the LaTeX code of the element into any io::Write
+    /// (a LatexFile, a Vec<u8>, stdout, a socket, ...)
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()>;
+
+    /// Adds the packages needed by the element (and by its content) to the registry
+    fn add_packages(&self, _packages: &mut Packages) {}
+
+    /// Writes the LaTeX code of the element through a BufWriter
+    fn write_latex<W: Write + ?Sized>(&self, file: &mut W) -> Result<()> {
+        let mut writer = BufWriter::new(file);
+        self.write_to_buffer(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Writes the LaTeX code of the element into any fmt::Write
+    /// (a String, a fmt::Formatter, ...)
+    fn write_to_fmt<W: fmt::Write + ?Sized>(&self, out: &mut W) -> Result<()> {
+        out.write_str(&self.to_latex()?)?;
+        Ok(())
+    }
+
+    /// Returns the LaTeX code of the element
+    fn to_latex(&self) -> Result<String> {
+        let mut out = Vec::new();
+        self.write_to_buffer(&mut out)?;
+        String::from_utf8(out).map_err(|e| Error::Io(io::Error::new(io::ErrorKind::InvalidData, e)))
+    }
 }
 
 impl Writable for String {
-    fn write_latex(&self, file: &mut LatexFile) {
-        let mut writer = BufWriter::new(file);
-        self.write_to_buffer(&mut writer);
+    fn write_to_buffer<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        write!(buf, "{}", self)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests_writable {
+    use super::*;
+
+    #[test]
+    fn write_into_vec() {
+        let mut out = Vec::new();
+        "Hello".to_string().write_to_buffer(&mut out).unwrap();
+        assert_eq!(out, b"Hello");
+    }
+
+    #[test]
+    fn write_into_string() {
+        let mut out = String::from("> ");
+        "Hello".to_string().write_to_fmt(&mut out).unwrap();
+        assert_eq!(out, "> Hello");
     }
 
-    fn write_to_buffer(&self, mut buf: &mut BufWriter<&mut LatexFile>) {
-        write!(&mut buf, "{}", self.to_string()).unwrap();
+    #[test]
+    fn to_latex() {
+        assert_eq!("Hello".to_string().to_latex().unwrap(), "Hello");
     }
 }